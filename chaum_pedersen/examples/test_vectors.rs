@@ -0,0 +1,41 @@
+//! Writes the known-answer test vectors of every flavor, as JSON files in the given directory.
+//!
+//! ```text
+//! cargo run -p chaum_pedersen --example test_vectors -- chaum_pedersen/tests/vectors
+//! ```
+
+use chaum_pedersen::protocol::constants::{
+    DLOG_GROUP_PARAMS, PALLAS_GROUP_PARAMS, VESTA_GROUP_PARAMS,
+};
+use chaum_pedersen::protocol::discrete_log::DiscreteLog;
+use chaum_pedersen::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+use chaum_pedersen::protocol::elliptic_curves::vesta::VestaEllipticCurve;
+use chaum_pedersen::protocol::vectors::TestVectors;
+use std::path::PathBuf;
+use std::{env, fs};
+
+const SEEDS: [&str; 4] = ["vector-0", "vector-1", "vector-2", "vector-3"];
+
+fn main() -> anyhow::Result<()> {
+    let dir = PathBuf::from(env::args().nth(1).unwrap_or_else(|| ".".to_string()));
+    let files = [
+        (
+            "discrete_log.json",
+            TestVectors::generate::<DiscreteLog>(&DLOG_GROUP_PARAMS, &SEEDS),
+        ),
+        (
+            "pallas.json",
+            TestVectors::generate::<PallasEllipticCurve>(&PALLAS_GROUP_PARAMS, &SEEDS),
+        ),
+        (
+            "vesta.json",
+            TestVectors::generate::<VestaEllipticCurve>(&VESTA_GROUP_PARAMS, &SEEDS),
+        ),
+    ];
+    for (name, vectors) in files {
+        let path = dir.join(name);
+        fs::write(&path, serde_json::to_string_pretty(&vectors)? + "\n")?;
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
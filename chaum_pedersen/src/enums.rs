@@ -1,5 +1,19 @@
+use serde::{Deserialize, Serialize};
+
 /// An enumeration representing the implemented flavors of Chaum-Pedersen protocols.
-#[derive(PartialEq, Debug, strum::EnumString, strum::EnumVariantNames, strum::Display)]
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Debug,
+    Serialize,
+    Deserialize,
+    strum::EnumString,
+    strum::EnumVariantNames,
+    strum::Display,
+)]
+#[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Flavor {
     DiscreteLog,
@@ -7,9 +21,52 @@ pub enum Flavor {
 }
 
 /// An enumeration representing the types of elliptic curves for which the protocol is implemented.
-#[derive(PartialEq, Debug, strum::EnumString, strum::EnumVariantNames, strum::Display)]
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Debug,
+    Serialize,
+    Deserialize,
+    strum::EnumString,
+    strum::EnumVariantNames,
+    strum::Display,
+)]
+#[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum EllipticCurve {
     Pallas,
     Vesta,
 }
+
+/// An enumeration representing the text encodings of keys, parameters and proofs.
+#[derive(
+    Clone, Copy, PartialEq, Eq, Debug, strum::EnumString, strum::EnumVariantNames, strum::Display,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum TextEncoding {
+    Armor,
+    Hex,
+    Base64Url,
+}
+
+/// An enumeration representing the memory-hard functions deriving secrets from passwords.
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Debug,
+    Serialize,
+    Deserialize,
+    strum::EnumString,
+    strum::EnumVariantNames,
+    strum::Display,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum KdfAlgorithm {
+    Argon2id,
+    Scrypt,
+}
@@ -0,0 +1,155 @@
+//! Text encodings of envelopes, for copying keys, parameters and proofs through tickets and config files.
+//!
+//! The binary form of an `Envelope` is followed by a 4 byte checksum, the start of its SHA-256,
+//! and written as hex, as unpadded base64url, or as a PEM-like block labelled after its content:
+//!
+//! ```text
+//! -----BEGIN CHAUM-PEDERSEN PUBLIC KEY-----
+//! Q1BaSwABAQEBAAAAIKpmfXXrE3SRB85aYcmy15UcIOmaeBxkbZrac-oBp7GcAAAA
+//! IA7iLLvgTkDECFF_DRee0jUPSYUT5HqkF0bBYFo0HEQKmtVjnQ
+//! -----END CHAUM-PEDERSEN PUBLIC KEY-----
+//! ```
+
+use crate::enums::TextEncoding;
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::secret::Secret;
+use crate::protocol::wire::{Content, Envelope};
+use anyhow::Result;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+const CHECKSUM_LEN: usize = 4;
+const LINE_LEN: usize = 64;
+
+/// The label of the armored block holding `content`.
+pub fn label(content: &Content) -> &'static str {
+    match content {
+        Content::Params { .. } => "CHAUM-PEDERSEN PARAMETERS",
+        Content::Statement { .. } => "CHAUM-PEDERSEN PUBLIC KEY",
+        Content::Secret { .. } => "CHAUM-PEDERSEN SECRET KEY",
+        Content::Commitment { .. } => "CHAUM-PEDERSEN COMMITMENT",
+        Content::InteractiveProof { .. } | Content::Proof { .. } => "CHAUM-PEDERSEN PROOF",
+    }
+}
+
+/// The intermediate copies are wiped, as the envelope may hold a secret key.
+pub fn encode(envelope: &Envelope, encoding: TextEncoding) -> String {
+    let mut bytes = Secret::new(envelope.to_binary());
+    let sum = checksum(bytes.expose());
+    bytes.expose_mut().extend_from_slice(&sum);
+    match encoding {
+        TextEncoding::Hex => hex::encode(bytes.expose()),
+        TextEncoding::Base64Url => URL_SAFE_NO_PAD.encode(bytes.expose()),
+        TextEncoding::Armor => {
+            let body = Secret::new(URL_SAFE_NO_PAD.encode(bytes.expose()));
+            let label = label(&envelope.content);
+            let mut text = format!("-----BEGIN {}-----\n", label);
+            // base64url is ASCII, so the chunks are valid UTF-8
+            for line in body.expose().as_bytes().chunks(LINE_LEN) {
+                text.push_str(std::str::from_utf8(line).unwrap_or_default());
+                text.push('\n');
+            }
+            text.push_str(&format!("-----END {}-----\n", label));
+            text
+        }
+    }
+}
+
+/// Decodes any of the text encodings, detected from the text itself.
+pub fn decode(text: &str) -> Result<Envelope> {
+    let text = text.trim();
+    if text.starts_with("-----BEGIN ") {
+        return decode_armor(text);
+    }
+    // hex digits are also base64url characters, the checksum tells the two apart
+    if let Ok(bytes) = hex::decode(text) {
+        if let Ok(envelope) = from_checked_bytes(&bytes) {
+            return Ok(envelope);
+        }
+    }
+    let bytes = URL_SAFE_NO_PAD
+        .decode(text)
+        .map_err(|_| ProtocolError::InvalidEncoding)?;
+    from_checked_bytes(&bytes)
+}
+
+fn decode_armor(text: &str) -> Result<Envelope> {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let (begin, end) = match (lines.first(), lines.last()) {
+        (Some(begin), Some(end)) if lines.len() > 2 => (*begin, *end),
+        _ => return Err(ProtocolError::InvalidEncoding.into()),
+    };
+    let label = begin
+        .strip_prefix("-----BEGIN ")
+        .and_then(|rest| rest.strip_suffix("-----"))
+        .ok_or(ProtocolError::InvalidEncoding)?;
+    if end != format!("-----END {}-----", label) {
+        return Err(ProtocolError::InvalidEncoding.into());
+    }
+    let bytes = URL_SAFE_NO_PAD
+        .decode(lines[1..lines.len() - 1].concat())
+        .map_err(|_| ProtocolError::InvalidEncoding)?;
+    let envelope = from_checked_bytes(&bytes)?;
+    if self::label(&envelope.content) != label {
+        return Err(ProtocolError::UnexpectedContent.into());
+    }
+    Ok(envelope)
+}
+
+fn from_checked_bytes(bytes: &[u8]) -> Result<Envelope> {
+    if bytes.len() < CHECKSUM_LEN {
+        return Err(ProtocolError::TruncatedBytes.into());
+    }
+    let (payload, sum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+    if checksum(payload) != sum {
+        return Err(ProtocolError::InvalidChecksum.into());
+    }
+    Envelope::from_binary(payload)
+}
+
+fn checksum(bytes: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let digest = Sha256::digest(bytes);
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::constants::PALLAS_GROUP_PARAMS;
+    use crate::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+
+    #[test]
+    fn armor_detects_alterations() {
+        let params = &PALLAS_GROUP_PARAMS;
+        let envelope = Envelope::statement::<PallasEllipticCurve>(&params.g, &params.h);
+
+        let text = encode(&envelope, TextEncoding::Armor);
+        assert!(text.starts_with("-----BEGIN CHAUM-PEDERSEN PUBLIC KEY-----\n"));
+        assert!(text.ends_with("-----END CHAUM-PEDERSEN PUBLIC KEY-----\n"));
+        // surrounding whitespace is ignored
+        assert_eq!(decode(&format!("\n  {}  \n", text)).unwrap(), envelope);
+
+        // a block relabelled as another kind of artefact is rejected
+        let relabelled = text.replace("PUBLIC KEY", "SECRET KEY");
+        assert!(decode(&relabelled).is_err());
+
+        for encoding in [
+            TextEncoding::Armor,
+            TextEncoding::Hex,
+            TextEncoding::Base64Url,
+        ] {
+            let text = encode(&envelope, encoding);
+            // flip one character of the body
+            let position = text.len() / 2;
+            let flipped = if &text[position..position + 1] == "a" {
+                "b"
+            } else {
+                "a"
+            };
+            let altered = format!("{}{}{}", &text[..position], flipped, &text[position + 1..]);
+            assert!(decode(&altered).is_err());
+        }
+        assert!(decode("not an encoding").is_err());
+    }
+}
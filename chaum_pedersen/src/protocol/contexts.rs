@@ -0,0 +1,36 @@
+//! Transcripts binding the signatures and proofs of the authentication service to it, shared by
+//! its server and its client.
+//!
+//! Each holds the name of the user and a one-time nonce issued by the server, so a signed request
+//! is only accepted once, for that user, by that service, even after the user registers again.
+
+use crate::protocol::transcript::Transcript;
+
+/// Label of the signatures of actions.
+pub const ACTION_CONTEXT: &[u8] = b"zkp-auth-signed-action";
+/// Label of the proofs of migrations to another group.
+pub const MIGRATION_CONTEXT: &[u8] = b"zkp-auth-migration";
+/// Label of the proofs of re-keying to another group.
+pub const REKEY_CONTEXT: &[u8] = b"zkp-auth-rekey";
+
+/// The context of an action signed by `user` under the server's `nonce`.
+pub fn action(user: &str, nonce: &str) -> Transcript {
+    bound(ACTION_CONTEXT, user, nonce)
+}
+
+/// The context of the migration of `user` under the server's `nonce`.
+pub fn migration(user: &str, nonce: &str) -> Transcript {
+    bound(MIGRATION_CONTEXT, user, nonce)
+}
+
+/// The context of `user` re-keying to another group under the server's `nonce`.
+pub fn rekey(user: &str, nonce: &str) -> Transcript {
+    bound(REKEY_CONTEXT, user, nonce)
+}
+
+fn bound(label: &[u8], user: &str, nonce: &str) -> Transcript {
+    let mut transcript = Transcript::new(label);
+    transcript.append_message(b"user", user.as_bytes());
+    transcript.append_message(b"nonce", nonce.as_bytes());
+    transcript
+}
@@ -0,0 +1,399 @@
+//! Proofs that the same secret `x` is behind keys of two different groups, `y1 = g1^x` and `y2 = g2^x`,
+//! for instance the Pallas and Vesta keys of a user.
+//!
+//! The scalar fields differ, so `x` is decomposed into `n` bits, `n` being small enough for `x`
+//! to be below both group orders. The prover commits to every bit `bi` in both groups as
+//! `Ci = g1^bi · h1^ri` and `Di = g2^bi · h2^si`, with `Σ 2^i·ri = Σ 2^i·si = 0`, so that
+//! `Π Ci^(2^i) = y1` and `Π Di^(2^i) = y2`. A ring proof per bit shows that `Ci` and `Di` both
+//! commit to `0` or both to `1`, its challenges being short enough to be scalars of both groups.
+
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+
+/// Byte length of the challenges, which must be below both group orders.
+pub const CHALLENGE_BYTES: usize = 16;
+
+type Challenge = [u8; CHALLENGE_BYTES];
+
+/// The proof that the commitments to one bit hide the same bit in both groups.
+///
+/// Branch `j` of the ring proves knowledge of `ri` and `si` with `Ci / g1^j = h1^ri`
+/// and `Di / g2^j = h2^si`, the challenges of both branches XOR to the Fiat-Shamir challenge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitProof<G1: CyclicGroup, G2: CyclicGroup> {
+    pub c: [Challenge; 2],
+    pub s1: [G1::Scalar; 2],
+    pub s2: [G2::Scalar; 2],
+}
+
+/// A proof that `log_g1 y1` in the first group equals `log_g2 y2` in the second one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrossGroupProof<G1: CyclicGroup, G2: CyclicGroup> {
+    /// The commitments `Ci` to the bits of the secret in the first group, least significant first.
+    pub commitments1: Vec<G1::Element>,
+    /// The commitments `Di` in the second group.
+    pub commitments2: Vec<G2::Element>,
+    pub bits: Vec<BitProof<G1, G2>>,
+}
+
+impl<G1: CyclicGroup, G2: CyclicGroup> CrossGroupProof<G1, G2> {
+    /// The number of bits of the secrets supported by both groups.
+    pub fn bit_length(
+        params1: &GroupParams<G1::Element>,
+        params2: &GroupParams<G2::Element>,
+    ) -> usize {
+        G1::order_bits(params1).min(G2::order_bits(params2)) - 1
+    }
+
+    /// The scalar of the second group with the same value as `x`, if `x` is below `2^bit_length`.
+    pub fn convert_secret(
+        params1: &GroupParams<G1::Element>,
+        params2: &GroupParams<G2::Element>,
+        x: &G1::Scalar,
+    ) -> Option<G2::Scalar> {
+        let n = Self::bit_length(params1, params2);
+        let bits = G1::scalar_bits(params1, x);
+        if bits[n..].iter().any(|bit| *bit) {
+            return None;
+        }
+        let powers = powers_of_two::<G2>(params2, n);
+        Some(
+            bits[..n]
+                .iter()
+                .zip(&powers)
+                .filter(|(bit, _)| **bit)
+                .fold(G2::scalar_zero(params2), |acc, (_, power)| {
+                    G2::scalar_add(params2, &acc, power)
+                }),
+        )
+    }
+
+    /// Proves that `x`, which has to be below `2^bit_length`, is behind `g1^x` and `g2^x`.
+    pub fn prove(
+        params1: &GroupParams<G1::Element>,
+        params2: &GroupParams<G2::Element>,
+        transcript: &mut Transcript,
+        x: &G1::Scalar,
+    ) -> Result<Self> {
+        let n = Self::bit_length(params1, params2);
+        let bits = G1::scalar_bits(params1, x);
+        if bits[n..].iter().any(|bit| *bit) {
+            return Err(ProtocolError::ValueOutOfRange.into());
+        }
+        let bits = &bits[..n];
+
+        let r = balanced_blindings::<G1>(params1, n);
+        let s = balanced_blindings::<G2>(params2, n);
+        let commitments1: Vec<G1::Element> = bits
+            .iter()
+            .zip(&r)
+            .map(|(bit, r)| bit_commitment::<G1>(params1, *bit, r))
+            .collect();
+        let commitments2: Vec<G2::Element> = bits
+            .iter()
+            .zip(&s)
+            .map(|(bit, s)| bit_commitment::<G2>(params2, *bit, s))
+            .collect();
+
+        // simulate the branch of the other bit value, commit honestly to the real one
+        let mut c_sim = Vec::with_capacity(n);
+        let mut s1_sim = Vec::with_capacity(n);
+        let mut s2_sim = Vec::with_capacity(n);
+        let mut k1 = Vec::with_capacity(n);
+        let mut k2 = Vec::with_capacity(n);
+        let mut t = Vec::with_capacity(n);
+        for i in 0..n {
+            let real = usize::from(bits[i]);
+            let c = random_challenge();
+            let sim1 = G1::random_scalar(params1);
+            let sim2 = G2::random_scalar(params2);
+            let nonce1 = G1::random_scalar(params1);
+            let nonce2 = G2::random_scalar(params2);
+
+            let mut t_bit = [
+                (G1::identity(params1), G2::identity(params2)),
+                (G1::identity(params1), G2::identity(params2)),
+            ];
+            t_bit[real] = (
+                G1::exp(params1, &params1.h, &nonce1),
+                G2::exp(params2, &params2.h, &nonce2),
+            );
+            t_bit[1 - real] = Self::branch_commitments(
+                params1,
+                params2,
+                &commitments1[i],
+                &commitments2[i],
+                1 - real,
+                &c,
+                &sim1,
+                &sim2,
+            );
+
+            c_sim.push(c);
+            s1_sim.push(sim1);
+            s2_sim.push(sim2);
+            k1.push(nonce1);
+            k2.push(nonce2);
+            t.push(t_bit);
+        }
+
+        let challenge = Self::challenge(transcript, &commitments1, &commitments2, &t);
+        let proofs = (0..n)
+            .map(|i| {
+                let real = usize::from(bits[i]);
+                let c_real = xor(&challenge, &c_sim[i]);
+                let mut c = [c_sim[i]; 2];
+                c[real] = c_real;
+
+                let mut s1 = [s1_sim[i].clone(), s1_sim[i].clone()];
+                s1[real] = G1::scalar_add(
+                    params1,
+                    &k1[i],
+                    &G1::scalar_mul(params1, &challenge_scalar::<G1>(params1, &c_real), &r[i]),
+                );
+                let mut s2 = [s2_sim[i].clone(), s2_sim[i].clone()];
+                s2[real] = G2::scalar_add(
+                    params2,
+                    &k2[i],
+                    &G2::scalar_mul(params2, &challenge_scalar::<G2>(params2, &c_real), &s[i]),
+                );
+                BitProof { c, s1, s2 }
+            })
+            .collect();
+
+        Ok(CrossGroupProof {
+            commitments1,
+            commitments2,
+            bits: proofs,
+        })
+    }
+
+    /// Verifies that the same secret is behind `y1` in the first group and `y2` in the second one.
+    pub fn verify(
+        &self,
+        params1: &GroupParams<G1::Element>,
+        params2: &GroupParams<G2::Element>,
+        transcript: &mut Transcript,
+        y1: &G1::Element,
+        y2: &G2::Element,
+    ) -> bool {
+        let n = Self::bit_length(params1, params2);
+        if self.commitments1.len() != n
+            || self.commitments2.len() != n
+            || self.bits.len() != n
+            || !self.commitments1.iter().all(|c| G1::contains(params1, c))
+            || !self.commitments2.iter().all(|d| G2::contains(params2, d))
+        {
+            return false;
+        }
+        if G1::multi_exp(
+            params1,
+            &self.commitments1,
+            &powers_of_two::<G1>(params1, n),
+        ) != *y1
+            || G2::multi_exp(
+                params2,
+                &self.commitments2,
+                &powers_of_two::<G2>(params2, n),
+            ) != *y2
+        {
+            return false;
+        }
+
+        let t: Vec<_> = (0..n)
+            .map(|i| {
+                let proof = &self.bits[i];
+                [0, 1].map(|j| {
+                    Self::branch_commitments(
+                        params1,
+                        params2,
+                        &self.commitments1[i],
+                        &self.commitments2[i],
+                        j,
+                        &proof.c[j],
+                        &proof.s1[j],
+                        &proof.s2[j],
+                    )
+                })
+            })
+            .collect();
+        let challenge = Self::challenge(transcript, &self.commitments1, &self.commitments2, &t);
+        self.bits
+            .iter()
+            .all(|proof| xor(&proof.c[0], &proof.c[1]) == challenge)
+    }
+
+    /// The commitments `h1^s1 · (Ci / g1^j)^-c` and `h2^s2 · (Di / g2^j)^-c` of branch `j`.
+    #[allow(clippy::too_many_arguments)]
+    fn branch_commitments(
+        params1: &GroupParams<G1::Element>,
+        params2: &GroupParams<G2::Element>,
+        commitment1: &G1::Element,
+        commitment2: &G2::Element,
+        j: usize,
+        c: &Challenge,
+        s1: &G1::Scalar,
+        s2: &G2::Scalar,
+    ) -> (G1::Element, G2::Element) {
+        let mut v1 = commitment1.clone();
+        let mut v2 = commitment2.clone();
+        if j == 1 {
+            v1 = G1::op(params1, &v1, &G1::inverse(params1, &params1.g));
+            v2 = G2::op(params2, &v2, &G2::inverse(params2, &params2.g));
+        }
+        let minus_c1 = G1::scalar_neg(params1, &challenge_scalar::<G1>(params1, c));
+        let minus_c2 = G2::scalar_neg(params2, &challenge_scalar::<G2>(params2, c));
+        (
+            G1::multi_exp(params1, &[params1.h.clone(), v1], &[s1.clone(), minus_c1]),
+            G2::multi_exp(params2, &[params2.h.clone(), v2], &[s2.clone(), minus_c2]),
+        )
+    }
+
+    fn challenge(
+        transcript: &mut Transcript,
+        commitments1: &[G1::Element],
+        commitments2: &[G2::Element],
+        t: &[[(G1::Element, G2::Element); 2]],
+    ) -> Challenge {
+        transcript.append_message(b"protocol", b"cross-group-dleq");
+        for ((c, d), t_bit) in commitments1.iter().zip(commitments2).zip(t) {
+            transcript.append_element::<G1>(b"c", c);
+            transcript.append_element::<G2>(b"d", d);
+            for (t1, t2) in t_bit {
+                transcript.append_element::<G1>(b"t1", t1);
+                transcript.append_element::<G2>(b"t2", t2);
+            }
+        }
+        let mut challenge = [0u8; CHALLENGE_BYTES];
+        challenge.copy_from_slice(&transcript.challenge_bytes(b"c")[..CHALLENGE_BYTES]);
+        challenge
+    }
+}
+
+/// `g^bit · h^r`.
+fn bit_commitment<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    bit: bool,
+    r: &G::Scalar,
+) -> G::Element {
+    G::multi_exp(
+        params,
+        &[params.g.clone(), params.h.clone()],
+        &[G::scalar_from_u64(params, bit as u64), r.clone()],
+    )
+}
+
+/// Random blinding factors `ri` with `Σ 2^i·ri = 0`.
+fn balanced_blindings<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    n: usize,
+) -> Vec<G::Scalar> {
+    let powers = powers_of_two::<G>(params, n);
+    let mut r: Vec<G::Scalar> = (1..n).map(|_| G::random_scalar(params)).collect();
+    let rest = r
+        .iter()
+        .zip(&powers[1..])
+        .fold(G::scalar_zero(params), |acc, (ri, power)| {
+            G::scalar_add(params, &acc, &G::scalar_mul(params, ri, power))
+        });
+    r.insert(0, G::scalar_neg(params, &rest));
+    r
+}
+
+fn powers_of_two<G: CyclicGroup>(params: &GroupParams<G::Element>, n: usize) -> Vec<G::Scalar> {
+    let two = G::scalar_from_u64(params, 2);
+    let mut powers = vec![G::scalar_from_u64(params, 1)];
+    for i in 1..n {
+        powers.push(G::scalar_mul(params, &powers[i - 1], &two));
+    }
+    powers
+}
+
+fn random_challenge() -> Challenge {
+    use rand::RngCore;
+    let mut c = [0u8; CHALLENGE_BYTES];
+    rand::rngs::OsRng.fill_bytes(&mut c);
+    c
+}
+
+fn xor(a: &Challenge, b: &Challenge) -> Challenge {
+    let mut out = [0u8; CHALLENGE_BYTES];
+    for i in 0..CHALLENGE_BYTES {
+        out[i] = a[i] ^ b[i];
+    }
+    out
+}
+
+/// Interprets a challenge as a big-endian integer, which is below the group order.
+fn challenge_scalar<G: CyclicGroup>(params: &GroupParams<G::Element>, c: &Challenge) -> G::Scalar {
+    let base = G::scalar_from_u64(params, 256);
+    c.iter().fold(G::scalar_zero(params), |acc, byte| {
+        G::scalar_add(
+            params,
+            &G::scalar_mul(params, &acc, &base),
+            &G::scalar_from_u64(params, *byte as u64),
+        )
+    })
+}
+
+impl<G1: CyclicGroup, G2: CyclicGroup> IntoBytes<BitProof<G1, G2>> for BitProof<G1, G2> {
+    fn to(t: &BitProof<G1, G2>) -> Vec<u8> {
+        let mut out = Vec::new();
+        t.c.iter().for_each(|c| encoding::put_bytes(&mut out, c));
+        t.s1.iter().for_each(|s| encoding::put(&mut out, s));
+        t.s2.iter().for_each(|s| encoding::put(&mut out, s));
+        out
+    }
+}
+
+impl<G1: CyclicGroup, G2: CyclicGroup> FromBytes<BitProof<G1, G2>> for BitProof<G1, G2> {
+    fn from(bytes: &[u8]) -> Result<BitProof<G1, G2>> {
+        let mut reader = Reader::new(bytes);
+        let mut take_challenge = || -> Result<Challenge> {
+            Ok(reader
+                .take_bytes()?
+                .try_into()
+                .map_err(|_| ProtocolError::TruncatedBytes)?)
+        };
+        let c = [take_challenge()?, take_challenge()?];
+        let proof = BitProof {
+            c,
+            s1: [reader.take()?, reader.take()?],
+            s2: [reader.take()?, reader.take()?],
+        };
+        reader.finish()?;
+        Ok(proof)
+    }
+}
+
+impl<G1: CyclicGroup, G2: CyclicGroup> IntoBytes<CrossGroupProof<G1, G2>>
+    for CrossGroupProof<G1, G2>
+{
+    fn to(t: &CrossGroupProof<G1, G2>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put_all(&mut out, &t.commitments1);
+        encoding::put_all(&mut out, &t.commitments2);
+        encoding::put_all(&mut out, &t.bits);
+        out
+    }
+}
+
+impl<G1: CyclicGroup, G2: CyclicGroup> FromBytes<CrossGroupProof<G1, G2>>
+    for CrossGroupProof<G1, G2>
+{
+    fn from(bytes: &[u8]) -> Result<CrossGroupProof<G1, G2>> {
+        let mut reader = Reader::new(bytes);
+        let proof = CrossGroupProof {
+            commitments1: reader.take_all()?,
+            commitments2: reader.take_all()?,
+            bits: reader.take_all()?,
+        };
+        reader.finish()?;
+        Ok(proof)
+    }
+}
@@ -0,0 +1,109 @@
+//! Designated-verifier Chaum-Pedersen proofs.
+//!
+//! The prover shows "I know `x` with `y1 = g1^x` and `y2 = g2^x`, or I know the secret key behind
+//! the verifier's `yv = g^xv`" with an OR proof. The designated verifier knows it did not produce the
+//! proof itself and is convinced, while anybody else has to consider that the verifier simulated it
+//! with its own key. A proof thus can't be shown around, which gives the prover deniability.
+
+use crate::protocol::dleq::DleqStatement;
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::or_proof::{OrProof, Statement};
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+
+/// Branch of the OR proof holding the DLEQ statement.
+const PROVER_BRANCH: usize = 0;
+/// Branch of the OR proof holding the verifier's key.
+const VERIFIER_BRANCH: usize = 1;
+
+/// A DLEQ proof only convincing the owner of `verifier_key`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DesignatedProof<G: CyclicGroup> {
+    pub proof: OrProof<G>,
+}
+
+impl<G: CyclicGroup> DesignatedProof<G> {
+    /// Proves the DLEQ statement with its witness `x` to the owner of `verifier_key`.
+    pub fn prove(
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        statement: &DleqStatement<G>,
+        verifier_key: &G::Element,
+        x: &G::Scalar,
+    ) -> Result<Self> {
+        let statements = Self::statements(params, statement, verifier_key);
+        let proof = OrProof::prove(params, transcript, &statements, PROVER_BRANCH, x)?;
+        Ok(DesignatedProof { proof })
+    }
+
+    /// Produces a proof of any DLEQ statement, true or not, with the verifier's secret key.
+    ///
+    /// Such proofs can't be told apart from the prover's ones, which is what makes them non-transferable.
+    pub fn simulate(
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        statement: &DleqStatement<G>,
+        verifier_secret: &G::Scalar,
+    ) -> Result<Self> {
+        let verifier_key = G::exp(params, &params.g, verifier_secret);
+        let statements = Self::statements(params, statement, &verifier_key);
+        let proof = OrProof::prove(
+            params,
+            transcript,
+            &statements,
+            VERIFIER_BRANCH,
+            verifier_secret,
+        )?;
+        Ok(DesignatedProof { proof })
+    }
+
+    pub fn verify(
+        &self,
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        statement: &DleqStatement<G>,
+        verifier_key: &G::Element,
+    ) -> bool {
+        let statements = Self::statements(params, statement, verifier_key);
+        self.proof.verify(params, transcript, &statements)
+    }
+
+    fn statements(
+        params: &GroupParams<G::Element>,
+        statement: &DleqStatement<G>,
+        verifier_key: &G::Element,
+    ) -> Vec<Statement<G>> {
+        vec![
+            Statement {
+                bases: vec![statement.g1.clone(), statement.g2.clone()],
+                values: vec![statement.y1.clone(), statement.y2.clone()],
+            },
+            Statement {
+                bases: vec![params.g.clone()],
+                values: vec![verifier_key.clone()],
+            },
+        ]
+    }
+}
+
+impl<G: CyclicGroup> IntoBytes<DesignatedProof<G>> for DesignatedProof<G> {
+    fn to(t: &DesignatedProof<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put(&mut out, &t.proof);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<DesignatedProof<G>> for DesignatedProof<G> {
+    fn from(bytes: &[u8]) -> Result<DesignatedProof<G>> {
+        let mut reader = Reader::new(bytes);
+        let proof = DesignatedProof {
+            proof: reader.take()?,
+        };
+        reader.finish()?;
+        Ok(proof)
+    }
+}
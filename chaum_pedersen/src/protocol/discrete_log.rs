@@ -1,17 +1,21 @@
+use crate::enums::{EllipticCurve, Flavor};
+use crate::protocol::group::{scalar_transcript, CyclicGroup};
+use crate::protocol::secret::{Nonce, Secret};
+use crate::protocol::transcript::Transcript;
 use crate::protocol::{GroupParams, Protocol};
 use crate::traits::Random;
 use crate::traits::{FromBytes, IntoBytes};
 use anyhow::Result;
 use num_bigint::{BigUint, RandBigInt};
-use num_traits::One;
+use num_traits::{One, Zero};
 use rand::rngs::OsRng;
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct DiscreteLog {}
 
 impl Protocol for DiscreteLog {
-    type Secret = BigUint;
-    type CommitmentRandom = BigUint;
+    type Secret = Secret<BigUint>;
+    type CommitmentRandom = Nonce<BigUint>;
     type Response = BigUint;
     type Challenge = BigUint;
     type GroupParameters = GroupParams<BigUint>;
@@ -26,12 +30,12 @@ impl Protocol for DiscreteLog {
     where
         Self: Sized,
     {
-        let y1 = params.g.modpow(x, &params.p);
-        let y2 = params.h.modpow(x, &params.p);
+        let y1 = params.g.modpow(x.expose(), &params.p);
+        let y2 = params.h.modpow(x.expose(), &params.p);
         let mut rng = OsRng;
-        let k = rng.gen_biguint_below(&params.p);
-        let r1 = params.g.modpow(&k, &params.p);
-        let r2 = params.h.modpow(&k, &params.p);
+        let k = Nonce::new(rng.gen_biguint_below(&params.p));
+        let r1 = params.g.modpow(k.expose(), &params.p);
+        let r2 = params.h.modpow(k.expose(), &params.p);
         ((y1, y2, r1, r2), k)
     }
 
@@ -51,6 +55,7 @@ impl Protocol for DiscreteLog {
     where
         Self: Sized,
     {
+        let (k, x) = (k.expose(), x.expose());
         if k >= &(c * x) {
             (k - c * x).modpow(&BigUint::one(), &params.q)
         } else {
@@ -76,6 +81,168 @@ impl Protocol for DiscreteLog {
     }
 }
 
+impl CyclicGroup for DiscreteLog {
+    type Element = BigUint;
+    type Scalar = BigUint;
+    const FLAVOR: Flavor = Flavor::DiscreteLog;
+    const CURVE: Option<EllipticCurve> = None;
+
+    fn identity(_: &GroupParams<BigUint>) -> BigUint {
+        BigUint::one()
+    }
+
+    fn op(params: &GroupParams<BigUint>, a: &BigUint, b: &BigUint) -> BigUint {
+        (a * b) % &params.p
+    }
+
+    fn inverse(params: &GroupParams<BigUint>, a: &BigUint) -> BigUint {
+        // `p` is prime, hence by Fermat's little theorem a^(p-2) = a^-1
+        a.modpow(&(&params.p - <BigUint as From<u32>>::from(2u32)), &params.p)
+    }
+
+    fn exp(params: &GroupParams<BigUint>, base: &BigUint, e: &BigUint) -> BigUint {
+        base.modpow(e, &params.p)
+    }
+
+    fn contains(params: &GroupParams<BigUint>, a: &BigUint) -> bool {
+        !a.is_zero() && a < &params.p && a.modpow(&params.q, &params.p).is_one()
+    }
+
+    fn scalar_from_u64(params: &GroupParams<BigUint>, v: u64) -> BigUint {
+        <BigUint as From<u64>>::from(v) % &params.q
+    }
+
+    fn scalar_add(params: &GroupParams<BigUint>, a: &BigUint, b: &BigUint) -> BigUint {
+        (a + b) % &params.q
+    }
+
+    fn scalar_sub(params: &GroupParams<BigUint>, a: &BigUint, b: &BigUint) -> BigUint {
+        let (a, b) = (a % &params.q, b % &params.q);
+        if a >= b {
+            a - b
+        } else {
+            &params.q - (b - a)
+        }
+    }
+
+    fn scalar_mul(params: &GroupParams<BigUint>, a: &BigUint, b: &BigUint) -> BigUint {
+        (a * b) % &params.q
+    }
+
+    fn scalar_invert(params: &GroupParams<BigUint>, a: &BigUint) -> Option<BigUint> {
+        let a = a % &params.q;
+        if a.is_zero() {
+            return None;
+        }
+        // `q` is prime as well
+        Some(a.modpow(&(&params.q - <BigUint as From<u32>>::from(2u32)), &params.q))
+    }
+
+    fn random_scalar(params: &GroupParams<BigUint>) -> BigUint {
+        OsRng.gen_biguint_below(&params.q)
+    }
+
+    fn scalar_from_wide_bytes(params: &GroupParams<BigUint>, bytes: &[u8; 64]) -> BigUint {
+        BigUint::from_bytes_be(bytes) % &params.q
+    }
+
+    fn hash_to_scalar(params: &GroupParams<BigUint>, domain: &str, message: &[u8]) -> BigUint {
+        let mut transcript = scalar_transcript::<Self>(domain, message);
+        // as many blocks as needed for 128 bits more than `q`, custom groups may have a large `q`
+        let blocks = (params.q.bits() as usize + 128).div_ceil(512);
+        let wide: Secret<Vec<u8>> = Secret::new(
+            (0..blocks)
+                .flat_map(|_| transcript.challenge_bytes(b"block"))
+                .collect(),
+        );
+        BigUint::from_bytes_be(wide.expose()) % &params.q
+    }
+
+    fn hash_to_element(params: &GroupParams<BigUint>, domain: &str, message: &[u8]) -> BigUint {
+        let mut transcript = Transcript::new(b"hash-to-element");
+        transcript.append_message(b"domain", domain.as_bytes());
+        transcript.append_message(b"message", message);
+        // raising to the cofactor (p - 1) / q maps into the subgroup of order q,
+        // 128 extra bits make the reduction modulo p unbiased enough
+        let cofactor = (&params.p - BigUint::one()) / &params.q;
+        let blocks = (params.p.bits() as usize + 128).div_ceil(512);
+        loop {
+            let wide: Vec<u8> = (0..blocks)
+                .flat_map(|_| transcript.challenge_bytes(b"block"))
+                .collect();
+            let element = (BigUint::from_bytes_be(&wide) % &params.p).modpow(&cofactor, &params.p);
+            if !element.is_zero() && !element.is_one() {
+                return element;
+            }
+        }
+    }
+
+    fn order_bits(params: &GroupParams<BigUint>) -> usize {
+        params.q.bits() as usize
+    }
+
+    fn scalar_bits(params: &GroupParams<BigUint>, a: &BigUint) -> Vec<bool> {
+        // scalars are not necessarily reduced, their bits beyond q would be dropped otherwise
+        let a = Secret::new(a % &params.q);
+        (0..params.q.bits()).map(|i| a.expose().bit(i)).collect()
+    }
+
+    fn is_valid_params(params: &GroupParams<BigUint>) -> bool {
+        let is_generator = |a: &BigUint| !a.is_one() && Self::contains(params, a);
+        // the cheap checks go first, the primality tests of large moduli taking a while
+        params.p.bits() >= MIN_MODULUS_BITS
+            && params.q.bits() >= MIN_ORDER_BITS
+            && ((&params.p - BigUint::one()) % &params.q).is_zero()
+            && is_generator(&params.g)
+            && is_generator(&params.h)
+            && params.g != params.h
+            && is_probable_prime(&params.q, PRIMALITY_ROUNDS)
+            && is_probable_prime(&params.p, PRIMALITY_ROUNDS)
+    }
+}
+
+/// Smallest modulus `p` valid parameters may have, in bits. The built-in group, a demonstration
+/// group of 255 bits, is below it and is not accepted as custom parameters.
+pub const MIN_MODULUS_BITS: u64 = 2048;
+/// Smallest order `q` valid parameters may have, in bits.
+pub const MIN_ORDER_BITS: u64 = 256;
+
+/// Miller-Rabin rounds of the parameter validation, a composite passing with probability below `4^-64`.
+const PRIMALITY_ROUNDS: usize = 64;
+
+/// The Miller-Rabin probabilistic primality test with `rounds` random bases.
+fn is_probable_prime(n: &BigUint, rounds: usize) -> bool {
+    for small in [2u32, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        if *n == <BigUint as From<u32>>::from(small) {
+            return true;
+        }
+        if (n % small).is_zero() {
+            return false;
+        }
+    }
+    if n.is_zero() || n.is_one() {
+        return false;
+    }
+    let two = <BigUint as From<u32>>::from(2u32);
+    let n_minus_one = n - BigUint::one();
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> s;
+    'witness: for _ in 0..rounds {
+        let mut x = OsRng.gen_biguint_range(&two, &n_minus_one).modpow(&d, n);
+        if x.is_one() || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = x.modpow(&two, n);
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
 impl IntoBytes<BigUint> for BigUint {
     fn to(t: &BigUint) -> Vec<u8> {
         t.to_bytes_be()
@@ -101,7 +268,9 @@ impl Random<BigUint> for BigUint {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::protocol::constants::DLOG_GROUP_PARAMS;
     use num_bigint::ToBigUint;
+    use num_traits::One;
 
     #[test]
     fn biguint_serialization() {
@@ -111,4 +280,29 @@ mod tests {
         // ensure reversability of (de)serialization operations
         assert_eq!(original, recovered);
     }
+
+    #[test]
+    fn scalar_bits_are_reduced() {
+        let params = &DLOG_GROUP_PARAMS;
+        let x = 5u64.to_biguint().unwrap();
+        let bits = DiscreteLog::scalar_bits(params, &(&x + &params.q));
+        assert_eq!(bits, DiscreteLog::scalar_bits(params, &x));
+        assert_eq!(bits.len(), DiscreteLog::order_bits(params));
+    }
+
+    #[test]
+    fn hash_to_scalar_covers_large_orders() {
+        // only `q` is involved, the Mersenne prime 2^521 - 1 is wider than a SHA-512 block
+        let q: BigUint = (BigUint::one() << 521) - BigUint::one();
+        let params = GroupParams {
+            q: q.clone(),
+            ..DLOG_GROUP_PARAMS.clone()
+        };
+        let scalars: Vec<BigUint> = (0..8u8)
+            .map(|i| DiscreteLog::hash_to_scalar(&params, "test", &[i]))
+            .collect();
+        assert!(scalars.iter().all(|x| *x < q));
+        assert!(scalars.iter().any(|x| x.bits() > 512));
+        assert!(DiscreteLog::hash_to_scalar(&DLOG_GROUP_PARAMS, "test", b"") < DLOG_GROUP_PARAMS.q);
+    }
 }
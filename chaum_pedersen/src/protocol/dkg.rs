@@ -0,0 +1,447 @@
+//! Pedersen's distributed key generation: `n` parties jointly produce a key `y = g^x`,
+//! shared `t`-of-`n`, without any party ever knowing `x`.
+//!
+//! 1. Every party deals a random secret `ai0` with Feldman's VSS, broadcasting the commitments
+//!    together with `h^ai0` and a DLEQ proof that it has the same discrete log as `g^ai0`,
+//!    and sends the share `fi(j)` privately to every party `j`.
+//! 2. Every party checks the shares it received and broadcasts a complaint against every dealer
+//!    whose share is invalid.
+//! 3. Accused dealers answer by broadcasting the disputed share. Dealers with an invalid dealing,
+//!    an invalid answer or no answer at all are disqualified, as are dealers that sent two
+//!    different dealings.
+//!
+//! The parties then sum the shares of the qualified dealers, the key is `y = Π g^ai0`.
+//! Together with `Π h^ai0` it forms a Chaum-Pedersen statement for the jointly held secret.
+
+use crate::protocol::dleq::{DleqProof, DleqStatement};
+use crate::protocol::elgamal::KeyPair;
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::shamir::{self, Polynomial, Share};
+use crate::protocol::threshold::{KeyShare, ThresholdPublicKey};
+use crate::protocol::transcript::Transcript;
+use crate::protocol::vss::{self, FeldmanVss};
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The broadcast part of a party's dealing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dealing<G: CyclicGroup> {
+    pub dealer: u64,
+    /// Feldman commitments to the dealer's polynomial, the first one being `g^ai0`.
+    pub vss: FeldmanVss<G>,
+    /// `h^ai0`.
+    pub h_commitment: G::Element,
+    /// Proof that `log_g g^ai0 == log_h h^ai0`.
+    pub proof: DleqProof<G>,
+}
+
+/// A broadcast complaint of `accuser` about the share it received from `dealer`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Complaint {
+    pub accuser: u64,
+    pub dealer: u64,
+}
+
+/// A dealer's answer to a complaint, revealing the disputed share.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Justification<G: CyclicGroup> {
+    pub dealer: u64,
+    pub share: Share<G>,
+}
+
+/// The result of the key generation for one party.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DkgOutput<G: CyclicGroup> {
+    /// The dealers whose contribution made it into the key.
+    pub qualified: Vec<u64>,
+    /// The threshold key, with the public key shares of every party.
+    pub public_key: ThresholdPublicKey<G>,
+    /// `h^x` for the jointly generated `x`.
+    pub y2: G::Element,
+    /// This party's share of `x`.
+    pub key_share: KeyShare<G>,
+}
+
+impl<G: CyclicGroup> DkgOutput<G> {
+    /// The Chaum-Pedersen statement `(y1, y2) = (g^x, h^x)` of the jointly generated secret.
+    pub fn statement(&self, params: &GroupParams<G::Element>) -> DleqStatement<G> {
+        DleqStatement {
+            g1: params.g.clone(),
+            y1: self.public_key.y.clone(),
+            g2: params.h.clone(),
+            y2: self.y2.clone(),
+        }
+    }
+}
+
+/// The state of one party running the key generation.
+pub struct DkgParticipant<G: CyclicGroup> {
+    index: u64,
+    t: usize,
+    n: usize,
+    polynomial: Polynomial<G>,
+    dealings: BTreeMap<u64, Dealing<G>>,
+    shares: BTreeMap<u64, Share<G>>,
+    /// Dealers that sent two different dealings.
+    equivocators: BTreeSet<u64>,
+}
+
+impl<G: CyclicGroup> DkgParticipant<G> {
+    /// Starts the party `index`, numbered from `1` to `n`, drawing its random polynomial.
+    pub fn new(params: &GroupParams<G::Element>, index: u64, t: usize, n: usize) -> Result<Self> {
+        shamir::check_threshold(t, n)?;
+        if index == 0 || index > n as u64 {
+            return Err(ProtocolError::InvalidShareIndex.into());
+        }
+        Ok(DkgParticipant {
+            index,
+            t,
+            n,
+            polynomial: Polynomial::random(params, &G::random_scalar(params), t),
+            dealings: BTreeMap::new(),
+            shares: BTreeMap::new(),
+            equivocators: BTreeSet::new(),
+        })
+    }
+
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// The dealing to broadcast to every party.
+    pub fn dealing(&self, params: &GroupParams<G::Element>, context: &Transcript) -> Dealing<G> {
+        let vss = FeldmanVss::commit(params, &self.polynomial);
+        let a0 = &self.polynomial.coefficients[0];
+        let h_commitment = G::exp(params, &params.h, a0);
+        let statement = dealing_statement(params, &vss.commitments[0], &h_commitment);
+        let proof = DleqProof::prove(
+            params,
+            &mut dealing_transcript(context, self.index),
+            &statement,
+            a0,
+        );
+        Dealing {
+            dealer: self.index,
+            vss,
+            h_commitment,
+            proof,
+        }
+    }
+
+    /// The share to send privately to `recipient`.
+    pub fn share_for(&self, params: &GroupParams<G::Element>, recipient: u64) -> Share<G> {
+        Share {
+            index: recipient,
+            value: self.polynomial.evaluate(params, recipient),
+        }
+    }
+
+    /// Records a dealing and the share it sent to this party,
+    /// returning a complaint to broadcast if the share is invalid.
+    ///
+    /// A dealing that differs from one already received from the same dealer is rejected,
+    /// and the dealer is disqualified for equivocating.
+    pub fn receive(
+        &mut self,
+        params: &GroupParams<G::Element>,
+        dealing: Dealing<G>,
+        share: Share<G>,
+    ) -> Option<Complaint> {
+        let dealer = dealing.dealer;
+        if let Some(first) = self.dealings.get(&dealer) {
+            if *first != dealing {
+                self.equivocators.insert(dealer);
+                return None;
+            }
+        }
+        let valid = share.index == self.index && dealing.vss.verify_share(params, &share);
+        self.dealings.insert(dealer, dealing);
+        if valid {
+            self.shares.insert(dealer, share);
+            None
+        } else {
+            Some(Complaint {
+                accuser: self.index,
+                dealer,
+            })
+        }
+    }
+
+    /// Answers the complaints against this party.
+    pub fn justify(
+        &self,
+        params: &GroupParams<G::Element>,
+        complaints: &[Complaint],
+    ) -> Vec<Justification<G>> {
+        complaints
+            .iter()
+            .filter(|complaint| complaint.dealer == self.index)
+            .map(|complaint| Justification {
+                dealer: self.index,
+                share: self.share_for(params, complaint.accuser),
+            })
+            .collect()
+    }
+
+    /// Determines the qualified dealers from the public broadcasts and derives this party's output.
+    pub fn finalize(
+        &self,
+        params: &GroupParams<G::Element>,
+        context: &Transcript,
+        complaints: &[Complaint],
+        justifications: &[Justification<G>],
+    ) -> Result<DkgOutput<G>> {
+        let mut shares = self.shares.clone();
+        let mut disqualified = self.equivocators.clone();
+
+        for (dealer, dealing) in &self.dealings {
+            if !self.is_valid_dealing(params, context, dealing) {
+                disqualified.insert(*dealer);
+            }
+        }
+        for complaint in complaints {
+            let Some(dealing) = self.dealings.get(&complaint.dealer) else {
+                continue;
+            };
+            let answer = justifications.iter().find(|justification| {
+                justification.dealer == complaint.dealer
+                    && justification.share.index == complaint.accuser
+                    && dealing.vss.verify_share(params, &justification.share)
+            });
+            match answer {
+                Some(justification) if complaint.accuser == self.index => {
+                    shares.insert(complaint.dealer, justification.share.clone());
+                }
+                Some(_) => {}
+                None => {
+                    disqualified.insert(complaint.dealer);
+                }
+            }
+        }
+
+        let qualified: Vec<u64> = self
+            .dealings
+            .keys()
+            .filter(|dealer| !disqualified.contains(dealer))
+            .cloned()
+            .collect();
+        if qualified.len() < self.t {
+            return Err(ProtocolError::NotEnoughShares.into());
+        }
+
+        let mut x = G::scalar_zero(params);
+        let mut y = G::identity(params);
+        let mut y2 = G::identity(params);
+        for dealer in &qualified {
+            let share = shares.get(dealer).ok_or(ProtocolError::NotEnoughShares)?;
+            let dealing = &self.dealings[dealer];
+            x = G::scalar_add(params, &x, &share.value);
+            y = G::op(params, &y, &dealing.vss.commitments[0]);
+            y2 = G::op(params, &y2, &dealing.h_commitment);
+        }
+
+        let public_shares = (1..=self.n as u64)
+            .map(|index| {
+                qualified.iter().fold(G::identity(params), |acc, dealer| {
+                    let commitments = &self.dealings[dealer].vss.commitments;
+                    G::op(
+                        params,
+                        &acc,
+                        &vss::evaluate_in_exponent::<G>(params, commitments, index),
+                    )
+                })
+            })
+            .collect();
+
+        Ok(DkgOutput {
+            qualified,
+            public_key: ThresholdPublicKey {
+                t: self.t,
+                y,
+                shares: public_shares,
+            },
+            y2,
+            key_share: KeyShare {
+                index: self.index,
+                keys: KeyPair::from_secret(params, x),
+            },
+        })
+    }
+
+    fn is_valid_dealing(
+        &self,
+        params: &GroupParams<G::Element>,
+        context: &Transcript,
+        dealing: &Dealing<G>,
+    ) -> bool {
+        dealing.vss.threshold() == self.t
+            && dealing.dealer != 0
+            && dealing.dealer <= self.n as u64
+            && dealing.proof.verify(
+                params,
+                &mut dealing_transcript(context, dealing.dealer),
+                &dealing_statement(params, &dealing.vss.commitments[0], &dealing.h_commitment),
+            )
+    }
+}
+
+/// Misbehaviour injected into `simulate`.
+#[derive(Clone, Debug, Default)]
+pub struct Faults {
+    /// `(dealer, recipient)` pairs for which the dealer sends a corrupted share.
+    pub corrupted_shares: Vec<(u64, u64)>,
+    /// Dealers that do not answer the complaints against them.
+    pub silent_dealers: Vec<u64>,
+    /// Dealers that send every party a second dealing of another polynomial.
+    pub equivocating_dealers: Vec<u64>,
+}
+
+/// Runs the key generation between `n` in-process parties, returning the output of every party.
+pub fn simulate<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    context: &Transcript,
+    t: usize,
+    n: usize,
+    faults: &Faults,
+) -> Result<Vec<DkgOutput<G>>> {
+    let mut parties = (1..=n as u64)
+        .map(|index| DkgParticipant::<G>::new(params, index, t, n))
+        .collect::<Result<Vec<_>>>()?;
+
+    let dealings: Vec<Dealing<G>> = parties
+        .iter()
+        .map(|party| party.dealing(params, context))
+        .collect();
+
+    let mut complaints = Vec::new();
+    for dealing in &dealings {
+        let dealer = &parties[(dealing.dealer - 1) as usize];
+        let shares: Vec<Share<G>> = (1..=n as u64)
+            .map(|recipient| {
+                let mut share = dealer.share_for(params, recipient);
+                if faults
+                    .corrupted_shares
+                    .contains(&(dealing.dealer, recipient))
+                {
+                    share.value = G::random_scalar(params);
+                }
+                share
+            })
+            .collect();
+        for (party, share) in parties.iter_mut().zip(shares) {
+            complaints.extend(party.receive(params, dealing.clone(), share));
+        }
+        if faults.equivocating_dealers.contains(&dealing.dealer) {
+            let other = DkgParticipant::<G>::new(params, dealing.dealer, t, n)?;
+            for party in parties.iter_mut() {
+                let share = other.share_for(params, party.index);
+                complaints.extend(party.receive(params, other.dealing(params, context), share));
+            }
+        }
+    }
+
+    let justifications: Vec<Justification<G>> = parties
+        .iter()
+        .filter(|party| !faults.silent_dealers.contains(&party.index))
+        .flat_map(|party| party.justify(params, &complaints))
+        .collect();
+
+    parties
+        .iter()
+        .map(|party| party.finalize(params, context, &complaints, &justifications))
+        .collect()
+}
+
+fn dealing_statement<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    g_commitment: &G::Element,
+    h_commitment: &G::Element,
+) -> DleqStatement<G> {
+    DleqStatement {
+        g1: params.g.clone(),
+        y1: g_commitment.clone(),
+        g2: params.h.clone(),
+        y2: h_commitment.clone(),
+    }
+}
+
+fn dealing_transcript(context: &Transcript, dealer: u64) -> Transcript {
+    let mut transcript = context.clone();
+    transcript.append_message(b"protocol", b"dkg-dealing");
+    transcript.append_message(b"dealer", &dealer.to_be_bytes());
+    transcript
+}
+
+impl<G: CyclicGroup> IntoBytes<Dealing<G>> for Dealing<G> {
+    fn to(t: &Dealing<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put_u64(&mut out, t.dealer);
+        encoding::put(&mut out, &t.vss);
+        encoding::put(&mut out, &t.h_commitment);
+        encoding::put(&mut out, &t.proof);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<Dealing<G>> for Dealing<G> {
+    fn from(bytes: &[u8]) -> Result<Dealing<G>> {
+        let mut reader = Reader::new(bytes);
+        let dealing = Dealing {
+            dealer: reader.take_u64()?,
+            vss: reader.take()?,
+            h_commitment: reader.take()?,
+            proof: reader.take()?,
+        };
+        reader.finish()?;
+        if dealing.vss.commitments.is_empty() {
+            return Err(ProtocolError::TruncatedBytes.into());
+        }
+        Ok(dealing)
+    }
+}
+
+impl IntoBytes<Complaint> for Complaint {
+    fn to(t: &Complaint) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put_u64(&mut out, t.accuser);
+        encoding::put_u64(&mut out, t.dealer);
+        out
+    }
+}
+
+impl FromBytes<Complaint> for Complaint {
+    fn from(bytes: &[u8]) -> Result<Complaint> {
+        let mut reader = Reader::new(bytes);
+        let complaint = Complaint {
+            accuser: reader.take_u64()?,
+            dealer: reader.take_u64()?,
+        };
+        reader.finish()?;
+        Ok(complaint)
+    }
+}
+
+impl<G: CyclicGroup> IntoBytes<Justification<G>> for Justification<G> {
+    fn to(t: &Justification<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put_u64(&mut out, t.dealer);
+        encoding::put(&mut out, &t.share);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<Justification<G>> for Justification<G> {
+    fn from(bytes: &[u8]) -> Result<Justification<G>> {
+        let mut reader = Reader::new(bytes);
+        let justification = Justification {
+            dealer: reader.take_u64()?,
+            share: reader.take()?,
+        };
+        reader.finish()?;
+        Ok(justification)
+    }
+}
@@ -0,0 +1,171 @@
+//! The non-interactive Chaum-Pedersen proof that `log_g1 y1 == log_g2 y2` (DLEQ).
+//!
+//! It is the Fiat-Shamir transform of the interactive `Protocol`, the challenge being
+//! derived from the transcript instead of being sent by the verifier.
+
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+
+/// The public statement of a DLEQ proof: `y1 = g1^x` and `y2 = g2^x`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DleqStatement<G: CyclicGroup> {
+    pub g1: G::Element,
+    pub y1: G::Element,
+    pub g2: G::Element,
+    pub y2: G::Element,
+}
+
+/// A proof that the discrete logs of a `DleqStatement` are equal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DleqProof<G: CyclicGroup> {
+    pub c: G::Scalar,
+    pub s: G::Scalar,
+}
+
+impl<G: CyclicGroup> DleqProof<G> {
+    /// Proves knowledge of `x` with `y1 = g1^x` and `y2 = g2^x`.
+    pub fn prove(
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        statement: &DleqStatement<G>,
+        x: &G::Scalar,
+    ) -> Self {
+        let k = G::random_scalar(params);
+        let t1 = G::exp(params, &statement.g1, &k);
+        let t2 = G::exp(params, &statement.g2, &k);
+        let c = Self::challenge(params, transcript, statement, &t1, &t2);
+        let s = G::scalar_add(params, &k, &G::scalar_mul(params, &c, x));
+        DleqProof { c, s }
+    }
+
+    pub fn verify(
+        &self,
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        statement: &DleqStatement<G>,
+    ) -> bool {
+        if !G::contains(params, &statement.y1) || !G::contains(params, &statement.y2) {
+            return false;
+        }
+        // recompute the commitments t = g^s · y^-c
+        let minus_c = G::scalar_neg(params, &self.c);
+        let t1 = G::multi_exp(
+            params,
+            &[statement.g1.clone(), statement.y1.clone()],
+            &[self.s.clone(), minus_c.clone()],
+        );
+        let t2 = G::multi_exp(
+            params,
+            &[statement.g2.clone(), statement.y2.clone()],
+            &[self.s.clone(), minus_c],
+        );
+        Self::challenge(params, transcript, statement, &t1, &t2) == self.c
+    }
+
+    fn challenge(
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        statement: &DleqStatement<G>,
+        t1: &G::Element,
+        t2: &G::Element,
+    ) -> G::Scalar {
+        transcript.append_message(b"protocol", b"dleq");
+        transcript.append_element::<G>(b"g1", &statement.g1);
+        transcript.append_element::<G>(b"y1", &statement.y1);
+        transcript.append_element::<G>(b"g2", &statement.g2);
+        transcript.append_element::<G>(b"y2", &statement.y2);
+        transcript.append_element::<G>(b"t1", t1);
+        transcript.append_element::<G>(b"t2", t2);
+        transcript.challenge_scalar::<G>(params, b"c")
+    }
+}
+
+/// Folds the statements `log_g1 y1 == log_g2[i] y2[i]`, which share `(g1, y1)`, into a single one.
+///
+/// Both sides are combined with weights derived from the transcript, `M = Π g2[i]^di` and
+/// `Z = Π y2[i]^di`, so one proof of `log_g1 y1 == log_M Z` covers the whole batch.
+pub fn batch_statement<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    transcript: &mut Transcript,
+    g1: &G::Element,
+    y1: &G::Element,
+    g2: &[G::Element],
+    y2: &[G::Element],
+) -> Result<DleqStatement<G>> {
+    if g2.len() != y2.len() {
+        return Err(ProtocolError::LengthMismatch.into());
+    }
+    if g2.is_empty() {
+        return Err(ProtocolError::EmptyBatch.into());
+    }
+    transcript.append_message(b"protocol", b"batch-dleq");
+    transcript.append_element::<G>(b"g1", g1);
+    transcript.append_element::<G>(b"y1", y1);
+    for (g, y) in g2.iter().zip(y2) {
+        transcript.append_element::<G>(b"g2", g);
+        transcript.append_element::<G>(b"y2", y);
+    }
+    let weights: Vec<G::Scalar> = g2
+        .iter()
+        .map(|_| transcript.challenge_scalar::<G>(params, b"weight"))
+        .collect();
+    Ok(DleqStatement {
+        g1: g1.clone(),
+        y1: y1.clone(),
+        g2: G::multi_exp(params, g2, &weights),
+        y2: G::multi_exp(params, y2, &weights),
+    })
+}
+
+impl<G: CyclicGroup> IntoBytes<DleqProof<G>> for DleqProof<G> {
+    fn to(t: &DleqProof<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put(&mut out, &t.c);
+        encoding::put(&mut out, &t.s);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<DleqProof<G>> for DleqProof<G> {
+    fn from(bytes: &[u8]) -> Result<DleqProof<G>> {
+        let mut reader = Reader::new(bytes);
+        let proof = DleqProof {
+            c: reader.take()?,
+            s: reader.take()?,
+        };
+        reader.finish()?;
+        Ok(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::constants::DLOG_GROUP_PARAMS;
+    use crate::protocol::discrete_log::DiscreteLog;
+
+    #[test]
+    fn dleq_proof() {
+        let params = DLOG_GROUP_PARAMS.to_owned();
+        let x = DiscreteLog::random_scalar(&params);
+        let statement = DleqStatement::<DiscreteLog> {
+            g1: params.g.clone(),
+            y1: DiscreteLog::exp(&params, &params.g, &x),
+            g2: params.h.clone(),
+            y2: DiscreteLog::exp(&params, &params.h, &x),
+        };
+        let proof = DleqProof::prove(&params, &mut Transcript::new(b"test"), &statement, &x);
+        assert!(proof.verify(&params, &mut Transcript::new(b"test"), &statement));
+
+        let other = DleqStatement {
+            y2: DiscreteLog::op(&params, &statement.y2, &params.h),
+            ..statement
+        };
+        assert!(!proof.verify(&params, &mut Transcript::new(b"test"), &other));
+    }
+}
@@ -0,0 +1,225 @@
+//! ElGamal encryption over the protocol groups, with verifiable decryption.
+//!
+//! A ciphertext of the group element `m` under the public key `y = g^x` is `(c1, c2) = (g^r, m · y^r)`.
+//! Scalars are encrypted "in the exponent" as `g^m`, which makes the scheme additively homomorphic.
+//! A decryption share `d = c1^x` comes with a Chaum-Pedersen proof that `log_g y == log_c1 d`,
+//! so anyone can check a decryption without learning the secret key.
+
+use crate::protocol::dleq::{DleqProof, DleqStatement};
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+use std::fmt;
+
+/// An ElGamal key pair, `y = g^x`.
+#[derive(Clone, PartialEq)]
+pub struct KeyPair<G: CyclicGroup> {
+    pub x: G::Scalar,
+    pub y: G::Element,
+}
+
+/// Prints the public key only, never `x`.
+impl<G: CyclicGroup> fmt::Debug for KeyPair<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPair")
+            .field("y", &self.y)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An ElGamal ciphertext `(c1, c2) = (g^r, m · y^r)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ciphertext<G: CyclicGroup> {
+    pub c1: G::Element,
+    pub c2: G::Element,
+}
+
+/// A decryption share `d = c1^x` together with its proof of correctness.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecryptionShare<G: CyclicGroup> {
+    pub d: G::Element,
+    pub proof: DleqProof<G>,
+}
+
+impl<G: CyclicGroup> KeyPair<G> {
+    pub fn generate(params: &GroupParams<G::Element>) -> Self {
+        Self::from_secret(params, G::random_scalar(params))
+    }
+
+    pub fn from_secret(params: &GroupParams<G::Element>, x: G::Scalar) -> Self {
+        let y = G::exp(params, &params.g, &x);
+        KeyPair { x, y }
+    }
+
+    pub fn decrypt(
+        &self,
+        params: &GroupParams<G::Element>,
+        ciphertext: &Ciphertext<G>,
+    ) -> G::Element {
+        let d = G::exp(params, &ciphertext.c1, &self.x);
+        ciphertext.decrypt_with(params, &d)
+    }
+
+    /// Computes the decryption share `c1^x` and proves that it matches the public key.
+    pub fn decryption_share(
+        &self,
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        ciphertext: &Ciphertext<G>,
+    ) -> DecryptionShare<G> {
+        let d = G::exp(params, &ciphertext.c1, &self.x);
+        let statement = share_statement(params, &self.y, ciphertext, &d);
+        let proof = DleqProof::prove(params, transcript, &statement, &self.x);
+        DecryptionShare { d, proof }
+    }
+}
+
+impl<G: CyclicGroup> Ciphertext<G> {
+    /// Encrypts the group element `m` under the public key `y`.
+    pub fn encrypt(params: &GroupParams<G::Element>, y: &G::Element, m: &G::Element) -> Self {
+        Self::encrypt_with(params, y, m, &G::random_scalar(params))
+    }
+
+    /// Encrypts `m` with the given randomness `r`.
+    pub fn encrypt_with(
+        params: &GroupParams<G::Element>,
+        y: &G::Element,
+        m: &G::Element,
+        r: &G::Scalar,
+    ) -> Self {
+        Ciphertext {
+            c1: G::exp(params, &params.g, r),
+            c2: G::op(params, m, &G::exp(params, y, r)),
+        }
+    }
+
+    /// Encrypts the scalar `m` in the exponent, as `g^m`.
+    pub fn encrypt_scalar(params: &GroupParams<G::Element>, y: &G::Element, m: &G::Scalar) -> Self {
+        Self::encrypt(params, y, &G::exp(params, &params.g, m))
+    }
+
+    /// Re-randomizes the ciphertext, the result decrypts to the same plaintext but is unlinkable to `self`.
+    pub fn rerandomize(&self, params: &GroupParams<G::Element>, y: &G::Element) -> Self {
+        self.add(params, &Self::encrypt(params, y, &G::identity(params)))
+    }
+
+    /// Homomorphically combines two ciphertexts, the result encrypts `m1 · m2`,
+    /// that is `g^(m1 + m2)` for scalars encrypted in the exponent.
+    pub fn add(&self, params: &GroupParams<G::Element>, other: &Self) -> Self {
+        Ciphertext {
+            c1: G::op(params, &self.c1, &other.c1),
+            c2: G::op(params, &self.c2, &other.c2),
+        }
+    }
+
+    /// Recovers the plaintext `c2 / d` from the decryption factor `d = c1^x`.
+    pub fn decrypt_with(&self, params: &GroupParams<G::Element>, d: &G::Element) -> G::Element {
+        G::op(params, &self.c2, &G::inverse(params, d))
+    }
+
+    /// Decrypts with a decryption share, after checking its proof against the public key `y`.
+    pub fn decrypt_with_share(
+        &self,
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        y: &G::Element,
+        share: &DecryptionShare<G>,
+    ) -> Option<G::Element> {
+        share
+            .verify(params, transcript, y, self)
+            .then(|| self.decrypt_with(params, &share.d))
+    }
+}
+
+impl<G: CyclicGroup> DecryptionShare<G> {
+    /// Checks that `d = c1^x` for the `x` behind the public key `y = g^x`.
+    pub fn verify(
+        &self,
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        y: &G::Element,
+        ciphertext: &Ciphertext<G>,
+    ) -> bool {
+        G::contains(params, &ciphertext.c1)
+            && self.proof.verify(
+                params,
+                transcript,
+                &share_statement(params, y, ciphertext, &self.d),
+            )
+    }
+}
+
+/// Recovers a small `m` from `g^m`, by trying every value up to `max`.
+pub fn discrete_log_bounded<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    element: &G::Element,
+    max: u64,
+) -> Option<u64> {
+    let mut candidate = G::identity(params);
+    for m in 0..=max {
+        if candidate == *element {
+            return Some(m);
+        }
+        candidate = G::op(params, &candidate, &params.g);
+    }
+    None
+}
+
+fn share_statement<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    y: &G::Element,
+    ciphertext: &Ciphertext<G>,
+    d: &G::Element,
+) -> DleqStatement<G> {
+    DleqStatement {
+        g1: params.g.clone(),
+        y1: y.clone(),
+        g2: ciphertext.c1.clone(),
+        y2: d.clone(),
+    }
+}
+
+impl<G: CyclicGroup> IntoBytes<Ciphertext<G>> for Ciphertext<G> {
+    fn to(t: &Ciphertext<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put(&mut out, &t.c1);
+        encoding::put(&mut out, &t.c2);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<Ciphertext<G>> for Ciphertext<G> {
+    fn from(bytes: &[u8]) -> Result<Ciphertext<G>> {
+        let mut reader = Reader::new(bytes);
+        let ciphertext = Ciphertext {
+            c1: reader.take()?,
+            c2: reader.take()?,
+        };
+        reader.finish()?;
+        Ok(ciphertext)
+    }
+}
+
+impl<G: CyclicGroup> IntoBytes<DecryptionShare<G>> for DecryptionShare<G> {
+    fn to(t: &DecryptionShare<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put(&mut out, &t.d);
+        encoding::put(&mut out, &t.proof);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<DecryptionShare<G>> for DecryptionShare<G> {
+    fn from(bytes: &[u8]) -> Result<DecryptionShare<G>> {
+        let mut reader = Reader::new(bytes);
+        let share = DecryptionShare {
+            d: reader.take()?,
+            proof: reader.take()?,
+        };
+        reader.finish()?;
+        Ok(share)
+    }
+}
@@ -4,4 +4,6 @@ use thiserror::Error;
 pub enum EllipticCurveError {
     #[error("Invalid bytes length for Scalar")]
     ScalarInvalidBytesLen,
+    #[error("Bytes do not encode a point of the curve")]
+    InvalidPoint,
 }
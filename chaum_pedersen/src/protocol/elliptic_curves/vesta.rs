@@ -1,7 +1,11 @@
+use crate::enums::{EllipticCurve, Flavor};
+use crate::protocol::group::{scalar_transcript, CyclicGroup};
+use crate::protocol::secret::{Nonce, Secret};
 use crate::protocol::{GroupParams, Protocol};
 use crate::traits::Random;
 use crate::traits::{FromBytes, IntoBytes};
 use anyhow::Result;
+use pasta_curves::arithmetic::CurveExt;
 use pasta_curves::group::ff::Field;
 use pasta_curves::group::ff::{FromUniformBytes, PrimeField};
 use pasta_curves::group::Group;
@@ -14,13 +18,14 @@ use rand_core::OsRng;
 
 use super::errors::EllipticCurveError;
 
+#[derive(Clone, Debug, PartialEq)]
 pub struct VestaEllipticCurve {}
 
 impl Protocol for VestaEllipticCurve {
-    type Secret = Scalar;
+    type Secret = Secret<Scalar>;
     type Response = Scalar;
     type Challenge = Scalar;
-    type CommitmentRandom = Scalar;
+    type CommitmentRandom = Nonce<Scalar>;
     type GroupParameters = GroupParams<Point>;
     type CommitParameters = (Point, Point, Point, Point);
 
@@ -31,12 +36,12 @@ impl Protocol for VestaEllipticCurve {
     where
         Self: Sized,
     {
-        let y1 = params.g * <Scalar as From<Scalar>>::from(x.clone());
-        let y2 = params.h * <Scalar as From<Scalar>>::from(x.clone());
+        let y1 = params.g * x.expose();
+        let y2 = params.h * x.expose();
         let mut rng = OsRng;
-        let k = <Scalar as Field>::random(&mut rng);
-        let r1 = params.g * k;
-        let r2 = params.h * k;
+        let k = Nonce::new(<Scalar as Field>::random(&mut rng));
+        let r1 = params.g * k.expose();
+        let r2 = params.h * k.expose();
         ((y1, y2, r1, r2), k)
     }
 
@@ -54,7 +59,7 @@ impl Protocol for VestaEllipticCurve {
     where
         Self: Sized,
     {
-        k + (c * x)
+        k.expose() + (c * x.expose())
     }
 
     fn verify(
@@ -68,6 +73,93 @@ impl Protocol for VestaEllipticCurve {
     }
 }
 
+impl CyclicGroup for VestaEllipticCurve {
+    type Element = Point;
+    type Scalar = Scalar;
+    const FLAVOR: Flavor = Flavor::EllipticCurve;
+    const CURVE: Option<EllipticCurve> = Some(EllipticCurve::Vesta);
+
+    fn identity(_: &GroupParams<Point>) -> Point {
+        Point::identity()
+    }
+
+    fn op(_: &GroupParams<Point>, a: &Point, b: &Point) -> Point {
+        a + b
+    }
+
+    fn inverse(_: &GroupParams<Point>, a: &Point) -> Point {
+        -a
+    }
+
+    fn exp(_: &GroupParams<Point>, base: &Point, e: &Scalar) -> Point {
+        base * e
+    }
+
+    fn contains(_: &GroupParams<Point>, _: &Point) -> bool {
+        // the curve has prime order and decoding already rejects points off the curve
+        true
+    }
+
+    fn scalar_from_u64(_: &GroupParams<Point>, v: u64) -> Scalar {
+        <Scalar as From<u64>>::from(v)
+    }
+
+    fn scalar_add(_: &GroupParams<Point>, a: &Scalar, b: &Scalar) -> Scalar {
+        a + b
+    }
+
+    fn scalar_sub(_: &GroupParams<Point>, a: &Scalar, b: &Scalar) -> Scalar {
+        a - b
+    }
+
+    fn scalar_mul(_: &GroupParams<Point>, a: &Scalar, b: &Scalar) -> Scalar {
+        a * b
+    }
+
+    fn scalar_invert(_: &GroupParams<Point>, a: &Scalar) -> Option<Scalar> {
+        a.invert().into()
+    }
+
+    fn random_scalar(_: &GroupParams<Point>) -> Scalar {
+        <Scalar as Field>::random(&mut OsRng)
+    }
+
+    fn scalar_from_wide_bytes(_: &GroupParams<Point>, bytes: &[u8; 64]) -> Scalar {
+        Scalar::from_uniform_bytes(bytes)
+    }
+
+    fn hash_to_scalar(_: &GroupParams<Point>, domain: &str, message: &[u8]) -> Scalar {
+        // 512 bits are 257 more than `q`
+        let wide =
+            Secret::new(scalar_transcript::<Self>(domain, message).challenge_bytes(b"block"));
+        Scalar::from_uniform_bytes(wide.expose())
+    }
+
+    fn hash_to_element(_: &GroupParams<Point>, domain: &str, message: &[u8]) -> Point {
+        Point::hash_to_curve(domain)(message)
+    }
+
+    fn order_bits(_: &GroupParams<Point>) -> usize {
+        Scalar::NUM_BITS as usize
+    }
+
+    fn scalar_bits(_: &GroupParams<Point>, a: &Scalar) -> Vec<bool> {
+        let repr = a.to_repr();
+        (0..Scalar::NUM_BITS as usize)
+            .map(|i| (repr.as_ref()[i / 8] >> (i % 8)) & 1 == 1)
+            .collect()
+    }
+
+    fn is_valid_params(params: &GroupParams<Point>) -> bool {
+        // `p` and `q` are fixed by the curve, and must be left as the identity
+        bool::from(params.p.is_identity())
+            && bool::from(params.q.is_identity())
+            && !bool::from(params.g.is_identity())
+            && !bool::from(params.h.is_identity())
+            && params.g != params.h
+    }
+}
+
 impl IntoBytes<Point> for Point {
     fn to(t: &Point) -> Vec<u8> {
         t.to_bytes().to_vec()
@@ -80,7 +172,8 @@ impl FromBytes<Point> for Point {
             .try_into()
             .map_err(|_| EllipticCurveError::ScalarInvalidBytesLen)?;
 
-        Ok(Point::from_bytes(&array).unwrap())
+        Option::from(Point::from_bytes(&array))
+            .ok_or_else(|| EllipticCurveError::InvalidPoint.into())
     }
 }
 
@@ -127,6 +220,12 @@ mod test {
         assert_eq!(original, recovered);
     }
 
+    #[test]
+    fn vesta_invalid_point_is_rejected() {
+        // the x-coordinate is not a canonical field element
+        assert!(<Point as FromBytes<Point>>::from(&[0xff; 32]).is_err());
+    }
+
     #[test]
     fn vesta_scalar_serialization() {
         let original = <Scalar as Random<Scalar>>::random().unwrap();
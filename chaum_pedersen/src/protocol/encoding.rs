@@ -0,0 +1,85 @@
+//! Length-prefixed byte encoding shared by the `IntoBytes`/`FromBytes` implementations of composite proofs.
+
+use crate::protocol::errors::ProtocolError;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+
+/// Appends `t` to `out`, prefixed with its length as a big-endian `u32`.
+pub(crate) fn put<T: IntoBytes<T>>(out: &mut Vec<u8>, t: &T) {
+    put_bytes(out, &T::to(t));
+}
+
+pub(crate) fn put_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+pub(crate) fn put_u64(out: &mut Vec<u8>, v: u64) {
+    put_bytes(out, &v.to_be_bytes());
+}
+
+/// Appends a sequence of values, prefixed with their count.
+pub(crate) fn put_all<T: IntoBytes<T>>(out: &mut Vec<u8>, ts: &[T]) {
+    out.extend_from_slice(&(ts.len() as u32).to_be_bytes());
+    ts.iter().for_each(|t| put(out, t));
+}
+
+/// Reads back values written with `put`, `put_bytes` and `put_all`.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes }
+    }
+
+    fn take_u32(&mut self) -> Result<usize> {
+        if self.bytes.len() < 4 {
+            return Err(ProtocolError::TruncatedBytes.into());
+        }
+        let (len, rest) = self.bytes.split_at(4);
+        self.bytes = rest;
+        Ok(u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize)
+    }
+
+    pub(crate) fn take_bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.take_u32()?;
+        if self.bytes.len() < len {
+            return Err(ProtocolError::TruncatedBytes.into());
+        }
+        let (chunk, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(chunk)
+    }
+
+    pub(crate) fn take_u64(&mut self) -> Result<u64> {
+        let bytes: [u8; 8] = self
+            .take_bytes()?
+            .try_into()
+            .map_err(|_| ProtocolError::TruncatedBytes)?;
+        Ok(u64::from_be_bytes(bytes))
+    }
+
+    pub(crate) fn take<T: FromBytes<T>>(&mut self) -> Result<T> {
+        T::from(self.take_bytes()?)
+    }
+
+    pub(crate) fn take_all<T: FromBytes<T>>(&mut self) -> Result<Vec<T>> {
+        let count = self.take_u32()?;
+        // every entry takes at least its 4 byte length prefix
+        if count > self.bytes.len() / 4 {
+            return Err(ProtocolError::TruncatedBytes.into());
+        }
+        (0..count).map(|_| self.take()).collect()
+    }
+
+    /// Fails if any bytes are left unread.
+    pub(crate) fn finish(self) -> Result<()> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(ProtocolError::TrailingBytes.into())
+        }
+    }
+}
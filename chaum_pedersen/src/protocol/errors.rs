@@ -0,0 +1,53 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ProtocolError {
+    #[error("Number of generators and secrets differ")]
+    LengthMismatch,
+    #[error("Committed value does not lie in the range")]
+    ValueOutOfRange,
+    #[error("Range bit length must be positive and below the bit length of the group order")]
+    InvalidRangeBits,
+    #[error("Threshold must be positive and at most the number of parties")]
+    InvalidThreshold,
+    #[error("Share indices must be positive and distinct")]
+    InvalidShareIndex,
+    #[error("Not enough valid shares")]
+    NotEnoughShares,
+    #[error("Batch must not be empty")]
+    EmptyBatch,
+    #[error("Element is the identity or lies outside the group")]
+    InvalidElement,
+    #[error("Challenge bit length must be between 1 and 64")]
+    InvalidChallengeBits,
+    #[error("More rounds than the largest soundness target needs")]
+    TooManyRounds,
+    #[error("Scalar variable is not declared in the relation")]
+    UnknownVariable,
+    #[error("Proof verification failed")]
+    InvalidProof,
+    #[error("Unexpected end of encoded bytes")]
+    TruncatedBytes,
+    #[error("Trailing bytes after decoding")]
+    TrailingBytes,
+    #[error(
+        "Group parameters do not define a large enough prime order group with distinct generators"
+    )]
+    InvalidParams,
+    #[error("Malformed encoding")]
+    InvalidEncoding,
+    #[error("Checksum mismatch, the text was altered")]
+    InvalidChecksum,
+    #[error("Unsupported wire format version")]
+    UnsupportedVersion,
+    #[error("Encoded artefact belongs to another group")]
+    GroupMismatch,
+    #[error("Encoded artefact is not of the expected kind")]
+    UnexpectedContent,
+    #[error("Test vector does not match the implementation")]
+    VectorMismatch,
+    #[error("Key derivation parameters are out of bounds or the salt is too short")]
+    InvalidKdfParams,
+    #[error("Master seed must be between 16 and 64 bytes")]
+    InvalidSeedLength,
+}
@@ -0,0 +1,168 @@
+use crate::enums::{EllipticCurve, Flavor};
+use crate::protocol::secret::Wipe;
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use std::fmt::Debug;
+
+/// Arithmetic of the prime order groups the protocols are implemented over.
+///
+/// Group elements are written multiplicatively (`g^x`), scalars are exponents modulo the group order `q`.
+/// Every operation takes the group parameters, as the discrete log backend needs `p` and `q` to reduce,
+/// while the elliptic curve backends simply ignore them.
+pub trait CyclicGroup: Clone + Debug + PartialEq {
+    type Element: Clone + PartialEq + Debug + IntoBytes<Self::Element> + FromBytes<Self::Element>;
+    type Scalar: Clone
+        + PartialEq
+        + Debug
+        + IntoBytes<Self::Scalar>
+        + FromBytes<Self::Scalar>
+        + Wipe;
+
+    /// The flavor of the group, identifying it in encoded artefacts along with `CURVE`.
+    const FLAVOR: Flavor;
+    /// The curve of an elliptic curve group.
+    const CURVE: Option<EllipticCurve>;
+
+    /// The neutral element of the group.
+    fn identity(params: &GroupParams<Self::Element>) -> Self::Element;
+
+    /// The group operation `a · b`.
+    fn op(
+        params: &GroupParams<Self::Element>,
+        a: &Self::Element,
+        b: &Self::Element,
+    ) -> Self::Element;
+
+    /// The inverse `a^-1` of a group element.
+    fn inverse(params: &GroupParams<Self::Element>, a: &Self::Element) -> Self::Element;
+
+    /// The exponentiation `base^e`.
+    fn exp(
+        params: &GroupParams<Self::Element>,
+        base: &Self::Element,
+        e: &Self::Scalar,
+    ) -> Self::Element;
+
+    /// Checks that `a` is an element of the prime order subgroup.
+    fn contains(params: &GroupParams<Self::Element>, a: &Self::Element) -> bool;
+
+    /// Embeds a small integer as a scalar.
+    fn scalar_from_u64(params: &GroupParams<Self::Element>, v: u64) -> Self::Scalar;
+
+    fn scalar_add(
+        params: &GroupParams<Self::Element>,
+        a: &Self::Scalar,
+        b: &Self::Scalar,
+    ) -> Self::Scalar;
+
+    fn scalar_sub(
+        params: &GroupParams<Self::Element>,
+        a: &Self::Scalar,
+        b: &Self::Scalar,
+    ) -> Self::Scalar;
+
+    fn scalar_mul(
+        params: &GroupParams<Self::Element>,
+        a: &Self::Scalar,
+        b: &Self::Scalar,
+    ) -> Self::Scalar;
+
+    /// The multiplicative inverse of a scalar, `None` for zero.
+    fn scalar_invert(params: &GroupParams<Self::Element>, a: &Self::Scalar)
+        -> Option<Self::Scalar>;
+
+    /// A uniformly random scalar in `[0, q)`.
+    fn random_scalar(params: &GroupParams<Self::Element>) -> Self::Scalar;
+
+    /// Reduces 64 uniform bytes modulo `q`, the bias being negligible for the implemented groups.
+    fn scalar_from_wide_bytes(
+        params: &GroupParams<Self::Element>,
+        bytes: &[u8; 64],
+    ) -> Self::Scalar;
+
+    /// Hashes `message` to a uniform scalar, `domain` separates the uses of the hash.
+    ///
+    /// The hash is separated by the flavor and curve of the group, and expanded to at least 128 bits
+    /// more than `q` before the reduction, whatever the size of `q`. Secrets derived from passwords
+    /// and seeds go through it.
+    fn hash_to_scalar(
+        params: &GroupParams<Self::Element>,
+        domain: &str,
+        message: &[u8],
+    ) -> Self::Scalar;
+
+    /// Hashes `message` to a group element whose discrete log to any generator is unknown,
+    /// `domain` separates the uses of the hash.
+    fn hash_to_element(
+        params: &GroupParams<Self::Element>,
+        domain: &str,
+        message: &[u8],
+    ) -> Self::Element;
+
+    /// The bit length of the group order `q`.
+    fn order_bits(params: &GroupParams<Self::Element>) -> usize;
+
+    /// The little-endian bits of a scalar, `order_bits` of them.
+    fn scalar_bits(params: &GroupParams<Self::Element>, a: &Self::Scalar) -> Vec<bool>;
+
+    /// Checks that the parameters define a group of prime order `q`, and that the generators
+    /// `g` and `h` are distinct elements of it other than the identity.
+    fn is_valid_params(params: &GroupParams<Self::Element>) -> bool;
+
+    /// Product of `bases[i]^exponents[i]`, extra entries of the longer slice are ignored.
+    fn multi_exp(
+        params: &GroupParams<Self::Element>,
+        bases: &[Self::Element],
+        exponents: &[Self::Scalar],
+    ) -> Self::Element {
+        bases
+            .iter()
+            .zip(exponents)
+            .fold(Self::identity(params), |acc, (base, e)| {
+                Self::op(params, &acc, &Self::exp(params, base, e))
+            })
+    }
+
+    /// The scalar `0`.
+    fn scalar_zero(params: &GroupParams<Self::Element>) -> Self::Scalar {
+        Self::scalar_from_u64(params, 0)
+    }
+
+    /// The additive inverse `-a` of a scalar.
+    fn scalar_neg(params: &GroupParams<Self::Element>, a: &Self::Scalar) -> Self::Scalar {
+        Self::scalar_sub(params, &Self::scalar_zero(params), a)
+    }
+}
+
+/// The transcript `hash_to_scalar` squeezes its bytes from, bound to the group and the domain.
+pub(crate) fn scalar_transcript<G: CyclicGroup>(domain: &str, message: &[u8]) -> Transcript {
+    let mut transcript = Transcript::new(b"hash-to-scalar");
+    transcript.append_message(b"flavor", G::FLAVOR.to_string().as_bytes());
+    if let Some(curve) = G::CURVE {
+        transcript.append_message(b"curve", curve.to_string().as_bytes());
+    }
+    transcript.append_message(b"domain", domain.as_bytes());
+    transcript.append_message(b"message", message);
+    transcript
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::constants::{DLOG_GROUP_PARAMS, PALLAS_GROUP_PARAMS, VESTA_GROUP_PARAMS};
+    use crate::protocol::discrete_log::DiscreteLog;
+    use crate::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+    use crate::protocol::elliptic_curves::vesta::VestaEllipticCurve;
+
+    #[test]
+    fn hash_to_scalar_separates_flavors() {
+        let pallas = PallasEllipticCurve::hash_to_scalar(&PALLAS_GROUP_PARAMS, "test", b"m");
+        let vesta = VestaEllipticCurve::hash_to_scalar(&VESTA_GROUP_PARAMS, "test", b"m");
+        let dlog = DiscreteLog::hash_to_scalar(&DLOG_GROUP_PARAMS, "test", b"m");
+        let pallas = <PallasEllipticCurve as CyclicGroup>::Scalar::to(&pallas);
+        let vesta = <VestaEllipticCurve as CyclicGroup>::Scalar::to(&vesta);
+        assert_ne!(pallas, vesta);
+        assert_ne!(pallas, dlog.to_bytes_le());
+    }
+}
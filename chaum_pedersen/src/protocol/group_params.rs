@@ -0,0 +1,61 @@
+//! Custom group parameters, letting deployments use their own groups without recompiling.
+//!
+//! Parameters are read from any encoding of a parameters `Envelope`: its JSON form, or one of
+//! the text encodings of `armor`. They are validated before use, and identified by a fingerprint
+//! that operators can compare across servers and clients.
+
+use crate::protocol::armor;
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::wire::Envelope;
+use crate::protocol::GroupParams;
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+/// Decodes and validates parameters for the group `G`.
+pub fn decode<G: CyclicGroup>(text: &str) -> Result<GroupParams<G::Element>> {
+    let text = text.trim();
+    let envelope = if text.starts_with('{') {
+        Envelope::from_json(text)?
+    } else {
+        armor::decode(text)?
+    };
+    let params = envelope.to_params::<G>()?;
+    if !G::is_valid_params(&params) {
+        return Err(ProtocolError::InvalidParams.into());
+    }
+    Ok(params)
+}
+
+/// The parameters stored in the file at `path` if one is given, and `default` otherwise.
+pub fn load<G: CyclicGroup>(
+    path: Option<&Path>,
+    default: &GroupParams<G::Element>,
+) -> Result<GroupParams<G::Element>> {
+    match path {
+        Some(path) => {
+            let text = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read group parameters {}", path.display()))?;
+            decode::<G>(&text)
+                .with_context(|| format!("Invalid group parameters in {}", path.display()))
+        }
+        None => Ok(default.clone()),
+    }
+}
+
+/// The fingerprint of the parameters and where they were loaded from, for operators to compare.
+pub fn describe<G: CyclicGroup>(params: &GroupParams<G::Element>, path: Option<&Path>) -> String {
+    format!(
+        "{} ({})",
+        fingerprint::<G>(params),
+        path.map_or("built-in".to_string(), |path| path.display().to_string())
+    )
+}
+
+/// The start of the SHA-256 of the encoded parameters, flavor and curve included.
+pub fn fingerprint<G: CyclicGroup>(params: &GroupParams<G::Element>) -> String {
+    let digest = Sha256::digest(Envelope::params::<G>(params).to_binary());
+    hex::encode(&digest[..16])
+}
@@ -0,0 +1,151 @@
+//! Hierarchical deterministic secrets: one master seed derives a secret for every server, username
+//! and flavor along a labelled path, such as
+//!
+//! ```text
+//! m/server=example.org:50051/user=peggy/flavor=elliptic_curve/curve=pallas
+//! ```
+//!
+//! Every node of the tree is 64 bytes hashed from its parent and the label and value of the step,
+//! so a node gives away the secrets below it only, and nothing about its parent or its siblings.
+//! The secret of a node is its bytes hashed to a scalar of the group with `hash_to_scalar`.
+
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::secret::Secret;
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use anyhow::Result;
+use rand_core::{OsRng, RngCore};
+use std::fmt;
+
+/// Length of generated master seeds.
+pub const SEED_LEN: usize = 32;
+/// Shortest master seed accepted.
+pub const MIN_SEED_LEN: usize = 16;
+/// Longest master seed accepted.
+pub const MAX_SEED_LEN: usize = 64;
+/// Domain of `hash_to_scalar` for the secrets of the nodes.
+pub const HD_DOMAIN: &str = "hd-secret";
+
+/// One step of a derivation path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathStep {
+    pub label: String,
+    pub value: String,
+}
+
+/// The steps from the master seed to a node.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DerivationPath {
+    steps: Vec<PathStep>,
+}
+
+impl DerivationPath {
+    /// The path of the master seed itself.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The path one step further down.
+    pub fn child(mut self, label: &str, value: &str) -> Self {
+        self.steps.push(PathStep {
+            label: label.to_string(),
+            value: value.to_string(),
+        });
+        self
+    }
+
+    pub fn steps(&self) -> &[PathStep] {
+        &self.steps
+    }
+
+    /// The path of the secret `user` logs in to `server` with in the group `G`.
+    pub fn login<G: CyclicGroup>(server: &str, user: &str) -> Self {
+        let path = Self::new()
+            .child("server", server)
+            .child("user", user)
+            .child("flavor", &G::FLAVOR.to_string());
+        match G::CURVE {
+            Some(curve) => path.child("curve", &curve.to_string()),
+            None => path,
+        }
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("m")?;
+        for step in &self.steps {
+            write!(f, "/{}={}", step.label, step.value)?;
+        }
+        Ok(())
+    }
+}
+
+/// A node of the derivation tree.
+#[derive(Debug)]
+pub struct HdKey(Secret<[u8; 64]>);
+
+impl HdKey {
+    /// A fresh random master seed.
+    pub fn generate_seed() -> Secret<Vec<u8>> {
+        let mut seed = Secret::new(vec![0u8; SEED_LEN]);
+        OsRng.fill_bytes(seed.expose_mut());
+        seed
+    }
+
+    /// The root of the tree of a master seed.
+    pub fn from_seed(seed: &[u8]) -> Result<Self> {
+        if !(MIN_SEED_LEN..=MAX_SEED_LEN).contains(&seed.len()) {
+            return Err(ProtocolError::InvalidSeedLength.into());
+        }
+        let mut transcript = Transcript::new(b"hd-seed");
+        transcript.append_message(b"seed", seed);
+        Ok(HdKey(Secret::new(transcript.challenge_bytes(b"node"))))
+    }
+
+    /// The node one step below.
+    pub fn child(&self, label: &str, value: &str) -> Self {
+        let mut transcript = Transcript::new(b"hd-child");
+        transcript.append_message(b"parent", self.0.expose());
+        transcript.append_message(b"label", label.as_bytes());
+        transcript.append_message(b"value", value.as_bytes());
+        HdKey(Secret::new(transcript.challenge_bytes(b"node")))
+    }
+
+    /// The node at the end of the path.
+    pub fn derive(&self, path: &DerivationPath) -> Self {
+        let root = HdKey(Secret::new(*self.0.expose()));
+        path.steps()
+            .iter()
+            .fold(root, |node, step| node.child(&step.label, &step.value))
+    }
+
+    /// The secret `x` of the node in the group `G`.
+    pub fn secret<G: CyclicGroup>(&self, params: &GroupParams<G::Element>) -> Secret<G::Scalar> {
+        Secret::new(G::hash_to_scalar(params, HD_DOMAIN, self.0.expose()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::discrete_log::DiscreteLog;
+    use crate::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+    use crate::protocol::elliptic_curves::vesta::VestaEllipticCurve;
+
+    #[test]
+    fn hd_paths_separate_flavors() {
+        let pallas = DerivationPath::login::<PallasEllipticCurve>("example.org:50051", "peggy");
+        let vesta = DerivationPath::login::<VestaEllipticCurve>("example.org:50051", "peggy");
+        assert_eq!(
+            pallas.to_string(),
+            "m/server=example.org:50051/user=peggy/flavor=elliptic_curve/curve=pallas"
+        );
+        assert_eq!(
+            DerivationPath::login::<DiscreteLog>("example.org:50051", "peggy").to_string(),
+            "m/server=example.org:50051/user=peggy/flavor=discrete_log"
+        );
+        assert_ne!(pallas, vesta);
+    }
+}
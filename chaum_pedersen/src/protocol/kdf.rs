@@ -0,0 +1,238 @@
+//! Derivation of secrets from passwords with a memory-hard function, Argon2id or scrypt.
+//!
+//! Every user has their own random salt and cost parameters, which the server stores with the
+//! registration and hands back before a login, so that the same password gives the same secret.
+//! The 64 byte output is hashed to a uniform scalar by `hash_to_scalar`.
+
+use crate::enums::KdfAlgorithm;
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::secret::Secret;
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use anyhow::Result;
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+/// Domain of `hash_to_scalar` for the outputs of the derivation.
+pub const KDF_DOMAIN: &str = "password-kdf";
+
+/// Length of generated salts.
+pub const SALT_LEN: usize = 16;
+/// Shortest salt accepted, salts are not secret but must be unique.
+pub const MIN_SALT_LEN: usize = 16;
+/// Longest salt accepted.
+pub const MAX_SALT_LEN: usize = 64;
+/// Most memory, in bytes, a derivation may take, since clients derive with whatever parameters the
+/// server hands back.
+pub const MAX_MEMORY: u64 = 1 << 30;
+/// Most passes over the memory, or lanes, a derivation may take.
+pub const MAX_COST: u32 = 64;
+
+/// The salt and costs of a password derivation.
+///
+/// The meaning of the costs depends on the algorithm, following the parameters of each function:
+///
+/// | algorithm  | `memory_cost`      | `time_cost`     | `parallelism` |
+/// |------------|--------------------|-----------------|---------------|
+/// | `argon2id` | memory in KiB      | iterations      | lanes         |
+/// | `scrypt`   | `log2(N)`          | block size `r`  | `p`           |
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub algorithm: KdfAlgorithm,
+    pub salt: Vec<u8>,
+    pub memory_cost: u32,
+    pub time_cost: u32,
+    pub parallelism: u32,
+}
+
+impl KdfParams {
+    /// The recommended costs of the algorithm with a fresh salt: 19 MiB and 2 iterations for
+    /// Argon2id, `N = 2^17` and `r = 8` for scrypt.
+    pub fn generate(algorithm: KdfAlgorithm) -> Self {
+        let (memory_cost, time_cost, parallelism) = recommended_costs(algorithm);
+        Self::with_costs(algorithm, memory_cost, time_cost, parallelism)
+    }
+
+    /// The recommended costs of the algorithm with a salt derived from `key` and `user`, which
+    /// a server hands out for unknown users so that they look like registered ones.
+    pub fn dummy(algorithm: KdfAlgorithm, key: &[u8], user: &str) -> Self {
+        let (memory_cost, time_cost, parallelism) = recommended_costs(algorithm);
+        let mut transcript = Transcript::new(b"kdf-dummy");
+        transcript.append_message(b"key", key);
+        transcript.append_message(b"user", user.as_bytes());
+        let salt = transcript.challenge_bytes(b"salt");
+        KdfParams {
+            algorithm,
+            salt: salt[..SALT_LEN].to_vec(),
+            memory_cost,
+            time_cost,
+            parallelism,
+        }
+    }
+
+    /// The given costs with a fresh salt.
+    pub fn with_costs(
+        algorithm: KdfAlgorithm,
+        memory_cost: u32,
+        time_cost: u32,
+        parallelism: u32,
+    ) -> Self {
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        KdfParams {
+            algorithm,
+            salt,
+            memory_cost,
+            time_cost,
+            parallelism,
+        }
+    }
+
+    /// Checks the length of the salt, and that the costs are valid and within the bounds.
+    pub fn check(&self) -> Result<()> {
+        let salt = (MIN_SALT_LEN..=MAX_SALT_LEN).contains(&self.salt.len());
+        let costs =
+            (1..=MAX_COST).contains(&self.time_cost) && (1..=MAX_COST).contains(&self.parallelism);
+        let memory = match self.algorithm {
+            KdfAlgorithm::Argon2id => {
+                self.memory_cost >= 8 * self.parallelism
+                    && self.memory_cost as u64 * 1024 <= MAX_MEMORY
+            }
+            KdfAlgorithm::Scrypt => {
+                (1..64).contains(&self.memory_cost)
+                    && (128 * self.time_cost as u64)
+                        .checked_shl(self.memory_cost)
+                        .is_some_and(|memory| memory <= MAX_MEMORY)
+            }
+        };
+        if salt && costs && memory {
+            Ok(())
+        } else {
+            Err(ProtocolError::InvalidKdfParams.into())
+        }
+    }
+
+    /// Whether the parameters use the algorithm of `minimum`, with costs at least as high.
+    pub fn is_at_least(&self, minimum: &KdfParams) -> bool {
+        self.algorithm == minimum.algorithm
+            && self.memory_cost >= minimum.memory_cost
+            && self.time_cost >= minimum.time_cost
+            && self.parallelism >= minimum.parallelism
+    }
+
+    /// Derives 64 bytes from the password.
+    pub fn derive(&self, password: &[u8]) -> Result<Secret<[u8; 64]>> {
+        self.check()?;
+        let mut output = Secret::new([0u8; 64]);
+        let out = output.expose_mut();
+        match self.algorithm {
+            KdfAlgorithm::Argon2id => {
+                let params = argon2::Params::new(
+                    self.memory_cost,
+                    self.time_cost,
+                    self.parallelism,
+                    Some(out.len()),
+                )
+                .map_err(|_| ProtocolError::InvalidKdfParams)?;
+                argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+                    .hash_password_into(password, &self.salt, out)
+                    .map_err(|_| ProtocolError::InvalidKdfParams)?;
+            }
+            KdfAlgorithm::Scrypt => {
+                let params = scrypt::Params::new(
+                    self.memory_cost as u8,
+                    self.time_cost,
+                    self.parallelism,
+                    out.len(),
+                )
+                .map_err(|_| ProtocolError::InvalidKdfParams)?;
+                scrypt::scrypt(password, &self.salt, &params, out)
+                    .map_err(|_| ProtocolError::InvalidKdfParams)?;
+            }
+        }
+        Ok(output)
+    }
+
+    /// Derives the secret `x` of `G` from the password.
+    pub fn derive_secret<G: CyclicGroup>(
+        &self,
+        params: &GroupParams<G::Element>,
+        password: &[u8],
+    ) -> Result<Secret<G::Scalar>> {
+        let bytes = self.derive(password)?;
+        Ok(Secret::new(G::hash_to_scalar(
+            params,
+            KDF_DOMAIN,
+            bytes.expose(),
+        )))
+    }
+}
+
+/// The memory, time and parallelism costs `generate` picks.
+fn recommended_costs(algorithm: KdfAlgorithm) -> (u32, u32, u32) {
+    match algorithm {
+        KdfAlgorithm::Argon2id => (19 * 1024, 2, 1),
+        KdfAlgorithm::Scrypt => (17, 8, 1),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn kdf_params_are_bounded() {
+        let mut kdf = KdfParams::with_costs(KdfAlgorithm::Argon2id, 64, 1, 1);
+        assert!(kdf.check().is_ok());
+        kdf.salt.truncate(MIN_SALT_LEN - 1);
+        assert!(kdf.check().is_err());
+
+        assert!(KdfParams::with_costs(KdfAlgorithm::Argon2id, 4, 1, 1)
+            .check()
+            .is_err());
+        assert!(KdfParams::with_costs(KdfAlgorithm::Argon2id, 1 << 21, 1, 1)
+            .check()
+            .is_err());
+        assert!(KdfParams::with_costs(KdfAlgorithm::Scrypt, 21, 8, 1)
+            .check()
+            .is_err());
+        assert!(KdfParams::with_costs(KdfAlgorithm::Scrypt, 10, 8, 0)
+            .check()
+            .is_err());
+        assert!(KdfParams::generate(KdfAlgorithm::Argon2id).check().is_ok());
+        assert!(KdfParams::generate(KdfAlgorithm::Scrypt).check().is_ok());
+    }
+
+    #[test]
+    fn dummy_kdf_params_are_deterministic() {
+        let kdf = KdfParams::dummy(KdfAlgorithm::Argon2id, b"key", "peggy");
+        assert!(kdf.check().is_ok());
+        assert_eq!(
+            kdf,
+            KdfParams::dummy(KdfAlgorithm::Argon2id, b"key", "peggy")
+        );
+        assert_ne!(
+            kdf,
+            KdfParams::dummy(KdfAlgorithm::Argon2id, b"key", "victor")
+        );
+        assert_ne!(
+            kdf,
+            KdfParams::dummy(KdfAlgorithm::Argon2id, b"other", "peggy")
+        );
+
+        let recommended = KdfParams::generate(KdfAlgorithm::Argon2id);
+        assert!(kdf.is_at_least(&recommended));
+        assert!(!KdfParams::with_costs(KdfAlgorithm::Argon2id, 64, 2, 1).is_at_least(&recommended));
+        assert!(!KdfParams::generate(KdfAlgorithm::Scrypt).is_at_least(&recommended));
+    }
+
+    #[test]
+    fn kdf_algorithms_have_snake_case_names() {
+        assert_eq!(KdfAlgorithm::Argon2id.to_string(), "argon2id");
+        assert_eq!(
+            "scrypt".parse::<KdfAlgorithm>().ok(),
+            Some(KdfAlgorithm::Scrypt)
+        );
+    }
+}
@@ -1,6 +1,37 @@
+pub mod armor;
 pub mod constants;
+pub mod contexts;
+pub mod cross_group;
+pub mod designated;
 pub mod discrete_log;
+pub mod dkg;
+pub mod dleq;
+pub mod elgamal;
 pub mod elliptic_curves;
+pub(crate) mod encoding;
+pub mod errors;
+pub mod group;
+pub mod group_params;
+pub mod hd;
+pub mod kdf;
+pub mod or_proof;
+pub mod parallel;
+pub mod pedersen;
+pub mod range;
+pub mod rekey;
+pub mod representation;
+pub mod ring;
+pub mod schnorr;
+pub mod secret;
+pub mod shamir;
+pub mod sigma;
+pub mod threshold;
+pub mod transcript;
+pub mod vectors;
+pub mod voprf;
+pub mod vrf;
+pub mod vss;
+pub mod wire;
 
 /// A struct representing parameters of groups used in implementation.
 #[derive(Copy, Clone, Debug)]
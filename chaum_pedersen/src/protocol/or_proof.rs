@@ -0,0 +1,244 @@
+//! Cramer-Damgård-Schoenmakers proofs of knowledge of a witness for one out of several statements.
+//!
+//! The prover answers the challenge honestly for the statement it knows the witness of and
+//! simulates the transcripts of all the others, the challenges being constrained to sum up to the
+//! Fiat-Shamir challenge. The verifier can not tell which statement was proven.
+
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+
+/// The statement "I know `x` such that `values[i] = bases[i]^x` for every `i`".
+///
+/// A single pair is a Schnorr statement, two pairs are the Chaum-Pedersen (DLEQ) statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statement<G: CyclicGroup> {
+    pub bases: Vec<G::Element>,
+    pub values: Vec<G::Element>,
+}
+
+impl<G: CyclicGroup> Statement<G> {
+    pub fn new(bases: Vec<G::Element>, values: Vec<G::Element>) -> Result<Self> {
+        if bases.is_empty() || bases.len() != values.len() {
+            return Err(ProtocolError::LengthMismatch.into());
+        }
+        Ok(Statement { bases, values })
+    }
+
+    /// The statement `bases[i]^x` for every base.
+    pub fn from_witness(
+        params: &GroupParams<G::Element>,
+        bases: Vec<G::Element>,
+        x: &G::Scalar,
+    ) -> Result<Self> {
+        let values = bases.iter().map(|base| G::exp(params, base, x)).collect();
+        Self::new(bases, values)
+    }
+
+    fn append_to(&self, transcript: &mut Transcript) {
+        for (base, value) in self.bases.iter().zip(&self.values) {
+            transcript.append_element::<G>(b"base", base);
+            transcript.append_element::<G>(b"value", value);
+        }
+    }
+
+    /// The commitments `bases[i]^s · values[i]^-c` a valid response `s` to `c` has to match.
+    fn commitments(
+        &self,
+        params: &GroupParams<G::Element>,
+        c: &G::Scalar,
+        s: &G::Scalar,
+    ) -> Vec<G::Element> {
+        let minus_c = G::scalar_neg(params, c);
+        self.bases
+            .iter()
+            .zip(&self.values)
+            .map(|(base, value)| {
+                G::multi_exp(
+                    params,
+                    &[base.clone(), value.clone()],
+                    &[s.clone(), minus_c.clone()],
+                )
+            })
+            .collect()
+    }
+}
+
+/// A proof of knowledge of the witness of one of several statements.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrProof<G: CyclicGroup> {
+    /// The per statement challenges, summing up to the Fiat-Shamir challenge.
+    pub c: Vec<G::Scalar>,
+    /// The per statement responses.
+    pub s: Vec<G::Scalar>,
+}
+
+impl<G: CyclicGroup> OrProof<G> {
+    /// Proves knowledge of the witness `x` of `statements[index]`.
+    pub fn prove(
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        statements: &[Statement<G>],
+        index: usize,
+        x: &G::Scalar,
+    ) -> Result<Self> {
+        if index >= statements.len() {
+            return Err(ProtocolError::LengthMismatch.into());
+        }
+        let mut c: Vec<G::Scalar> = statements
+            .iter()
+            .map(|_| G::random_scalar(params))
+            .collect();
+        let mut s: Vec<G::Scalar> = statements
+            .iter()
+            .map(|_| G::random_scalar(params))
+            .collect();
+
+        let k = G::random_scalar(params);
+        let commitments: Vec<Vec<G::Element>> = statements
+            .iter()
+            .enumerate()
+            .map(|(i, statement)| {
+                if i == index {
+                    statement
+                        .bases
+                        .iter()
+                        .map(|base| G::exp(params, base, &k))
+                        .collect()
+                } else {
+                    statement.commitments(params, &c[i], &s[i])
+                }
+            })
+            .collect();
+
+        let challenge = Self::challenge(params, transcript, statements, &commitments);
+        let simulated = c
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .fold(G::scalar_zero(params), |acc, (_, c)| {
+                G::scalar_add(params, &acc, c)
+            });
+        c[index] = G::scalar_sub(params, &challenge, &simulated);
+        s[index] = G::scalar_add(params, &k, &G::scalar_mul(params, &c[index], x));
+
+        Ok(OrProof { c, s })
+    }
+
+    pub fn verify(
+        &self,
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        statements: &[Statement<G>],
+    ) -> bool {
+        if statements.is_empty()
+            || self.c.len() != statements.len()
+            || self.s.len() != statements.len()
+        {
+            return false;
+        }
+        let well_formed = statements.iter().all(|statement| {
+            statement.bases.len() == statement.values.len()
+                && statement
+                    .values
+                    .iter()
+                    .all(|value| G::contains(params, value))
+        });
+        if !well_formed {
+            return false;
+        }
+
+        let commitments: Vec<Vec<G::Element>> = statements
+            .iter()
+            .zip(self.c.iter().zip(&self.s))
+            .map(|(statement, (c, s))| statement.commitments(params, c, s))
+            .collect();
+
+        let challenge = Self::challenge(params, transcript, statements, &commitments);
+        let sum = self.c.iter().fold(G::scalar_zero(params), |acc, c| {
+            G::scalar_add(params, &acc, c)
+        });
+        challenge == sum
+    }
+
+    fn challenge(
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        statements: &[Statement<G>],
+        commitments: &[Vec<G::Element>],
+    ) -> G::Scalar {
+        transcript.append_message(b"protocol", b"or-proof");
+        transcript.append_message(b"statements", &(statements.len() as u64).to_be_bytes());
+        for (statement, commitments) in statements.iter().zip(commitments) {
+            statement.append_to(transcript);
+            for t in commitments {
+                transcript.append_element::<G>(b"t", t);
+            }
+        }
+        transcript.challenge_scalar::<G>(params, b"c")
+    }
+}
+
+impl<G: CyclicGroup> IntoBytes<OrProof<G>> for OrProof<G> {
+    fn to(t: &OrProof<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put_all(&mut out, &t.c);
+        encoding::put_all(&mut out, &t.s);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<OrProof<G>> for OrProof<G> {
+    fn from(bytes: &[u8]) -> Result<OrProof<G>> {
+        let mut reader = Reader::new(bytes);
+        let proof = OrProof {
+            c: reader.take_all()?,
+            s: reader.take_all()?,
+        };
+        reader.finish()?;
+        Ok(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::constants::PALLAS_GROUP_PARAMS;
+    use crate::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+
+    #[test]
+    fn or_proof_hides_the_known_statement() {
+        let params = PALLAS_GROUP_PARAMS.to_owned();
+        let x = PallasEllipticCurve::random_scalar(&params);
+        let known =
+            Statement::<PallasEllipticCurve>::from_witness(&params, vec![params.g, params.h], &x)
+                .unwrap();
+        let other = Statement::new(vec![params.g, params.h], vec![params.h, params.g]).unwrap();
+
+        for statements in [
+            vec![known.clone(), other.clone()],
+            vec![other.clone(), known.clone()],
+        ] {
+            let index = statements.iter().position(|s| *s == known).unwrap();
+            let proof = OrProof::prove(
+                &params,
+                &mut Transcript::new(b"test"),
+                &statements,
+                index,
+                &x,
+            )
+            .unwrap();
+            assert!(proof.verify(&params, &mut Transcript::new(b"test"), &statements));
+        }
+
+        // without a known witness the proof does not verify
+        let statements = vec![other.clone(), other];
+        let proof =
+            OrProof::prove(&params, &mut Transcript::new(b"test"), &statements, 0, &x).unwrap();
+        assert!(!proof.verify(&params, &mut Transcript::new(b"test"), &statements));
+    }
+}
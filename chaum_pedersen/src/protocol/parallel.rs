@@ -0,0 +1,184 @@
+//! The interactive Chaum-Pedersen protocol repeated in parallel with short challenges.
+//!
+//! Provers unable to work with full-width challenges answer `k` independent rounds with `t`-bit
+//! challenges instead. A cheating prover has to guess every challenge, so the soundness error
+//! is `2^-(t·k)`, and `k` is picked so that `t·k` reaches the configured soundness target.
+
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::secret::{Nonce, Secret};
+use crate::protocol::GroupParams;
+use anyhow::Result;
+use rand::rngs::OsRng;
+use rand::Rng;
+
+/// The largest supported challenge bit length, challenges being sent as `u64`.
+pub const MAX_CHALLENGE_BITS: u32 = 64;
+/// The largest supported soundness target, which bounds the number of rounds a verifier may
+/// ask a prover for.
+pub const MAX_SOUNDNESS_BITS: usize = 256;
+
+/// The shape of a parallel run: `rounds` rounds with `challenge_bits`-bit challenges.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParallelConfig {
+    pub challenge_bits: u32,
+    pub rounds: usize,
+}
+
+/// The commitments `(r1, r2) = (g^k, h^k)` of one round.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoundCommitment<G: CyclicGroup> {
+    pub r1: G::Element,
+    pub r2: G::Element,
+}
+
+impl ParallelConfig {
+    pub fn new(challenge_bits: u32, rounds: usize) -> Result<Self> {
+        if challenge_bits == 0 || challenge_bits > MAX_CHALLENGE_BITS {
+            return Err(ProtocolError::InvalidChallengeBits.into());
+        }
+        if rounds == 0 {
+            return Err(ProtocolError::EmptyBatch.into());
+        }
+        // more rounds than the largest target needs only cost the prover
+        if rounds > MAX_SOUNDNESS_BITS.div_ceil(challenge_bits as usize) {
+            return Err(ProtocolError::TooManyRounds.into());
+        }
+        Ok(ParallelConfig {
+            challenge_bits,
+            rounds,
+        })
+    }
+
+    /// The fewest rounds of `challenge_bits`-bit challenges reaching `2^-soundness_bits`.
+    pub fn for_soundness(challenge_bits: u32, soundness_bits: usize) -> Result<Self> {
+        Self::new(
+            challenge_bits,
+            soundness_bits.div_ceil(challenge_bits.max(1) as usize),
+        )
+    }
+
+    /// `t·k`, the soundness error being `2^-(t·k)`.
+    pub fn soundness_bits(&self) -> usize {
+        self.challenge_bits as usize * self.rounds
+    }
+
+    /// The probability for a prover not knowing the secret to be accepted.
+    pub fn soundness_error(&self) -> f64 {
+        2f64.powi(-(self.soundness_bits().min(i32::MAX as usize) as i32))
+    }
+
+    /// Samples the verifier's challenges, one per round.
+    pub fn challenges(&self) -> Vec<u64> {
+        let mask = u64::MAX >> (u64::BITS - self.challenge_bits);
+        (0..self.rounds)
+            .map(|_| OsRng.gen::<u64>() & mask)
+            .collect()
+    }
+}
+
+/// The prover's first move: fresh commitments for every round, and their nonces.
+pub fn commit<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    config: &ParallelConfig,
+) -> (Vec<RoundCommitment<G>>, Vec<Nonce<G::Scalar>>) {
+    (0..config.rounds)
+        .map(|_| {
+            let k = Nonce::new(G::random_scalar(params));
+            let commitment = RoundCommitment {
+                r1: G::exp(params, &params.g, k.expose()),
+                r2: G::exp(params, &params.h, k.expose()),
+            };
+            (commitment, k)
+        })
+        .unzip()
+}
+
+/// The prover's answers `si = ki + ci·x` to the challenges.
+pub fn respond<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    config: &ParallelConfig,
+    nonces: &[Nonce<G::Scalar>],
+    challenges: &[u64],
+    x: &Secret<G::Scalar>,
+) -> Result<Vec<G::Scalar>> {
+    if nonces.len() != config.rounds || !is_valid_challenges(config, challenges) {
+        return Err(ProtocolError::LengthMismatch.into());
+    }
+    Ok(nonces
+        .iter()
+        .zip(challenges)
+        .map(|(k, c)| {
+            G::scalar_add(
+                params,
+                k.expose(),
+                &G::scalar_mul(params, &G::scalar_from_u64(params, *c), x.expose()),
+            )
+        })
+        .collect())
+}
+
+/// Checks `g^si = r1i · y1^ci` and `h^si = r2i · y2^ci` for every round.
+pub fn verify<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    config: &ParallelConfig,
+    (y1, y2): (&G::Element, &G::Element),
+    commitments: &[RoundCommitment<G>],
+    challenges: &[u64],
+    responses: &[G::Scalar],
+) -> bool {
+    if commitments.len() != config.rounds
+        || responses.len() != config.rounds
+        || !is_valid_challenges(config, challenges)
+        || !G::contains(params, y1)
+        || !G::contains(params, y2)
+    {
+        return false;
+    }
+    commitments
+        .iter()
+        .zip(challenges)
+        .zip(responses)
+        .all(|((commitment, c), s)| {
+            let c = G::scalar_from_u64(params, *c);
+            G::exp(params, &params.g, s) == G::op(params, &commitment.r1, &G::exp(params, y1, &c))
+                && G::exp(params, &params.h, s)
+                    == G::op(params, &commitment.r2, &G::exp(params, y2, &c))
+        })
+}
+
+fn is_valid_challenges(config: &ParallelConfig, challenges: &[u64]) -> bool {
+    challenges.len() == config.rounds
+        && challenges
+            .iter()
+            .all(|c| config.challenge_bits == u64::BITS || c >> config.challenge_bits == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_reach_soundness_target() {
+        let config = ParallelConfig::for_soundness(8, 128).unwrap();
+        assert_eq!(config.rounds, 16);
+        assert_eq!(config.soundness_bits(), 128);
+
+        let config = ParallelConfig::for_soundness(10, 128).unwrap();
+        assert_eq!(config.rounds, 13);
+        assert_eq!(config.soundness_error(), 2f64.powi(-130));
+
+        assert!(config.challenges().iter().all(|c| *c < 1 << 10));
+        assert!(ParallelConfig::for_soundness(0, 128).is_err());
+        assert!(ParallelConfig::for_soundness(65, 128).is_err());
+    }
+
+    #[test]
+    fn rounds_are_bounded() {
+        assert!(ParallelConfig::new(1, MAX_SOUNDNESS_BITS).is_ok());
+        assert!(ParallelConfig::new(1, MAX_SOUNDNESS_BITS + 1).is_err());
+        assert!(ParallelConfig::new(64, 5).is_err());
+        assert!(ParallelConfig::new(8, usize::MAX).is_err());
+        assert!(ParallelConfig::for_soundness(8, MAX_SOUNDNESS_BITS + 8).is_err());
+    }
+}
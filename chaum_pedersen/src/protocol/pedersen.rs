@@ -0,0 +1,186 @@
+//! Pedersen commitments `C = g^v · h^r` over the generators of `GroupParams`.
+//!
+//! The commitments are perfectly hiding and computationally binding, as long as
+//! nobody knows `log_g h`. They are additively homomorphic: `C(v1, r1) · C(v2, r2) = C(v1 + v2, r1 + r2)`.
+
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::representation::RepresentationProof;
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+use std::fmt;
+
+/// A commitment `C = g^v · h^r` to the value `v`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PedersenCommitment<G: CyclicGroup> {
+    pub c: G::Element,
+}
+
+/// The secret opening of a commitment.
+#[derive(Clone, PartialEq)]
+pub struct Opening<G: CyclicGroup> {
+    /// The committed value `v`.
+    pub v: G::Scalar,
+    /// The blinding factor `r`.
+    pub r: G::Scalar,
+}
+
+/// Prints nothing of the opening, which would reveal the committed value.
+impl<G: CyclicGroup> fmt::Debug for Opening<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Opening").finish_non_exhaustive()
+    }
+}
+
+impl<G: CyclicGroup> PedersenCommitment<G> {
+    /// Commits to `v` with fresh randomness.
+    pub fn commit(params: &GroupParams<G::Element>, v: &G::Scalar) -> (Self, Opening<G>) {
+        let opening = Opening {
+            v: v.clone(),
+            r: G::random_scalar(params),
+        };
+        (Self::open(params, &opening), opening)
+    }
+
+    /// Recomputes the commitment determined by an opening.
+    pub fn open(params: &GroupParams<G::Element>, opening: &Opening<G>) -> Self {
+        PedersenCommitment {
+            c: G::multi_exp(
+                params,
+                &[params.g.clone(), params.h.clone()],
+                &[opening.v.clone(), opening.r.clone()],
+            ),
+        }
+    }
+
+    /// Checks that `opening` opens this commitment.
+    pub fn verify_opening(&self, params: &GroupParams<G::Element>, opening: &Opening<G>) -> bool {
+        G::contains(params, &self.c) && Self::open(params, opening) == *self
+    }
+
+    /// Homomorphically adds two commitments, the result commits to the sum of the values.
+    pub fn add(&self, params: &GroupParams<G::Element>, other: &Self) -> Self {
+        PedersenCommitment {
+            c: G::op(params, &self.c, &other.c),
+        }
+    }
+}
+
+impl<G: CyclicGroup> Opening<G> {
+    /// The opening of the sum of the two commitments opened by `self` and `other`.
+    pub fn add(&self, params: &GroupParams<G::Element>, other: &Self) -> Self {
+        Opening {
+            v: G::scalar_add(params, &self.v, &other.v),
+            r: G::scalar_add(params, &self.r, &other.r),
+        }
+    }
+}
+
+/// A proof that two commitments hide the same value, without revealing it.
+///
+/// If `C1 = g^v · h^r1` and `C2 = g^v · h^r2`, then `C1 / C2 = h^(r1 - r2)`,
+/// so it suffices to prove knowledge of the discrete log of `C1 / C2` to the base `h`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EqualityProof<G: CyclicGroup> {
+    pub proof: RepresentationProof<G>,
+}
+
+impl<G: CyclicGroup> EqualityProof<G> {
+    pub fn prove(
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        (c1, opening1): (&PedersenCommitment<G>, &Opening<G>),
+        (c2, opening2): (&PedersenCommitment<G>, &Opening<G>),
+    ) -> Result<Self> {
+        let quotient = Self::quotient(params, transcript, c1, c2);
+        let r = G::scalar_sub(params, &opening1.r, &opening2.r);
+        let proof = RepresentationProof::prove(
+            params,
+            transcript,
+            std::slice::from_ref(&params.h),
+            &quotient,
+            &[r],
+        )?;
+        Ok(EqualityProof { proof })
+    }
+
+    pub fn verify(
+        &self,
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        c1: &PedersenCommitment<G>,
+        c2: &PedersenCommitment<G>,
+    ) -> bool {
+        let quotient = Self::quotient(params, transcript, c1, c2);
+        self.proof.verify(
+            params,
+            transcript,
+            std::slice::from_ref(&params.h),
+            &quotient,
+        )
+    }
+
+    fn quotient(
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        c1: &PedersenCommitment<G>,
+        c2: &PedersenCommitment<G>,
+    ) -> G::Element {
+        transcript.append_message(b"protocol", b"pedersen-equality");
+        transcript.append_params::<G>(params);
+        transcript.append_element::<G>(b"c1", &c1.c);
+        transcript.append_element::<G>(b"c2", &c2.c);
+        G::op(params, &c1.c, &G::inverse(params, &c2.c))
+    }
+}
+
+impl<G: CyclicGroup> IntoBytes<PedersenCommitment<G>> for PedersenCommitment<G> {
+    fn to(t: &PedersenCommitment<G>) -> Vec<u8> {
+        G::Element::to(&t.c)
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<PedersenCommitment<G>> for PedersenCommitment<G> {
+    fn from(bytes: &[u8]) -> Result<PedersenCommitment<G>> {
+        Ok(PedersenCommitment {
+            c: <G::Element as FromBytes<_>>::from(bytes)?,
+        })
+    }
+}
+
+impl<G: CyclicGroup> IntoBytes<Opening<G>> for Opening<G> {
+    fn to(t: &Opening<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put(&mut out, &t.v);
+        encoding::put(&mut out, &t.r);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<Opening<G>> for Opening<G> {
+    fn from(bytes: &[u8]) -> Result<Opening<G>> {
+        let mut reader = Reader::new(bytes);
+        let opening = Opening {
+            v: reader.take()?,
+            r: reader.take()?,
+        };
+        reader.finish()?;
+        Ok(opening)
+    }
+}
+
+impl<G: CyclicGroup> IntoBytes<EqualityProof<G>> for EqualityProof<G> {
+    fn to(t: &EqualityProof<G>) -> Vec<u8> {
+        RepresentationProof::to(&t.proof)
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<EqualityProof<G>> for EqualityProof<G> {
+    fn from(bytes: &[u8]) -> Result<EqualityProof<G>> {
+        Ok(EqualityProof {
+            proof: <RepresentationProof<G> as FromBytes<_>>::from(bytes)?,
+        })
+    }
+}
@@ -0,0 +1,185 @@
+//! Proofs that a Pedersen committed value lies in `[0, 2^n)`, via its bit decomposition.
+//!
+//! The prover commits to every bit `bi` as `Ci = g^bi · h^ri`, proves with an OR proof that
+//! each `Ci` commits to either `0` or `1`, and picks the `ri` so that `C = Π Ci^(2^i)`,
+//! which the verifier checks directly.
+
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::or_proof::{OrProof, Statement};
+use crate::protocol::pedersen::{Opening, PedersenCommitment};
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+
+/// A proof that a committed value lies in `[0, 2^n)`, `n` being the number of bit commitments.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeProof<G: CyclicGroup> {
+    /// The commitments to the bits of the value, least significant first.
+    pub bits: Vec<PedersenCommitment<G>>,
+    /// The proofs that every bit commitment hides `0` or `1`.
+    pub proofs: Vec<OrProof<G>>,
+}
+
+impl<G: CyclicGroup> RangeProof<G> {
+    /// Proves that the value committed with `opening` lies in `[0, 2^n)`.
+    pub fn prove(
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        opening: &Opening<G>,
+        n: usize,
+    ) -> Result<Self> {
+        Self::check_bits(params, n)?;
+        let bits = G::scalar_bits(params, &opening.v);
+        if bits[n..].iter().any(|bit| *bit) {
+            return Err(ProtocolError::ValueOutOfRange.into());
+        }
+
+        // pick the last blinding factor so that Σ 2^i·ri = r
+        let mut r: Vec<G::Scalar> = (0..n - 1).map(|_| G::random_scalar(params)).collect();
+        let powers = Self::powers_of_two(params, n);
+        let partial = r
+            .iter()
+            .zip(&powers)
+            .fold(G::scalar_zero(params), |acc, (ri, power)| {
+                G::scalar_add(params, &acc, &G::scalar_mul(params, ri, power))
+            });
+        let last_power_inverse =
+            G::scalar_invert(params, &powers[n - 1]).ok_or(ProtocolError::ValueOutOfRange)?;
+        r.push(G::scalar_mul(
+            params,
+            &G::scalar_sub(params, &opening.r, &partial),
+            &last_power_inverse,
+        ));
+
+        let openings: Vec<Opening<G>> = bits[..n]
+            .iter()
+            .zip(r)
+            .map(|(bit, r)| Opening {
+                v: G::scalar_from_u64(params, *bit as u64),
+                r,
+            })
+            .collect();
+        let commitments: Vec<PedersenCommitment<G>> = openings
+            .iter()
+            .map(|opening| PedersenCommitment::open(params, opening))
+            .collect();
+
+        Self::append_to(params, transcript, &commitments);
+        let proofs = commitments
+            .iter()
+            .zip(&openings)
+            .map(|(commitment, opening)| {
+                OrProof::prove(
+                    params,
+                    transcript,
+                    &Self::bit_statements(params, commitment)?,
+                    usize::from(opening.v == G::scalar_from_u64(params, 1)),
+                    &opening.r,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(RangeProof {
+            bits: commitments,
+            proofs,
+        })
+    }
+
+    /// Verifies that `commitment` hides a value in `[0, 2^n)`.
+    pub fn verify(
+        &self,
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        commitment: &PedersenCommitment<G>,
+        n: usize,
+    ) -> bool {
+        if Self::check_bits(params, n).is_err()
+            || self.bits.len() != n
+            || self.proofs.len() != n
+            || !G::contains(params, &commitment.c)
+        {
+            return false;
+        }
+
+        let elements: Vec<G::Element> = self.bits.iter().map(|bit| bit.c.clone()).collect();
+        let combined = G::multi_exp(params, &elements, &Self::powers_of_two(params, n));
+        if combined != commitment.c {
+            return false;
+        }
+
+        Self::append_to(params, transcript, &self.bits);
+        self.bits.iter().zip(&self.proofs).all(|(bit, proof)| {
+            Self::bit_statements(params, bit)
+                .map(|statements| proof.verify(params, transcript, &statements))
+                .unwrap_or(false)
+        })
+    }
+
+    fn check_bits(params: &GroupParams<G::Element>, n: usize) -> Result<()> {
+        // 2^n must stay below the group order, otherwise the decomposition wraps around
+        if n == 0 || n >= G::order_bits(params) {
+            return Err(ProtocolError::InvalidRangeBits.into());
+        }
+        Ok(())
+    }
+
+    fn powers_of_two(params: &GroupParams<G::Element>, n: usize) -> Vec<G::Scalar> {
+        let two = G::scalar_from_u64(params, 2);
+        let mut powers = vec![G::scalar_from_u64(params, 1)];
+        for i in 1..n {
+            powers.push(G::scalar_mul(params, &powers[i - 1], &two));
+        }
+        powers
+    }
+
+    /// A bit commitment `C` hides `0` if `C = h^r`, and `1` if `C / g = h^r`.
+    fn bit_statements(
+        params: &GroupParams<G::Element>,
+        commitment: &PedersenCommitment<G>,
+    ) -> Result<Vec<Statement<G>>> {
+        let minus_g = G::inverse(params, &params.g);
+        Ok(vec![
+            Statement::new(vec![params.h.clone()], vec![commitment.c.clone()])?,
+            Statement::new(
+                vec![params.h.clone()],
+                vec![G::op(params, &commitment.c, &minus_g)],
+            )?,
+        ])
+    }
+
+    fn append_to(
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        commitments: &[PedersenCommitment<G>],
+    ) {
+        transcript.append_message(b"protocol", b"range");
+        transcript.append_params::<G>(params);
+        for commitment in commitments {
+            transcript.append_element::<G>(b"bit", &commitment.c);
+        }
+    }
+}
+
+impl<G: CyclicGroup> IntoBytes<RangeProof<G>> for RangeProof<G> {
+    fn to(t: &RangeProof<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put_all(&mut out, &t.bits);
+        encoding::put_all(&mut out, &t.proofs);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<RangeProof<G>> for RangeProof<G> {
+    fn from(bytes: &[u8]) -> Result<RangeProof<G>> {
+        let mut reader = Reader::new(bytes);
+        let proof = RangeProof {
+            bits: reader.take_all()?,
+            proofs: reader.take_all()?,
+        };
+        reader.finish()?;
+        Ok(proof)
+    }
+}
@@ -0,0 +1,142 @@
+//! Re-keying to another group, such as the other curve of the Pasta cycle, under a fresh secret of
+//! that group.
+//!
+//! Unlike the proofs of `cross_group`, nothing shows that the same secret is behind both keys, the
+//! old key only hands over to the new one. That works whatever the sizes of the secrets and of the
+//! group orders: the old key `y = g^x` signs the new statement `(y1, y2) = (g'^x', h'^x')`, and a
+//! DLEQ proof under `x'` shows that the statement is well formed. Both are bound to a context of
+//! the caller, e.g. the user and a nonce of the server, so that a proof is only accepted once.
+
+use crate::protocol::dleq::{DleqProof, DleqStatement};
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::schnorr::Signature;
+use crate::protocol::transcript::Transcript;
+use crate::protocol::wire::Envelope;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+
+/// A proof that the key of `G1` hands over to the statement of `G2`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RekeyProof<G1: CyclicGroup, G2: CyclicGroup> {
+    /// The signature of the new statement under the old key.
+    pub signature: Signature<G1>,
+    /// The proof that both keys of the new statement have the same secret.
+    pub proof: DleqProof<G2>,
+}
+
+impl<G1: CyclicGroup, G2: CyclicGroup> RekeyProof<G1, G2> {
+    /// Hands the key of `x1` over to the keys `(g^x2, h^x2)` of the second group.
+    pub fn prove(
+        params1: &GroupParams<G1::Element>,
+        params2: &GroupParams<G2::Element>,
+        context: &Transcript,
+        x1: &G1::Scalar,
+        x2: &G2::Scalar,
+    ) -> Self {
+        let statement = DleqStatement::<G2> {
+            g1: params2.g.clone(),
+            y1: G2::exp(params2, &params2.g, x2),
+            g2: params2.h.clone(),
+            y2: G2::exp(params2, &params2.h, x2),
+        };
+        let message = Envelope::statement::<G2>(&statement.y1, &statement.y2).to_binary();
+        RekeyProof {
+            signature: Signature::sign_in_context(params1, context, x1, &message),
+            proof: DleqProof::prove(params2, &mut context.clone(), &statement, x2),
+        }
+    }
+
+    /// Verifies that the key `y` of the first group hands over to `(y1, y2)` in the second one.
+    pub fn verify(
+        &self,
+        params1: &GroupParams<G1::Element>,
+        params2: &GroupParams<G2::Element>,
+        context: &Transcript,
+        y: &G1::Element,
+        y1: &G2::Element,
+        y2: &G2::Element,
+    ) -> bool {
+        let statement = DleqStatement::<G2> {
+            g1: params2.g.clone(),
+            y1: y1.clone(),
+            g2: params2.h.clone(),
+            y2: y2.clone(),
+        };
+        let message = Envelope::statement::<G2>(y1, y2).to_binary();
+        self.signature
+            .verify_in_context(params1, context, y, &message)
+            && self.proof.verify(params2, &mut context.clone(), &statement)
+    }
+}
+
+impl<G1: CyclicGroup, G2: CyclicGroup> IntoBytes<RekeyProof<G1, G2>> for RekeyProof<G1, G2> {
+    fn to(t: &RekeyProof<G1, G2>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put(&mut out, &t.signature);
+        encoding::put(&mut out, &t.proof);
+        out
+    }
+}
+
+impl<G1: CyclicGroup, G2: CyclicGroup> FromBytes<RekeyProof<G1, G2>> for RekeyProof<G1, G2> {
+    fn from(bytes: &[u8]) -> Result<RekeyProof<G1, G2>> {
+        let mut reader = Reader::new(bytes);
+        let proof = RekeyProof {
+            signature: reader.take()?,
+            proof: reader.take()?,
+        };
+        reader.finish()?;
+        Ok(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::constants::{DLOG_GROUP_PARAMS, PALLAS_GROUP_PARAMS, VESTA_GROUP_PARAMS};
+    use crate::protocol::contexts;
+    use crate::protocol::discrete_log::DiscreteLog;
+    use crate::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+    use crate::protocol::elliptic_curves::vesta::VestaEllipticCurve;
+
+    fn rekey<G1: CyclicGroup, G2: CyclicGroup>(
+        params1: &GroupParams<G1::Element>,
+        params2: &GroupParams<G2::Element>,
+    ) {
+        // any secret hands over, whatever its size
+        let x = G1::random_scalar(params1);
+        let y = G1::exp(params1, &params1.g, &x);
+        let x2 = G2::random_scalar(params2);
+        let y1 = G2::exp(params2, &params2.g, &x2);
+        let y2 = G2::exp(params2, &params2.h, &x2);
+
+        let context = contexts::rekey("peggy", "nonce-1");
+        let proof = RekeyProof::<G1, G2>::prove(params1, params2, &context, &x, &x2);
+        assert!(proof.verify(params1, params2, &context, &y, &y1, &y2));
+        let recovered =
+            <RekeyProof<G1, G2> as FromBytes<_>>::from(&RekeyProof::to(&proof)).unwrap();
+        assert_eq!(proof, recovered);
+
+        // the proof holds for neither another user, nonce, old key nor statement
+        for other in [
+            contexts::rekey("victor", "nonce-1"),
+            contexts::rekey("peggy", "nonce-2"),
+            contexts::migration("peggy", "nonce-1"),
+        ] {
+            assert!(!proof.verify(params1, params2, &other, &y, &y1, &y2));
+        }
+        let other = G1::exp(params1, &params1.g, &G1::random_scalar(params1));
+        assert!(!proof.verify(params1, params2, &context, &other, &y1, &y2));
+        let other = G2::exp(params2, &params2.h, &G2::random_scalar(params2));
+        assert!(!proof.verify(params1, params2, &context, &y, &y1, &other));
+    }
+
+    #[test]
+    fn rekey_between_groups() {
+        rekey::<PallasEllipticCurve, VestaEllipticCurve>(&PALLAS_GROUP_PARAMS, &VESTA_GROUP_PARAMS);
+        rekey::<VestaEllipticCurve, PallasEllipticCurve>(&VESTA_GROUP_PARAMS, &PALLAS_GROUP_PARAMS);
+        rekey::<DiscreteLog, PallasEllipticCurve>(&DLOG_GROUP_PARAMS, &PALLAS_GROUP_PARAMS);
+    }
+}
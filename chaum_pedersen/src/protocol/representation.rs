@@ -0,0 +1,150 @@
+//! Okamoto's proof of knowledge of a representation `(x1, ..., xn)` of `y = g1^x1 · ... · gn^xn`.
+//!
+//! With `n = 1` this is Schnorr's proof of knowledge of a discrete log, with `n = 2` and the
+//! generators `g`, `h` it proves knowledge of the opening of a Pedersen commitment.
+
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+
+/// A non-interactive proof of knowledge of a representation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepresentationProof<G: CyclicGroup> {
+    /// The prover's commitment `t = g1^k1 · ... · gn^kn`.
+    pub t: G::Element,
+    /// The responses `si = ki + c·xi`.
+    pub s: Vec<G::Scalar>,
+}
+
+impl<G: CyclicGroup> RepresentationProof<G> {
+    /// Computes the public value `y = g1^x1 · ... · gn^xn`.
+    pub fn statement(
+        params: &GroupParams<G::Element>,
+        generators: &[G::Element],
+        secrets: &[G::Scalar],
+    ) -> Result<G::Element> {
+        if generators.is_empty() || generators.len() != secrets.len() {
+            return Err(ProtocolError::LengthMismatch.into());
+        }
+        Ok(G::multi_exp(params, generators, secrets))
+    }
+
+    /// Proves knowledge of `secrets` such that `y` is their representation in `generators`.
+    pub fn prove(
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        generators: &[G::Element],
+        y: &G::Element,
+        secrets: &[G::Scalar],
+    ) -> Result<Self> {
+        if generators.is_empty() || generators.len() != secrets.len() {
+            return Err(ProtocolError::LengthMismatch.into());
+        }
+        let k: Vec<G::Scalar> = generators
+            .iter()
+            .map(|_| G::random_scalar(params))
+            .collect();
+        let t = G::multi_exp(params, generators, &k);
+
+        let c = Self::challenge(params, transcript, generators, y, &t);
+
+        let s = k
+            .iter()
+            .zip(secrets)
+            .map(|(k, x)| G::scalar_add(params, k, &G::scalar_mul(params, &c, x)))
+            .collect();
+        Ok(RepresentationProof { t, s })
+    }
+
+    /// Verifies the proof against the public value `y` and the `generators`.
+    pub fn verify(
+        &self,
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        generators: &[G::Element],
+        y: &G::Element,
+    ) -> bool {
+        if generators.is_empty()
+            || generators.len() != self.s.len()
+            || !G::contains(params, &self.t)
+            || !G::contains(params, y)
+        {
+            return false;
+        }
+        let c = Self::challenge(params, transcript, generators, y, &self.t);
+
+        let lhs = G::multi_exp(params, generators, &self.s);
+        let rhs = G::op(params, &self.t, &G::exp(params, y, &c));
+        lhs == rhs
+    }
+
+    fn challenge(
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        generators: &[G::Element],
+        y: &G::Element,
+        t: &G::Element,
+    ) -> G::Scalar {
+        transcript.append_message(b"protocol", b"representation");
+        for generator in generators {
+            transcript.append_element::<G>(b"generator", generator);
+        }
+        transcript.append_element::<G>(b"y", y);
+        transcript.append_element::<G>(b"t", t);
+        transcript.challenge_scalar::<G>(params, b"c")
+    }
+}
+
+impl<G: CyclicGroup> IntoBytes<RepresentationProof<G>> for RepresentationProof<G> {
+    fn to(t: &RepresentationProof<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put(&mut out, &t.t);
+        encoding::put_all(&mut out, &t.s);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<RepresentationProof<G>> for RepresentationProof<G> {
+    fn from(bytes: &[u8]) -> Result<RepresentationProof<G>> {
+        let mut reader = Reader::new(bytes);
+        let proof = RepresentationProof {
+            t: reader.take()?,
+            s: reader.take_all()?,
+        };
+        reader.finish()?;
+        Ok(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::constants::PALLAS_GROUP_PARAMS;
+    use crate::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+
+    #[test]
+    fn representation_bound_to_transcript() {
+        let params = PALLAS_GROUP_PARAMS.to_owned();
+        let generators = vec![params.g, params.h];
+        let secrets = vec![
+            PallasEllipticCurve::random_scalar(&params),
+            PallasEllipticCurve::random_scalar(&params),
+        ];
+        let y =
+            RepresentationProof::<PallasEllipticCurve>::statement(&params, &generators, &secrets)
+                .unwrap();
+        let proof = RepresentationProof::<PallasEllipticCurve>::prove(
+            &params,
+            &mut Transcript::new(b"login"),
+            &generators,
+            &y,
+            &secrets,
+        )
+        .unwrap();
+        assert!(!proof.verify(&params, &mut Transcript::new(b"signup"), &generators, &y));
+    }
+}
@@ -0,0 +1,122 @@
+//! Linkable ring signatures: a signature by "one of the members of the ring", where signatures
+//! produced with the same key can be linked together through their key image.
+//!
+//! The signer with `yj = g^x` publishes the key image `I = H(yj)^x` and proves with an OR proof
+//! over the ring that for some `i`, `log_g yi == log_H(yi) I`. The key image only depends on the
+//! signer's key, so a second signature by the same member is detected, yet nothing ties it to `yj`.
+//! Signatures are bound to a topic (e.g. a poll or an epoch), so a verifier can allow one signature
+//! per member and topic.
+
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::or_proof::{OrProof, Statement};
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+
+/// Domain separation tag of the hash of public keys to key image bases.
+pub const HASH_TO_GROUP_DOMAIN: &str = "chaum-pedersen-ring-key-image";
+
+/// A linkable ring signature.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RingSignature<G: CyclicGroup> {
+    /// `I = H(y)^x`, the same for every signature of a key.
+    pub key_image: G::Element,
+    pub proof: OrProof<G>,
+}
+
+impl<G: CyclicGroup> RingSignature<G> {
+    /// Signs `message` on `topic` as a member of `ring`, `x` being the secret key of `ring[index]`.
+    pub fn sign(
+        params: &GroupParams<G::Element>,
+        ring: &[G::Element],
+        index: usize,
+        x: &G::Scalar,
+        topic: &[u8],
+        message: &[u8],
+    ) -> Result<Self> {
+        let signer = ring.get(index).ok_or(ProtocolError::LengthMismatch)?;
+        let key_image = G::exp(params, &key_image_base::<G>(params, signer), x);
+        let statements = Self::statements(params, ring, &key_image);
+        let proof = OrProof::prove(
+            params,
+            &mut transcript::<G>(&key_image, topic, message),
+            &statements,
+            index,
+            x,
+        )?;
+        Ok(RingSignature { key_image, proof })
+    }
+
+    pub fn verify(
+        &self,
+        params: &GroupParams<G::Element>,
+        ring: &[G::Element],
+        topic: &[u8],
+        message: &[u8],
+    ) -> bool {
+        if self.key_image == G::identity(params) || !G::contains(params, &self.key_image) {
+            return false;
+        }
+        let statements = Self::statements(params, ring, &self.key_image);
+        self.proof.verify(
+            params,
+            &mut transcript::<G>(&self.key_image, topic, message),
+            &statements,
+        )
+    }
+
+    /// Whether both signatures were produced with the same key.
+    pub fn is_linked(&self, other: &Self) -> bool {
+        self.key_image == other.key_image
+    }
+
+    /// One DLEQ statement `(g, yi), (H(yi), I)` per member of the ring.
+    fn statements(
+        params: &GroupParams<G::Element>,
+        ring: &[G::Element],
+        key_image: &G::Element,
+    ) -> Vec<Statement<G>> {
+        ring.iter()
+            .map(|y| Statement {
+                bases: vec![params.g.clone(), key_image_base::<G>(params, y)],
+                values: vec![y.clone(), key_image.clone()],
+            })
+            .collect()
+    }
+}
+
+fn key_image_base<G: CyclicGroup>(params: &GroupParams<G::Element>, y: &G::Element) -> G::Element {
+    G::hash_to_element(params, HASH_TO_GROUP_DOMAIN, &G::Element::to(y))
+}
+
+fn transcript<G: CyclicGroup>(key_image: &G::Element, topic: &[u8], message: &[u8]) -> Transcript {
+    let mut transcript = Transcript::new(b"linkable-ring-signature");
+    transcript.append_message(b"topic", topic);
+    transcript.append_element::<G>(b"key-image", key_image);
+    transcript.append_message(b"message", message);
+    transcript
+}
+
+impl<G: CyclicGroup> IntoBytes<RingSignature<G>> for RingSignature<G> {
+    fn to(t: &RingSignature<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put(&mut out, &t.key_image);
+        encoding::put(&mut out, &t.proof);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<RingSignature<G>> for RingSignature<G> {
+    fn from(bytes: &[u8]) -> Result<RingSignature<G>> {
+        let mut reader = Reader::new(bytes);
+        let signature = RingSignature {
+            key_image: reader.take()?,
+            proof: reader.take()?,
+        };
+        reader.finish()?;
+        Ok(signature)
+    }
+}
@@ -0,0 +1,103 @@
+//! Schnorr signatures under the keys users authenticate with, `y1 = g^x`.
+//!
+//! A signature is the Fiat-Shamir transform of the proof of knowledge of `x`,
+//! with the message absorbed into the challenge.
+
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+
+/// A signature `(c, s)` with `c = H(g, y, g^k, m)` and `s = k + c·x`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature<G: CyclicGroup> {
+    pub c: G::Scalar,
+    pub s: G::Scalar,
+}
+
+impl<G: CyclicGroup> Signature<G> {
+    /// Signs `message` with the secret `x`.
+    pub fn sign(params: &GroupParams<G::Element>, x: &G::Scalar, message: &[u8]) -> Self {
+        Self::sign_in_context(params, &Transcript::new(b"schnorr-signature"), x, message)
+    }
+
+    /// Signs `message` with the secret `x`, the signature only verifying in the same `context`,
+    /// e.g. a transcript holding the signer's name and a nonce of the verifier.
+    pub fn sign_in_context(
+        params: &GroupParams<G::Element>,
+        context: &Transcript,
+        x: &G::Scalar,
+        message: &[u8],
+    ) -> Self {
+        let y = G::exp(params, &params.g, x);
+        let k = G::random_scalar(params);
+        let t = G::exp(params, &params.g, &k);
+        let c = Self::challenge(params, context, &y, &t, message);
+        let s = G::scalar_add(params, &k, &G::scalar_mul(params, &c, x));
+        Signature { c, s }
+    }
+
+    /// Verifies the signature of `message` under the public key `y = g^x`.
+    pub fn verify(&self, params: &GroupParams<G::Element>, y: &G::Element, message: &[u8]) -> bool {
+        self.verify_in_context(params, &Transcript::new(b"schnorr-signature"), y, message)
+    }
+
+    /// Verifies the signature of `message` made in `context` under the public key `y = g^x`.
+    pub fn verify_in_context(
+        &self,
+        params: &GroupParams<G::Element>,
+        context: &Transcript,
+        y: &G::Element,
+        message: &[u8],
+    ) -> bool {
+        if !G::contains(params, y) {
+            return false;
+        }
+        // recompute the commitment t = g^s · y^-c
+        let t = G::multi_exp(
+            params,
+            &[params.g.clone(), y.clone()],
+            &[self.s.clone(), G::scalar_neg(params, &self.c)],
+        );
+        Self::challenge(params, context, y, &t, message) == self.c
+    }
+
+    fn challenge(
+        params: &GroupParams<G::Element>,
+        context: &Transcript,
+        y: &G::Element,
+        t: &G::Element,
+        message: &[u8],
+    ) -> G::Scalar {
+        let mut transcript = context.clone();
+        transcript.append_message(b"signature", b"schnorr");
+        transcript.append_element::<G>(b"g", &params.g);
+        transcript.append_element::<G>(b"y", y);
+        transcript.append_element::<G>(b"t", t);
+        transcript.append_message(b"message", message);
+        transcript.challenge_scalar::<G>(params, b"c")
+    }
+}
+
+impl<G: CyclicGroup> IntoBytes<Signature<G>> for Signature<G> {
+    fn to(t: &Signature<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put(&mut out, &t.c);
+        encoding::put(&mut out, &t.s);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<Signature<G>> for Signature<G> {
+    fn from(bytes: &[u8]) -> Result<Signature<G>> {
+        let mut reader = Reader::new(bytes);
+        let signature = Signature {
+            c: reader.take()?,
+            s: reader.take()?,
+        };
+        reader.finish()?;
+        Ok(signature)
+    }
+}
@@ -0,0 +1,154 @@
+//! Wrappers of secret values, such as secret keys `x` and commitment randomness `k`.
+//!
+//! A `Secret` is overwritten with zeros when dropped, prints as redacted in `Debug`, and is not
+//! `Clone`, so copies can only be made explicitly from `expose`.
+
+use num_bigint::BigUint;
+use pasta_curves::group::ff::Field;
+use pasta_curves::{Fp, Fq};
+use std::fmt;
+use std::hint::black_box;
+use zeroize::Zeroize;
+
+/// Values that can be overwritten with zeros in place.
+///
+/// `zeroize::Zeroize` cannot be implemented on the foreign number types, hence this trait.
+pub trait Wipe {
+    fn wipe(&mut self);
+}
+
+impl Wipe for BigUint {
+    fn wipe(&mut self) {
+        // clearing from the lowest bit up leaves every digit at zero before the number is
+        // normalized, so no copy of the value is left behind by a reallocation
+        for bit in 0..self.bits() {
+            self.set_bit(bit, false);
+        }
+        black_box(self);
+    }
+}
+
+impl Wipe for Fp {
+    fn wipe(&mut self) {
+        *self = Fp::ZERO;
+        black_box(self);
+    }
+}
+
+impl Wipe for Fq {
+    fn wipe(&mut self) {
+        *self = Fq::ZERO;
+        black_box(self);
+    }
+}
+
+impl<const N: usize> Wipe for [u8; N] {
+    fn wipe(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Wipe for Vec<u8> {
+    fn wipe(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Wipe for String {
+    fn wipe(&mut self) {
+        self.zeroize();
+    }
+}
+
+/// A secret value, wiped when dropped.
+pub struct Secret<T: Wipe>(T);
+
+/// The randomness of a commitment, which must never be used with two challenges.
+pub type Nonce<T> = Secret<T>;
+
+impl<T: Wipe> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Secret(value)
+    }
+
+    /// The secret value, for computations with it.
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    /// The secret value, for filling it in place.
+    pub fn expose_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Wipe> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Secret::new(value)
+    }
+}
+
+impl<T: Wipe> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.wipe();
+    }
+}
+
+impl<T: Wipe> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(<redacted>)")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use num_traits::Zero;
+
+    #[test]
+    fn secrets_are_wiped() {
+        let mut x: BigUint = BigUint::from(0xdead_beef_u64) << 1000;
+        x.wipe();
+        assert!(x.is_zero());
+
+        let mut x = Fp::from(7);
+        x.wipe();
+        assert_eq!(x, Fp::ZERO);
+
+        let mut digest = [0xffu8; 64];
+        digest.wipe();
+        assert_eq!(digest, [0u8; 64]);
+    }
+
+    #[test]
+    fn secrets_are_redacted() {
+        let x = Secret::new(Fq::from(7));
+        assert_eq!(format!("{:?}", x), "Secret(<redacted>)");
+        assert_eq!(x.expose(), &Fq::from(7));
+    }
+
+    #[test]
+    fn secrets_of_schemes_are_redacted() {
+        use crate::protocol::constants::PALLAS_GROUP_PARAMS;
+        use crate::protocol::elgamal::KeyPair;
+        use crate::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+        use crate::protocol::pedersen::PedersenCommitment;
+        use crate::protocol::shamir::Share;
+        use crate::protocol::wire::Envelope;
+
+        let params = &PALLAS_GROUP_PARAMS;
+        let x = Fq::from(0xdead_beef);
+        let hidden = format!("{:?}", x);
+        let keys = KeyPair::<PallasEllipticCurve>::from_secret(params, x);
+        let share = Share::<PallasEllipticCurve> { index: 1, value: x };
+        let (_, opening) = PedersenCommitment::<PallasEllipticCurve>::commit(params, &x);
+        for debug in [
+            format!("{:?}", keys),
+            format!("{:?}", share),
+            format!("{:?}", opening),
+            format!("{:?}", Envelope::secret::<PallasEllipticCurve>(&x)),
+        ] {
+            assert!(!debug.contains(&hidden), "{} reveals the secret", debug);
+        }
+    }
+}
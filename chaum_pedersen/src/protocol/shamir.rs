@@ -0,0 +1,172 @@
+//! Shamir secret sharing of scalars, the basis of the threshold schemes.
+//!
+//! A secret `s` is the constant term of a random polynomial `f` of degree `t - 1`,
+//! the share of party `i` (numbered from `1`) is `f(i)`. Any `t` shares recover `s`
+//! by Lagrange interpolation at `0`, fewer reveal nothing about it.
+
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+use std::fmt;
+
+/// A share `f(index)` of a secret.
+#[derive(Clone, PartialEq)]
+pub struct Share<G: CyclicGroup> {
+    pub index: u64,
+    pub value: G::Scalar,
+}
+
+/// Prints the index of the share, not its value.
+impl<G: CyclicGroup> fmt::Debug for Share<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Share")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A polynomial over the scalars, lowest degree coefficient first.
+#[derive(Clone, PartialEq)]
+pub struct Polynomial<G: CyclicGroup> {
+    pub coefficients: Vec<G::Scalar>,
+}
+
+/// Prints the degree only, the constant coefficient being the shared secret.
+impl<G: CyclicGroup> fmt::Debug for Polynomial<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Polynomial")
+            .field("degree", &self.coefficients.len().saturating_sub(1))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G: CyclicGroup> Polynomial<G> {
+    /// A random polynomial of degree `t - 1` with the constant term `secret`.
+    pub fn random(params: &GroupParams<G::Element>, secret: &G::Scalar, t: usize) -> Self {
+        let coefficients = std::iter::once(secret.clone())
+            .chain((1..t).map(|_| G::random_scalar(params)))
+            .collect();
+        Polynomial { coefficients }
+    }
+
+    pub fn evaluate(&self, params: &GroupParams<G::Element>, x: u64) -> G::Scalar {
+        let x = G::scalar_from_u64(params, x);
+        // Horner's rule
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(G::scalar_zero(params), |acc, coefficient| {
+                G::scalar_add(params, &G::scalar_mul(params, &acc, &x), coefficient)
+            })
+    }
+
+    /// The shares of the parties `1..=n`.
+    pub fn shares(&self, params: &GroupParams<G::Element>, n: u64) -> Vec<Share<G>> {
+        (1..=n)
+            .map(|index| Share {
+                index,
+                value: self.evaluate(params, index),
+            })
+            .collect()
+    }
+}
+
+/// Splits `secret` into `n` shares, any `t` of which recover it.
+pub fn split<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    secret: &G::Scalar,
+    t: usize,
+    n: usize,
+) -> Result<Vec<Share<G>>> {
+    check_threshold(t, n)?;
+    Ok(Polynomial::<G>::random(params, secret, t).shares(params, n as u64))
+}
+
+/// Recovers the secret from shares with distinct indices, all of which are used.
+pub fn recover<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    shares: &[Share<G>],
+) -> Result<G::Scalar> {
+    let indices: Vec<u64> = shares.iter().map(|share| share.index).collect();
+    shares
+        .iter()
+        .try_fold(G::scalar_zero(params), |acc, share| {
+            let lambda = lagrange_coefficient::<G>(params, &indices, share.index)?;
+            Ok(G::scalar_add(
+                params,
+                &acc,
+                &G::scalar_mul(params, &lambda, &share.value),
+            ))
+        })
+}
+
+/// The Lagrange coefficient `Π j / (j - i)` of `index` for interpolating at `0` from `indices`.
+pub fn lagrange_coefficient<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    indices: &[u64],
+    index: u64,
+) -> Result<G::Scalar> {
+    let i = G::scalar_from_u64(params, index);
+    let (numerator, denominator) = indices.iter().filter(|j| **j != index).fold(
+        (G::scalar_from_u64(params, 1), G::scalar_from_u64(params, 1)),
+        |(numerator, denominator), j| {
+            let j = G::scalar_from_u64(params, *j);
+            (
+                G::scalar_mul(params, &numerator, &j),
+                G::scalar_mul(params, &denominator, &G::scalar_sub(params, &j, &i)),
+            )
+        },
+    );
+    // the denominator is zero only for duplicated indices
+    let inverse = G::scalar_invert(params, &denominator).ok_or(ProtocolError::InvalidShareIndex)?;
+    Ok(G::scalar_mul(params, &numerator, &inverse))
+}
+
+pub fn check_threshold(t: usize, n: usize) -> Result<()> {
+    if t == 0 || t > n {
+        return Err(ProtocolError::InvalidThreshold.into());
+    }
+    Ok(())
+}
+
+impl<G: CyclicGroup> IntoBytes<Share<G>> for Share<G> {
+    fn to(t: &Share<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put_u64(&mut out, t.index);
+        encoding::put(&mut out, &t.value);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<Share<G>> for Share<G> {
+    fn from(bytes: &[u8]) -> Result<Share<G>> {
+        let mut reader = Reader::new(bytes);
+        let share = Share {
+            index: reader.take_u64()?,
+            value: reader.take()?,
+        };
+        reader.finish()?;
+        Ok(share)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::constants::DLOG_GROUP_PARAMS;
+    use crate::protocol::discrete_log::DiscreteLog;
+
+    #[test]
+    fn shamir_recovery() {
+        let params = DLOG_GROUP_PARAMS.to_owned();
+        let secret = DiscreteLog::random_scalar(&params);
+        let shares = split::<DiscreteLog>(&params, &secret, 3, 5).unwrap();
+        assert_eq!(recover(&params, &shares[1..4]).unwrap(), secret);
+        assert_eq!(recover(&params, &shares).unwrap(), secret);
+        assert_ne!(recover(&params, &shares[..2]).unwrap(), secret);
+        assert!(split::<DiscreteLog>(&params, &secret, 6, 5).is_err());
+    }
+}
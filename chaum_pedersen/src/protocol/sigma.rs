@@ -0,0 +1,275 @@
+//! Sigma protocols for any statement made of linear relations between discrete logs.
+//!
+//! A statement is declared as a `LinearRelation`: secret scalars, and equations
+//! `lhs = base1^xi · base2^xj · ...` over them. For example the Chaum-Pedersen statement
+//! together with a Pedersen commitment to the same secret reads
+//!
+//! ```text
+//! y1 = g^x,  y2 = h^x,  C = g^x · h^r
+//! ```
+//!
+//! From the declaration alone the relation derives the interactive prover and verifier,
+//! the honest-verifier simulator, and the non-interactive `SigmaProof` via Fiat-Shamir.
+
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::secret::{Nonce, Wipe};
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+
+/// A secret scalar of a relation, identified by its declaration order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScalarVar(usize);
+
+/// An equation `lhs = Π base^x` of a relation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Equation<G: CyclicGroup> {
+    pub lhs: G::Element,
+    pub terms: Vec<(ScalarVar, G::Element)>,
+}
+
+/// A statement made of linear relations between discrete logs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearRelation<G: CyclicGroup> {
+    scalars: usize,
+    equations: Vec<Equation<G>>,
+}
+
+/// A non-interactive proof `(c, s)` of knowledge of a witness of a relation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SigmaProof<G: CyclicGroup> {
+    pub c: G::Scalar,
+    /// The responses `si = ki + c·xi`, one per scalar of the relation.
+    pub s: Vec<G::Scalar>,
+}
+
+impl<G: CyclicGroup> Default for LinearRelation<G> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<G: CyclicGroup> LinearRelation<G> {
+    pub fn new() -> Self {
+        LinearRelation {
+            scalars: 0,
+            equations: Vec::new(),
+        }
+    }
+
+    /// Declares a new secret scalar.
+    pub fn scalar(&mut self) -> ScalarVar {
+        self.scalars += 1;
+        ScalarVar(self.scalars - 1)
+    }
+
+    /// Declares the equation `lhs = Π base^x` over the `terms` `(x, base)`.
+    pub fn equation(&mut self, lhs: G::Element, terms: &[(ScalarVar, G::Element)]) -> Result<()> {
+        if terms.iter().any(|(x, _)| x.0 >= self.scalars) {
+            return Err(ProtocolError::UnknownVariable.into());
+        }
+        self.equations.push(Equation {
+            lhs,
+            terms: terms.to_vec(),
+        });
+        Ok(())
+    }
+
+    pub fn scalars(&self) -> usize {
+        self.scalars
+    }
+
+    pub fn equations(&self) -> &[Equation<G>] {
+        &self.equations
+    }
+
+    /// Checks that `witness`, one scalar per declared variable, satisfies every equation.
+    pub fn is_satisfied(&self, params: &GroupParams<G::Element>, witness: &[G::Scalar]) -> bool {
+        witness.len() == self.scalars
+            && self
+                .images(params, &witness.iter().collect::<Vec<_>>())
+                .iter()
+                .zip(&self.equations)
+                .all(|(image, equation)| *image == equation.lhs)
+    }
+
+    /// The first move of the prover: commitments to fresh nonces, one per equation,
+    /// and the nonces to answer the challenge with.
+    pub fn commit(
+        &self,
+        params: &GroupParams<G::Element>,
+    ) -> (Vec<G::Element>, Vec<Nonce<G::Scalar>>) {
+        let nonces: Vec<Nonce<G::Scalar>> = (0..self.scalars)
+            .map(|_| Nonce::new(G::random_scalar(params)))
+            .collect();
+        let exposed: Vec<&G::Scalar> = nonces.iter().map(Nonce::expose).collect();
+        (self.images(params, &exposed), nonces)
+    }
+
+    /// The last move of the prover: the responses `si = ki + c·xi`.
+    pub fn respond(
+        &self,
+        params: &GroupParams<G::Element>,
+        nonces: &[Nonce<G::Scalar>],
+        c: &G::Scalar,
+        witness: &[G::Scalar],
+    ) -> Result<Vec<G::Scalar>> {
+        if nonces.len() != self.scalars || witness.len() != self.scalars {
+            return Err(ProtocolError::LengthMismatch.into());
+        }
+        Ok(nonces
+            .iter()
+            .zip(witness)
+            .map(|(k, x)| G::scalar_add(params, k.expose(), &G::scalar_mul(params, c, x)))
+            .collect())
+    }
+
+    /// The verifier's check of an interactive transcript, `Π base^s = t · lhs^c` for every equation.
+    pub fn check(
+        &self,
+        params: &GroupParams<G::Element>,
+        commitments: &[G::Element],
+        c: &G::Scalar,
+        responses: &[G::Scalar],
+    ) -> bool {
+        responses.len() == self.scalars
+            && commitments.len() == self.equations.len()
+            && self.is_well_formed(params)
+            && self.commitments(params, c, responses) == commitments
+    }
+
+    /// The honest-verifier simulator: an accepting transcript for the challenge `c`,
+    /// produced without the witness.
+    pub fn simulate(
+        &self,
+        params: &GroupParams<G::Element>,
+        c: &G::Scalar,
+    ) -> (Vec<G::Element>, Vec<G::Scalar>) {
+        let responses: Vec<G::Scalar> = (0..self.scalars)
+            .map(|_| G::random_scalar(params))
+            .collect();
+        (self.commitments(params, c, &responses), responses)
+    }
+
+    /// `Π base^xi` for every equation, the copies of the scalars are wiped as they may be secret.
+    fn images(&self, params: &GroupParams<G::Element>, scalars: &[&G::Scalar]) -> Vec<G::Element> {
+        self.equations
+            .iter()
+            .map(|equation| {
+                let (mut exponents, bases): (Vec<G::Scalar>, Vec<G::Element>) = equation
+                    .terms
+                    .iter()
+                    .map(|(x, base)| (scalars[x.0].clone(), base.clone()))
+                    .unzip();
+                let image = G::multi_exp(params, &bases, &exponents);
+                exponents.iter_mut().for_each(Wipe::wipe);
+                image
+            })
+            .collect()
+    }
+
+    /// The commitments `Π base^si · lhs^-c` a valid response `s` to `c` has to match.
+    fn commitments(
+        &self,
+        params: &GroupParams<G::Element>,
+        c: &G::Scalar,
+        responses: &[G::Scalar],
+    ) -> Vec<G::Element> {
+        let minus_c = G::scalar_neg(params, c);
+        self.images(params, &responses.iter().collect::<Vec<_>>())
+            .iter()
+            .zip(&self.equations)
+            .map(|(image, equation)| G::op(params, image, &G::exp(params, &equation.lhs, &minus_c)))
+            .collect()
+    }
+
+    fn is_well_formed(&self, params: &GroupParams<G::Element>) -> bool {
+        self.equations.iter().all(|equation| {
+            G::contains(params, &equation.lhs)
+                && equation
+                    .terms
+                    .iter()
+                    .all(|(_, base)| G::contains(params, base))
+        })
+    }
+
+    fn append_to(&self, transcript: &mut Transcript) {
+        transcript.append_message(b"scalars", &(self.scalars as u64).to_be_bytes());
+        for equation in &self.equations {
+            transcript.append_element::<G>(b"lhs", &equation.lhs);
+            for (x, base) in &equation.terms {
+                transcript.append_message(b"scalar", &(x.0 as u64).to_be_bytes());
+                transcript.append_element::<G>(b"base", base);
+            }
+        }
+    }
+}
+
+impl<G: CyclicGroup> SigmaProof<G> {
+    /// Proves knowledge of `witness`, one scalar per declared variable of `relation`.
+    pub fn prove(
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        relation: &LinearRelation<G>,
+        witness: &[G::Scalar],
+    ) -> Result<Self> {
+        if witness.len() != relation.scalars {
+            return Err(ProtocolError::LengthMismatch.into());
+        }
+        let (commitments, nonces) = relation.commit(params);
+        let c = Self::challenge(params, transcript, relation, &commitments);
+        let s = relation.respond(params, &nonces, &c, witness)?;
+        Ok(SigmaProof { c, s })
+    }
+
+    pub fn verify(
+        &self,
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        relation: &LinearRelation<G>,
+    ) -> bool {
+        if self.s.len() != relation.scalars || !relation.is_well_formed(params) {
+            return false;
+        }
+        let commitments = relation.commitments(params, &self.c, &self.s);
+        Self::challenge(params, transcript, relation, &commitments) == self.c
+    }
+
+    fn challenge(
+        params: &GroupParams<G::Element>,
+        transcript: &mut Transcript,
+        relation: &LinearRelation<G>,
+        commitments: &[G::Element],
+    ) -> G::Scalar {
+        transcript.append_message(b"protocol", b"linear-relation");
+        relation.append_to(transcript);
+        for t in commitments {
+            transcript.append_element::<G>(b"t", t);
+        }
+        transcript.challenge_scalar::<G>(params, b"c")
+    }
+}
+
+impl<G: CyclicGroup> IntoBytes<SigmaProof<G>> for SigmaProof<G> {
+    fn to(t: &SigmaProof<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put(&mut out, &t.c);
+        encoding::put_all(&mut out, &t.s);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<SigmaProof<G>> for SigmaProof<G> {
+    fn from(bytes: &[u8]) -> Result<SigmaProof<G>> {
+        let mut reader = Reader::new(bytes);
+        let proof = SigmaProof {
+            c: reader.take()?,
+            s: reader.take_all()?,
+        };
+        reader.finish()?;
+        Ok(proof)
+    }
+}
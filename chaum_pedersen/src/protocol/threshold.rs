@@ -0,0 +1,199 @@
+//! Threshold ElGamal decryption: any `t` out of `n` trustees jointly decrypt.
+//!
+//! Every trustee holds a Shamir share `xi` of the secret key and publishes `yi = g^xi`.
+//! A partial decryption `di = c1^xi` carries a Chaum-Pedersen proof of consistency with `yi`,
+//! the combiner discards the shares whose proof fails and interpolates `c1^x = Π di^λi`.
+
+use crate::protocol::elgamal::{Ciphertext, DecryptionShare, KeyPair};
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::shamir::{self, Share};
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+
+/// The public part of a threshold key.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThresholdPublicKey<G: CyclicGroup> {
+    /// The number of trustees needed to decrypt.
+    pub t: usize,
+    /// The public key `y = g^x` ciphertexts are encrypted under.
+    pub y: G::Element,
+    /// The public key shares `yi = g^xi`, indexed from `1`.
+    pub shares: Vec<G::Element>,
+}
+
+/// The secret key share of the trustee `index`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyShare<G: CyclicGroup> {
+    pub index: u64,
+    pub keys: KeyPair<G>,
+}
+
+/// A partial decryption of the trustee `index`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartialDecryption<G: CyclicGroup> {
+    pub index: u64,
+    pub share: DecryptionShare<G>,
+}
+
+/// The plaintext recovered by the combiner, together with the trustees whose share was discarded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Combined<G: CyclicGroup> {
+    pub plaintext: G::Element,
+    pub rejected: Vec<u64>,
+}
+
+/// Generates a key with a trusted dealer and splits it among `n` trustees, `t` of which can decrypt.
+pub fn deal<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    t: usize,
+    n: usize,
+) -> Result<(ThresholdPublicKey<G>, Vec<KeyShare<G>>)> {
+    let x = G::random_scalar(params);
+    let shares = shamir::split::<G>(params, &x, t, n)?;
+    let y = G::exp(params, &params.g, &x);
+    Ok(from_shares(params, t, y, shares))
+}
+
+/// Assembles a threshold key from already distributed Shamir shares of the secret behind `y`.
+pub fn from_shares<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    t: usize,
+    y: G::Element,
+    shares: Vec<Share<G>>,
+) -> (ThresholdPublicKey<G>, Vec<KeyShare<G>>) {
+    let key_shares: Vec<KeyShare<G>> = shares
+        .into_iter()
+        .map(|share| KeyShare {
+            index: share.index,
+            keys: KeyPair::from_secret(params, share.value),
+        })
+        .collect();
+    let public_key = ThresholdPublicKey {
+        t,
+        y,
+        shares: key_shares
+            .iter()
+            .map(|share| share.keys.y.clone())
+            .collect(),
+    };
+    (public_key, key_shares)
+}
+
+impl<G: CyclicGroup> KeyShare<G> {
+    /// Computes the partial decryption `c1^xi` with its proof, bound to the `context` transcript.
+    pub fn partial_decryption(
+        &self,
+        params: &GroupParams<G::Element>,
+        context: &Transcript,
+        ciphertext: &Ciphertext<G>,
+    ) -> PartialDecryption<G> {
+        let share = self.keys.decryption_share(
+            params,
+            &mut share_transcript(context, self.index),
+            ciphertext,
+        );
+        PartialDecryption {
+            index: self.index,
+            share,
+        }
+    }
+}
+
+impl<G: CyclicGroup> ThresholdPublicKey<G> {
+    /// Checks a partial decryption against the public key share of its trustee.
+    pub fn verify_partial(
+        &self,
+        params: &GroupParams<G::Element>,
+        context: &Transcript,
+        ciphertext: &Ciphertext<G>,
+        partial: &PartialDecryption<G>,
+    ) -> bool {
+        let Some(y) = self.share(partial.index) else {
+            return false;
+        };
+        partial.share.verify(
+            params,
+            &mut share_transcript(context, partial.index),
+            y,
+            ciphertext,
+        )
+    }
+
+    /// Verifies the partial decryptions, discards the invalid and duplicated ones,
+    /// and decrypts with the first `t` valid ones.
+    pub fn combine(
+        &self,
+        params: &GroupParams<G::Element>,
+        context: &Transcript,
+        ciphertext: &Ciphertext<G>,
+        partials: &[PartialDecryption<G>],
+    ) -> Result<Combined<G>> {
+        let mut valid: Vec<&PartialDecryption<G>> = Vec::new();
+        let mut rejected = Vec::new();
+        for partial in partials {
+            if valid.iter().any(|v| v.index == partial.index) {
+                continue;
+            }
+            if self.verify_partial(params, context, ciphertext, partial) {
+                valid.push(partial);
+            } else {
+                rejected.push(partial.index);
+            }
+        }
+        if valid.len() < self.t {
+            return Err(ProtocolError::NotEnoughShares.into());
+        }
+        valid.truncate(self.t);
+
+        let indices: Vec<u64> = valid.iter().map(|partial| partial.index).collect();
+        let d = valid.iter().try_fold(G::identity(params), |acc, partial| {
+            let lambda = shamir::lagrange_coefficient::<G>(params, &indices, partial.index)?;
+            Ok::<_, anyhow::Error>(G::op(
+                params,
+                &acc,
+                &G::exp(params, &partial.share.d, &lambda),
+            ))
+        })?;
+        Ok(Combined {
+            plaintext: ciphertext.decrypt_with(params, &d),
+            rejected,
+        })
+    }
+
+    fn share(&self, index: u64) -> Option<&G::Element> {
+        let position = usize::try_from(index).ok()?.checked_sub(1)?;
+        self.shares.get(position)
+    }
+}
+
+fn share_transcript(context: &Transcript, index: u64) -> Transcript {
+    let mut transcript = context.clone();
+    transcript.append_message(b"protocol", b"threshold-elgamal");
+    transcript.append_message(b"index", &index.to_be_bytes());
+    transcript
+}
+
+impl<G: CyclicGroup> IntoBytes<PartialDecryption<G>> for PartialDecryption<G> {
+    fn to(t: &PartialDecryption<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put_u64(&mut out, t.index);
+        encoding::put(&mut out, &t.share);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<PartialDecryption<G>> for PartialDecryption<G> {
+    fn from(bytes: &[u8]) -> Result<PartialDecryption<G>> {
+        let mut reader = Reader::new(bytes);
+        let partial = PartialDecryption {
+            index: reader.take_u64()?,
+            share: reader.take()?,
+        };
+        reader.finish()?;
+        Ok(partial)
+    }
+}
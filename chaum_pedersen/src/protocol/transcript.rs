@@ -0,0 +1,89 @@
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::GroupParams;
+use crate::traits::IntoBytes;
+use sha2::{Digest, Sha512};
+
+/// A Fiat-Shamir transcript shared by the non-interactive proofs of the crate.
+///
+/// Every message is absorbed together with its label and length, so distinct sequences of
+/// messages can never produce the same challenge. Squeezed challenges are absorbed back,
+/// which lets one transcript drive several proofs in a row.
+#[derive(Clone)]
+pub struct Transcript {
+    hasher: Sha512,
+}
+
+impl Transcript {
+    /// Starts a transcript, `label` separates the domain of the protocol using it.
+    pub fn new(label: &[u8]) -> Self {
+        let mut transcript = Transcript {
+            hasher: Sha512::new(),
+        };
+        transcript.append_message(b"chaum-pedersen-transcript", label);
+        transcript
+    }
+
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        self.hasher.update((label.len() as u64).to_be_bytes());
+        self.hasher.update(label);
+        self.hasher.update((message.len() as u64).to_be_bytes());
+        self.hasher.update(message);
+    }
+
+    pub fn append_element<G: CyclicGroup>(&mut self, label: &[u8], element: &G::Element) {
+        self.append_message(label, &G::Element::to(element));
+    }
+
+    pub fn append_scalar<G: CyclicGroup>(&mut self, label: &[u8], scalar: &G::Scalar) {
+        self.append_message(label, &G::Scalar::to(scalar));
+    }
+
+    /// Appends the generators `g` and `h` the proof is bound to.
+    pub fn append_params<G: CyclicGroup>(&mut self, params: &GroupParams<G::Element>) {
+        self.append_element::<G>(b"g", &params.g);
+        self.append_element::<G>(b"h", &params.h);
+    }
+
+    /// Derives 64 uniform bytes from everything absorbed so far.
+    pub fn challenge_bytes(&mut self, label: &[u8]) -> [u8; 64] {
+        let mut hasher = self.hasher.clone();
+        hasher.update((label.len() as u64).to_be_bytes());
+        hasher.update(label);
+        let mut output = [0u8; 64];
+        output.copy_from_slice(&hasher.finalize());
+        self.append_message(label, &output);
+        output
+    }
+
+    /// Derives a challenge scalar, reducing the wide output modulo the group order.
+    pub fn challenge_scalar<G: CyclicGroup>(
+        &mut self,
+        params: &GroupParams<G::Element>,
+        label: &[u8],
+    ) -> G::Scalar {
+        G::scalar_from_wide_bytes(params, &self.challenge_bytes(label))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcript_challenges_depend_on_messages() {
+        let mut first = Transcript::new(b"test");
+        first.append_message(b"m", b"ab");
+        let mut second = Transcript::new(b"test");
+        second.append_message(b"m", b"a");
+        second.append_message(b"", b"b");
+        assert_ne!(first.challenge_bytes(b"c"), second.challenge_bytes(b"c"));
+    }
+
+    #[test]
+    fn transcript_challenges_chain() {
+        let mut transcript = Transcript::new(b"test");
+        let first = transcript.challenge_bytes(b"c");
+        let second = transcript.challenge_bytes(b"c");
+        assert_ne!(first, second);
+    }
+}
@@ -0,0 +1,205 @@
+//! Deterministic known-answer test vectors, for checking other implementations against this one.
+//!
+//! A vector runs the interactive protocol with `x`, `k` and `c` derived from a seed, and records
+//! every value with all the encodings of the artefacts involved. Replaying a vector regenerates it
+//! from its seed and parameters, so any change of the arithmetic or of an encoding is caught.
+
+use crate::enums::{EllipticCurve, Flavor, TextEncoding};
+use crate::protocol::armor;
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::transcript::Transcript;
+use crate::protocol::wire::{Bytes, Envelope, InteractiveProof, WIRE_VERSION};
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+const DESCRIPTION: &str = "Chaum-Pedersen known-answer vectors: y1 = g^x, y2 = h^x, r1 = g^k, \
+r2 = h^k and s = k + c·x mod q, with x, k and c derived from the seed. Values are hex encoded \
+with the byte encodings of the crate: big-endian integers for the discrete log flavor, \
+compressed points and little-endian scalars for elliptic curves.";
+
+/// A file of test vectors for one group.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TestVectors {
+    pub description: String,
+    pub wire_version: u16,
+    pub vectors: Vec<TestVector>,
+}
+
+/// One run of the interactive protocol, with the encodings of its artefacts.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TestVector {
+    pub seed: String,
+    pub flavor: Flavor,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curve: Option<EllipticCurve>,
+    pub g: Bytes,
+    pub h: Bytes,
+    pub p: Bytes,
+    pub q: Bytes,
+    pub x: Bytes,
+    pub k: Bytes,
+    pub c: Bytes,
+    pub s: Bytes,
+    pub y1: Bytes,
+    pub y2: Bytes,
+    pub r1: Bytes,
+    pub r2: Bytes,
+    pub encodings: Vec<Encodings>,
+}
+
+/// Every encoding of one artefact.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Encodings {
+    /// The kind of the artefact, as in its JSON envelope.
+    pub kind: String,
+    pub json: serde_json::Value,
+    pub cbor: Bytes,
+    pub binary: Bytes,
+    pub hex: String,
+    pub base64url: String,
+    pub armor: String,
+}
+
+impl TestVectors {
+    /// Vectors for every seed.
+    pub fn generate<G: CyclicGroup>(params: &GroupParams<G::Element>, seeds: &[&str]) -> Self {
+        TestVectors {
+            description: DESCRIPTION.to_string(),
+            wire_version: WIRE_VERSION,
+            vectors: seeds
+                .iter()
+                .map(|seed| TestVector::generate::<G>(params, seed))
+                .collect(),
+        }
+    }
+
+    pub fn check<G: CyclicGroup>(&self) -> Result<()> {
+        if self.wire_version != WIRE_VERSION {
+            return Err(ProtocolError::UnsupportedVersion.into());
+        }
+        self.vectors.iter().try_for_each(TestVector::check::<G>)
+    }
+}
+
+impl TestVector {
+    pub fn generate<G: CyclicGroup>(params: &GroupParams<G::Element>, seed: &str) -> Self {
+        let mut transcript = Transcript::new(b"chaum-pedersen-test-vectors");
+        transcript.append_params::<G>(params);
+        transcript.append_message(b"seed", seed.as_bytes());
+        let x = transcript.challenge_scalar::<G>(params, b"x");
+        let k = transcript.challenge_scalar::<G>(params, b"k");
+        let c = transcript.challenge_scalar::<G>(params, b"c");
+        let proof = InteractiveProof::<G> {
+            y1: G::exp(params, &params.g, &x),
+            y2: G::exp(params, &params.h, &x),
+            r1: G::exp(params, &params.g, &k),
+            r2: G::exp(params, &params.h, &k),
+            s: G::scalar_add(params, &k, &G::scalar_mul(params, &c, &x)),
+            c,
+        };
+        let envelopes = [
+            Envelope::params::<G>(params),
+            Envelope::secret::<G>(&x),
+            Envelope::statement::<G>(&proof.y1, &proof.y2),
+            Envelope::commitment::<G>(&proof.r1, &proof.r2),
+            Envelope::interactive_proof(&proof),
+        ];
+        TestVector {
+            seed: seed.to_string(),
+            flavor: G::FLAVOR,
+            curve: G::CURVE,
+            g: element::<G>(&params.g),
+            h: element::<G>(&params.h),
+            p: element::<G>(&params.p),
+            q: element::<G>(&params.q),
+            x: scalar::<G>(&x),
+            k: scalar::<G>(&k),
+            c: scalar::<G>(&proof.c),
+            s: scalar::<G>(&proof.s),
+            y1: element::<G>(&proof.y1),
+            y2: element::<G>(&proof.y2),
+            r1: element::<G>(&proof.r1),
+            r2: element::<G>(&proof.r2),
+            encodings: envelopes.iter().map(Encodings::new).collect(),
+        }
+    }
+
+    /// Regenerates the vector from its seed and parameters, and checks that every encoding
+    /// decodes back to its artefact and that the recorded transcript is accepted.
+    pub fn check<G: CyclicGroup>(&self) -> Result<()> {
+        if self.flavor != G::FLAVOR || self.curve != G::CURVE {
+            return Err(ProtocolError::GroupMismatch.into());
+        }
+        let params = GroupParams {
+            g: from_element::<G>(&self.g)?,
+            h: from_element::<G>(&self.h)?,
+            p: from_element::<G>(&self.p)?,
+            q: from_element::<G>(&self.q)?,
+        };
+        if *self != Self::generate::<G>(&params, &self.seed) {
+            return Err(ProtocolError::VectorMismatch.into());
+        }
+        for encodings in &self.encodings {
+            encodings.check()?;
+        }
+        let proof = self
+            .encodings
+            .iter()
+            .find(|encodings| encodings.kind == "interactive_proof")
+            .ok_or(ProtocolError::VectorMismatch)?;
+        let proof = Envelope::from_json(&proof.json.to_string())?.to_interactive_proof::<G>()?;
+        if !proof.verify(&params) {
+            return Err(ProtocolError::InvalidProof.into());
+        }
+        Ok(())
+    }
+}
+
+impl Encodings {
+    fn new(envelope: &Envelope) -> Self {
+        let json = serde_json::to_value(envelope).unwrap_or_default();
+        Encodings {
+            kind: json["content"]["kind"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            cbor: Bytes(envelope.to_cbor().unwrap_or_default()),
+            binary: Bytes(envelope.to_binary()),
+            hex: armor::encode(envelope, TextEncoding::Hex),
+            base64url: armor::encode(envelope, TextEncoding::Base64Url),
+            armor: armor::encode(envelope, TextEncoding::Armor),
+            json,
+        }
+    }
+
+    /// Checks that all the encodings decode to the same envelope.
+    fn check(&self) -> Result<()> {
+        let envelope = Envelope::from_json(&self.json.to_string())?;
+        let decoded = [
+            Envelope::from_cbor(&self.cbor.0)?,
+            Envelope::from_binary(&self.binary.0)?,
+            armor::decode(&self.hex)?,
+            armor::decode(&self.base64url)?,
+            armor::decode(&self.armor)?,
+        ];
+        if decoded.iter().any(|other| *other != envelope) {
+            return Err(ProtocolError::VectorMismatch.into());
+        }
+        Ok(())
+    }
+}
+
+fn element<G: CyclicGroup>(element: &G::Element) -> Bytes {
+    Bytes(G::Element::to(element))
+}
+
+fn scalar<G: CyclicGroup>(scalar: &G::Scalar) -> Bytes {
+    Bytes(G::Scalar::to(scalar))
+}
+
+fn from_element<G: CyclicGroup>(bytes: &Bytes) -> Result<G::Element> {
+    <G::Element as FromBytes<G::Element>>::from(&bytes.0)
+}
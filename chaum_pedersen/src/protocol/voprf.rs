@@ -0,0 +1,186 @@
+//! Verifiable oblivious pseudorandom function, in the style of RFC 9497 but not interoperable
+//! with it: there are no RFC ciphersuites, context strings or hash-to-group, so its test vectors
+//! do not apply. It runs on the backends of the crate, a Ristretto one is out of scope.
+//!
+//! The server holds a key pair `pk = g^k`. The client hashes its input to `P = H(input)` and sends
+//! the blinded element `B = P^r`, the server answers `Z = B^k` with a Chaum-Pedersen proof that
+//! `log_g pk == log_B Z`, batched over all the elements of a request. The client unblinds
+//! `N = Z^(1/r) = P^k` and hashes it together with the input into the output.
+//!
+//! The server never learns the input and the client never learns `k`, while the proof ensures
+//! every client is served with the same key, so outputs can't be used to tag clients.
+
+use crate::protocol::dleq::{self, DleqProof};
+use crate::protocol::elgamal::KeyPair;
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+use std::fmt;
+
+/// Domain separation tag of the hash of inputs to group elements.
+pub const HASH_TO_GROUP_DOMAIN: &str = "chaum-pedersen-voprf";
+
+/// The client's state for one input, `element = H(input)^r` being sent to the server.
+#[derive(Clone, PartialEq)]
+pub struct Blinded<G: CyclicGroup> {
+    pub input: Vec<u8>,
+    pub r: G::Scalar,
+    pub element: G::Element,
+}
+
+/// Prints the blinded element only, the input and `r` staying private to the client.
+impl<G: CyclicGroup> fmt::Debug for Blinded<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Blinded")
+            .field("element", &self.element)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The server's answer to a batch of blinded elements.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evaluation<G: CyclicGroup> {
+    /// `Zi = Bi^k`, in the order of the request.
+    pub elements: Vec<G::Element>,
+    /// Batched proof that every `Zi` was computed with the key behind `pk`.
+    pub proof: DleqProof<G>,
+}
+
+/// Blinds `input` with a fresh non-zero `r`.
+pub fn blind<G: CyclicGroup>(params: &GroupParams<G::Element>, input: &[u8]) -> Blinded<G> {
+    let r = loop {
+        let r = G::random_scalar(params);
+        if G::scalar_invert(params, &r).is_some() {
+            break r;
+        }
+    };
+    let element = G::exp(params, &hash_input::<G>(params, input), &r);
+    Blinded {
+        input: input.to_vec(),
+        r,
+        element,
+    }
+}
+
+/// Evaluates the blinded elements of a request under `key` and proves it.
+pub fn blind_evaluate<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    context: &Transcript,
+    key: &KeyPair<G>,
+    blinded: &[G::Element],
+) -> Result<Evaluation<G>> {
+    if blinded.iter().any(|b| !is_valid_element::<G>(params, b)) {
+        return Err(ProtocolError::InvalidElement.into());
+    }
+    let elements: Vec<G::Element> = blinded.iter().map(|b| G::exp(params, b, &key.x)).collect();
+    let mut transcript = proof_transcript(context);
+    let statement = dleq::batch_statement(
+        params,
+        &mut transcript,
+        &params.g,
+        &key.y,
+        blinded,
+        &elements,
+    )?;
+    let proof = DleqProof::prove(params, &mut transcript, &statement, &key.x);
+    Ok(Evaluation { elements, proof })
+}
+
+/// Checks the server's proof against its public key `pk` and unblinds the outputs.
+pub fn finalize<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    context: &Transcript,
+    pk: &G::Element,
+    blinded: &[Blinded<G>],
+    evaluation: &Evaluation<G>,
+) -> Result<Vec<[u8; 64]>> {
+    if blinded.len() != evaluation.elements.len() {
+        return Err(ProtocolError::LengthMismatch.into());
+    }
+    if evaluation
+        .elements
+        .iter()
+        .any(|z| !is_valid_element::<G>(params, z))
+    {
+        return Err(ProtocolError::InvalidElement.into());
+    }
+    let requested: Vec<G::Element> = blinded.iter().map(|b| b.element.clone()).collect();
+    let mut transcript = proof_transcript(context);
+    let statement = dleq::batch_statement(
+        params,
+        &mut transcript,
+        &params.g,
+        pk,
+        &requested,
+        &evaluation.elements,
+    )?;
+    if !evaluation.proof.verify(params, &mut transcript, &statement) {
+        return Err(ProtocolError::InvalidProof.into());
+    }
+    blinded
+        .iter()
+        .zip(&evaluation.elements)
+        .map(|(b, z)| {
+            let r_inv = G::scalar_invert(params, &b.r).ok_or(ProtocolError::InvalidElement)?;
+            Ok(output::<G>(&b.input, &G::exp(params, z, &r_inv)))
+        })
+        .collect()
+}
+
+/// Evaluates the function directly on `input`, which lets the server recognize outputs it issued.
+pub fn evaluate<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    key: &KeyPair<G>,
+    input: &[u8],
+) -> [u8; 64] {
+    output::<G>(
+        input,
+        &G::exp(params, &hash_input::<G>(params, input), &key.x),
+    )
+}
+
+fn hash_input<G: CyclicGroup>(params: &GroupParams<G::Element>, input: &[u8]) -> G::Element {
+    G::hash_to_element(params, HASH_TO_GROUP_DOMAIN, input)
+}
+
+fn output<G: CyclicGroup>(input: &[u8], n: &G::Element) -> [u8; 64] {
+    let mut transcript = Transcript::new(b"voprf-finalize");
+    transcript.append_message(b"input", input);
+    transcript.append_element::<G>(b"element", n);
+    transcript.challenge_bytes(b"output")
+}
+
+fn is_valid_element<G: CyclicGroup>(params: &GroupParams<G::Element>, a: &G::Element) -> bool {
+    G::contains(params, a) && *a != G::identity(params)
+}
+
+fn proof_transcript(context: &Transcript) -> Transcript {
+    let mut transcript = context.clone();
+    transcript.append_message(b"protocol", b"voprf");
+    transcript
+}
+
+impl<G: CyclicGroup> IntoBytes<Evaluation<G>> for Evaluation<G> {
+    fn to(t: &Evaluation<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put_all(&mut out, &t.elements);
+        encoding::put(&mut out, &t.proof);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<Evaluation<G>> for Evaluation<G> {
+    fn from(bytes: &[u8]) -> Result<Evaluation<G>> {
+        let mut reader = Reader::new(bytes);
+        let evaluation = Evaluation {
+            elements: reader.take_all()?,
+            proof: reader.take()?,
+        };
+        reader.finish()?;
+        Ok(evaluation)
+    }
+}
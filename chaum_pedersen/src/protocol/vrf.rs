@@ -0,0 +1,101 @@
+//! Verifiable random function: the output for the message `m` is derived from `Γ = H(m)^x`,
+//! and a Chaum-Pedersen proof that `log_g y == log_H(m) Γ` lets anyone holding `y = g^x` verify it.
+//!
+//! The output is unique for a given key and message, and looks random to whoever ignores `x`,
+//! which makes it suitable for lottery draws or leader election.
+
+use crate::protocol::dleq::{DleqProof, DleqStatement};
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::transcript::Transcript;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+
+/// Domain separation tag of the hash of messages to group elements.
+pub const HASH_TO_GROUP_DOMAIN: &str = "chaum-pedersen-vrf";
+
+/// The proof of a VRF output, `gamma = H(m)^x`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VrfProof<G: CyclicGroup> {
+    pub gamma: G::Element,
+    pub proof: DleqProof<G>,
+}
+
+impl<G: CyclicGroup> VrfProof<G> {
+    /// Evaluates the function on `message` with the secret `x`, returning the output and its proof.
+    pub fn prove(
+        params: &GroupParams<G::Element>,
+        x: &G::Scalar,
+        message: &[u8],
+    ) -> ([u8; 64], Self) {
+        let base = G::hash_to_element(params, HASH_TO_GROUP_DOMAIN, message);
+        let gamma = G::exp(params, &base, x);
+        let statement = DleqStatement {
+            g1: params.g.clone(),
+            y1: G::exp(params, &params.g, x),
+            g2: base,
+            y2: gamma.clone(),
+        };
+        let proof = DleqProof::prove(params, &mut transcript(message), &statement, x);
+        let vrf = VrfProof { gamma, proof };
+        (vrf.output(), vrf)
+    }
+
+    /// Checks the proof against the public key `y` and returns the output it attests.
+    pub fn verify(
+        &self,
+        params: &GroupParams<G::Element>,
+        y: &G::Element,
+        message: &[u8],
+    ) -> Result<[u8; 64]> {
+        let statement = DleqStatement {
+            g1: params.g.clone(),
+            y1: y.clone(),
+            g2: G::hash_to_element(params, HASH_TO_GROUP_DOMAIN, message),
+            y2: self.gamma.clone(),
+        };
+        if !self
+            .proof
+            .verify(params, &mut transcript(message), &statement)
+        {
+            return Err(ProtocolError::InvalidProof.into());
+        }
+        Ok(self.output())
+    }
+
+    /// The output derived from `gamma`, only meaningful once the proof is verified.
+    pub fn output(&self) -> [u8; 64] {
+        let mut transcript = Transcript::new(b"vrf-output");
+        transcript.append_element::<G>(b"gamma", &self.gamma);
+        transcript.challenge_bytes(b"output")
+    }
+}
+
+fn transcript(message: &[u8]) -> Transcript {
+    let mut transcript = Transcript::new(b"vrf");
+    transcript.append_message(b"message", message);
+    transcript
+}
+
+impl<G: CyclicGroup> IntoBytes<VrfProof<G>> for VrfProof<G> {
+    fn to(t: &VrfProof<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put(&mut out, &t.gamma);
+        encoding::put(&mut out, &t.proof);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<VrfProof<G>> for VrfProof<G> {
+    fn from(bytes: &[u8]) -> Result<VrfProof<G>> {
+        let mut reader = Reader::new(bytes);
+        let proof = VrfProof {
+            gamma: reader.take()?,
+            proof: reader.take()?,
+        };
+        reader.finish()?;
+        Ok(proof)
+    }
+}
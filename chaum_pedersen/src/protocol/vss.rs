@@ -0,0 +1,236 @@
+//! Verifiable secret sharing: Shamir sharing with public commitments to the dealer's polynomial,
+//! so every holder can check its share without trusting the dealer.
+//!
+//! Feldman's scheme commits to the coefficients `ak` as `Ak = g^ak`, which reveals `g^s`.
+//! Pedersen's scheme commits as `Ck = g^ak · h^bk` with a second, blinding polynomial,
+//! which keeps the secret perfectly hidden.
+
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::shamir::{self, Polynomial, Share};
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+use std::fmt;
+
+/// The dealer's commitments `Ak = g^ak` to the coefficients of its polynomial.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeldmanVss<G: CyclicGroup> {
+    pub commitments: Vec<G::Element>,
+}
+
+/// The dealer's commitments `Ck = g^ak · h^bk` to the coefficients of both its polynomials.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PedersenVss<G: CyclicGroup> {
+    pub commitments: Vec<G::Element>,
+}
+
+/// A share of Pedersen's scheme, `value = f(index)` and `blinding = f'(index)`.
+#[derive(Clone, PartialEq)]
+pub struct PedersenShare<G: CyclicGroup> {
+    pub index: u64,
+    pub value: G::Scalar,
+    pub blinding: G::Scalar,
+}
+
+/// Prints the index of the share, not its value and blinding.
+impl<G: CyclicGroup> fmt::Debug for PedersenShare<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PedersenShare")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G: CyclicGroup> FeldmanVss<G> {
+    /// Splits `secret` into `n` shares, any `t` of which recover it.
+    pub fn deal(
+        params: &GroupParams<G::Element>,
+        secret: &G::Scalar,
+        t: usize,
+        n: usize,
+    ) -> Result<(Self, Vec<Share<G>>)> {
+        shamir::check_threshold(t, n)?;
+        let polynomial = Polynomial::<G>::random(params, secret, t);
+        Ok((
+            Self::commit(params, &polynomial),
+            polynomial.shares(params, n as u64),
+        ))
+    }
+
+    pub fn commit(params: &GroupParams<G::Element>, polynomial: &Polynomial<G>) -> Self {
+        FeldmanVss {
+            commitments: polynomial
+                .coefficients
+                .iter()
+                .map(|a| G::exp(params, &params.g, a))
+                .collect(),
+        }
+    }
+
+    /// Checks `g^share = Π Ak^(index^k)`.
+    pub fn verify_share(&self, params: &GroupParams<G::Element>, share: &Share<G>) -> bool {
+        share.index != 0
+            && self.is_well_formed(params)
+            && G::exp(params, &params.g, &share.value) == self.public_share(params, share.index)
+    }
+
+    /// The public value `g^f(index)` of a share, computable by anyone.
+    pub fn public_share(&self, params: &GroupParams<G::Element>, index: u64) -> G::Element {
+        evaluate_in_exponent::<G>(params, &self.commitments, index)
+    }
+
+    /// The commitment `g^s` to the shared secret.
+    pub fn public_key(&self) -> Option<&G::Element> {
+        self.commitments.first()
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.commitments.len()
+    }
+
+    fn is_well_formed(&self, params: &GroupParams<G::Element>) -> bool {
+        !self.commitments.is_empty() && self.commitments.iter().all(|c| G::contains(params, c))
+    }
+}
+
+impl<G: CyclicGroup> PedersenVss<G> {
+    /// Splits `secret` into `n` shares, any `t` of which recover it.
+    pub fn deal(
+        params: &GroupParams<G::Element>,
+        secret: &G::Scalar,
+        t: usize,
+        n: usize,
+    ) -> Result<(Self, Vec<PedersenShare<G>>)> {
+        shamir::check_threshold(t, n)?;
+        let polynomial = Polynomial::<G>::random(params, secret, t);
+        let blinding = Polynomial::<G>::random(params, &G::random_scalar(params), t);
+        Ok(Self::commit(params, &polynomial, &blinding, n as u64))
+    }
+
+    /// Commits to both polynomials and computes the shares of the parties `1..=n`.
+    pub fn commit(
+        params: &GroupParams<G::Element>,
+        polynomial: &Polynomial<G>,
+        blinding: &Polynomial<G>,
+        n: u64,
+    ) -> (Self, Vec<PedersenShare<G>>) {
+        let commitments = polynomial
+            .coefficients
+            .iter()
+            .zip(&blinding.coefficients)
+            .map(|(a, b)| {
+                G::multi_exp(
+                    params,
+                    &[params.g.clone(), params.h.clone()],
+                    &[a.clone(), b.clone()],
+                )
+            })
+            .collect();
+        let shares = (1..=n)
+            .map(|index| PedersenShare {
+                index,
+                value: polynomial.evaluate(params, index),
+                blinding: blinding.evaluate(params, index),
+            })
+            .collect();
+        (PedersenVss { commitments }, shares)
+    }
+
+    /// Checks `g^value · h^blinding = Π Ck^(index^k)`.
+    pub fn verify_share(&self, params: &GroupParams<G::Element>, share: &PedersenShare<G>) -> bool {
+        if share.index == 0
+            || self.commitments.is_empty()
+            || !self.commitments.iter().all(|c| G::contains(params, c))
+        {
+            return false;
+        }
+        let lhs = G::multi_exp(
+            params,
+            &[params.g.clone(), params.h.clone()],
+            &[share.value.clone(), share.blinding.clone()],
+        );
+        lhs == evaluate_in_exponent::<G>(params, &self.commitments, share.index)
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.commitments.len()
+    }
+}
+
+/// Evaluates the committed polynomial at `index` in the exponent, `Π Ck^(index^k)`.
+pub fn evaluate_in_exponent<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    commitments: &[G::Element],
+    index: u64,
+) -> G::Element {
+    let x = G::scalar_from_u64(params, index);
+    // Horner's rule in the exponent
+    commitments
+        .iter()
+        .rev()
+        .fold(G::identity(params), |acc, commitment| {
+            G::op(params, &G::exp(params, &acc, &x), commitment)
+        })
+}
+
+impl<G: CyclicGroup> IntoBytes<FeldmanVss<G>> for FeldmanVss<G> {
+    fn to(t: &FeldmanVss<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put_all(&mut out, &t.commitments);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<FeldmanVss<G>> for FeldmanVss<G> {
+    fn from(bytes: &[u8]) -> Result<FeldmanVss<G>> {
+        let mut reader = Reader::new(bytes);
+        let vss = FeldmanVss {
+            commitments: reader.take_all()?,
+        };
+        reader.finish()?;
+        Ok(vss)
+    }
+}
+
+impl<G: CyclicGroup> IntoBytes<PedersenVss<G>> for PedersenVss<G> {
+    fn to(t: &PedersenVss<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put_all(&mut out, &t.commitments);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<PedersenVss<G>> for PedersenVss<G> {
+    fn from(bytes: &[u8]) -> Result<PedersenVss<G>> {
+        let mut reader = Reader::new(bytes);
+        let vss = PedersenVss {
+            commitments: reader.take_all()?,
+        };
+        reader.finish()?;
+        Ok(vss)
+    }
+}
+
+impl<G: CyclicGroup> IntoBytes<PedersenShare<G>> for PedersenShare<G> {
+    fn to(t: &PedersenShare<G>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encoding::put_u64(&mut out, t.index);
+        encoding::put(&mut out, &t.value);
+        encoding::put(&mut out, &t.blinding);
+        out
+    }
+}
+
+impl<G: CyclicGroup> FromBytes<PedersenShare<G>> for PedersenShare<G> {
+    fn from(bytes: &[u8]) -> Result<PedersenShare<G>> {
+        let mut reader = Reader::new(bytes);
+        let share = PedersenShare {
+            index: reader.take_u64()?,
+            value: reader.take()?,
+            blinding: reader.take()?,
+        };
+        reader.finish()?;
+        Ok(share)
+    }
+}
@@ -0,0 +1,534 @@
+//! A versioned, self-describing encoding of group parameters, statements, commitments and proofs.
+//!
+//! Every artefact is wrapped in an `Envelope` recording the wire format version and the flavor
+//! and curve of its group, so a stored value can never be read back in another group.
+//! Envelopes go through serde, as JSON with hex encoded values or as CBOR with raw bytes,
+//! and have a compact binary form:
+//!
+//! ```text
+//! "CPZK" | version: u16 | flavor: u8 | curve: u8 | kind: u8 | length-prefixed fields
+//! ```
+
+use crate::enums::{EllipticCurve, Flavor};
+use crate::protocol::designated::DesignatedProof;
+use crate::protocol::dleq::DleqProof;
+use crate::protocol::encoding::{self, Reader};
+use crate::protocol::errors::ProtocolError;
+use crate::protocol::group::CyclicGroup;
+use crate::protocol::or_proof::OrProof;
+use crate::protocol::range::RangeProof;
+use crate::protocol::representation::RepresentationProof;
+use crate::protocol::ring::RingSignature;
+use crate::protocol::schnorr::Signature;
+use crate::protocol::secret::Secret;
+use crate::protocol::sigma::SigmaProof;
+use crate::protocol::vrf::VrfProof;
+use crate::protocol::GroupParams;
+use crate::traits::{FromBytes, IntoBytes};
+use anyhow::Result;
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// The version of the encoding, bumped on any incompatible change.
+pub const WIRE_VERSION: u16 = 1;
+
+const MAGIC: &[u8; 4] = b"CPZK";
+
+/// Raw bytes, written as hex in human-readable formats.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bytes(pub Vec<u8>);
+
+/// Raw bytes of a secret, wiped when dropped and redacted in `Debug`.
+#[derive(Debug)]
+pub struct SecretBytes(pub Secret<Vec<u8>>);
+
+/// The content of an envelope, tagged with its `kind`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Content {
+    Params {
+        g: Bytes,
+        h: Bytes,
+        p: Bytes,
+        q: Bytes,
+    },
+    Statement {
+        y1: Bytes,
+        y2: Bytes,
+    },
+    /// The secret `x` of a user, to be kept out of shared storage.
+    Secret {
+        x: SecretBytes,
+    },
+    Commitment {
+        r1: Bytes,
+        r2: Bytes,
+    },
+    InteractiveProof {
+        y1: Bytes,
+        y2: Bytes,
+        r1: Bytes,
+        r2: Bytes,
+        c: Bytes,
+        s: Bytes,
+    },
+    /// A non-interactive proof, `scheme` naming its `WireProof` type.
+    Proof {
+        scheme: String,
+        data: Bytes,
+    },
+}
+
+/// An encoded artefact together with the version and group it was encoded for.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Envelope {
+    pub version: u16,
+    pub flavor: Flavor,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curve: Option<EllipticCurve>,
+    pub content: Content,
+}
+
+/// A full run of the interactive protocol: the statement, the commitment, the challenge and the response.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InteractiveProof<G: CyclicGroup> {
+    pub y1: G::Element,
+    pub y2: G::Element,
+    pub r1: G::Element,
+    pub r2: G::Element,
+    pub c: G::Scalar,
+    pub s: G::Scalar,
+}
+
+/// A non-interactive proof that can be wrapped in an envelope.
+pub trait WireProof: Sized + IntoBytes<Self> + FromBytes<Self> {
+    type Group: CyclicGroup;
+    /// The name of the proof in the envelope.
+    const SCHEME: &'static str;
+}
+
+macro_rules! wire_proof {
+    ($proof:ident, $scheme:literal) => {
+        impl<G: CyclicGroup> WireProof for $proof<G> {
+            type Group = G;
+            const SCHEME: &'static str = $scheme;
+        }
+    };
+}
+
+wire_proof!(DleqProof, "dleq");
+wire_proof!(Signature, "schnorr");
+wire_proof!(SigmaProof, "sigma");
+wire_proof!(OrProof, "or");
+wire_proof!(RangeProof, "range");
+wire_proof!(RepresentationProof, "representation");
+wire_proof!(VrfProof, "vrf");
+wire_proof!(DesignatedProof, "designated");
+wire_proof!(RingSignature, "ring");
+
+impl<G: CyclicGroup> InteractiveProof<G> {
+    /// Checks `g^s = r1 · y1^c` and `h^s = r2 · y2^c`.
+    pub fn verify(&self, params: &GroupParams<G::Element>) -> bool {
+        G::contains(params, &self.y1)
+            && G::contains(params, &self.y2)
+            && G::exp(params, &params.g, &self.s)
+                == G::op(params, &self.r1, &G::exp(params, &self.y1, &self.c))
+            && G::exp(params, &params.h, &self.s)
+                == G::op(params, &self.r2, &G::exp(params, &self.y2, &self.c))
+    }
+}
+
+impl Envelope {
+    fn new<G: CyclicGroup>(content: Content) -> Self {
+        Envelope {
+            version: WIRE_VERSION,
+            flavor: G::FLAVOR,
+            curve: G::CURVE,
+            content,
+        }
+    }
+
+    pub fn params<G: CyclicGroup>(params: &GroupParams<G::Element>) -> Self {
+        Self::new::<G>(Content::Params {
+            g: element::<G>(&params.g),
+            h: element::<G>(&params.h),
+            p: element::<G>(&params.p),
+            q: element::<G>(&params.q),
+        })
+    }
+
+    /// The public statement `(y1, y2)` of a user.
+    pub fn statement<G: CyclicGroup>(y1: &G::Element, y2: &G::Element) -> Self {
+        Self::new::<G>(Content::Statement {
+            y1: element::<G>(y1),
+            y2: element::<G>(y2),
+        })
+    }
+
+    pub fn secret<G: CyclicGroup>(x: &G::Scalar) -> Self {
+        Self::new::<G>(Content::Secret {
+            x: SecretBytes(Secret::new(G::Scalar::to(x))),
+        })
+    }
+
+    /// The commitment `(r1, r2)` of a run of the interactive protocol.
+    pub fn commitment<G: CyclicGroup>(r1: &G::Element, r2: &G::Element) -> Self {
+        Self::new::<G>(Content::Commitment {
+            r1: element::<G>(r1),
+            r2: element::<G>(r2),
+        })
+    }
+
+    pub fn interactive_proof<G: CyclicGroup>(proof: &InteractiveProof<G>) -> Self {
+        Self::new::<G>(Content::InteractiveProof {
+            y1: element::<G>(&proof.y1),
+            y2: element::<G>(&proof.y2),
+            r1: element::<G>(&proof.r1),
+            r2: element::<G>(&proof.r2),
+            c: Bytes(G::Scalar::to(&proof.c)),
+            s: Bytes(G::Scalar::to(&proof.s)),
+        })
+    }
+
+    pub fn proof<P: WireProof>(proof: &P) -> Self {
+        Self::new::<P::Group>(Content::Proof {
+            scheme: P::SCHEME.to_string(),
+            data: Bytes(P::to(proof)),
+        })
+    }
+
+    /// Checks that the envelope was encoded for the group `G`.
+    pub fn check_group<G: CyclicGroup>(&self) -> Result<()> {
+        if self.flavor != G::FLAVOR || self.curve != G::CURVE {
+            return Err(ProtocolError::GroupMismatch.into());
+        }
+        Ok(())
+    }
+
+    pub fn to_params<G: CyclicGroup>(&self) -> Result<GroupParams<G::Element>> {
+        self.check_group::<G>()?;
+        match &self.content {
+            Content::Params { g, h, p, q } => Ok(GroupParams {
+                g: from_element::<G>(&g.0)?,
+                h: from_element::<G>(&h.0)?,
+                p: from_element::<G>(&p.0)?,
+                q: from_element::<G>(&q.0)?,
+            }),
+            _ => Err(ProtocolError::UnexpectedContent.into()),
+        }
+    }
+
+    pub fn to_statement<G: CyclicGroup>(&self) -> Result<(G::Element, G::Element)> {
+        self.check_group::<G>()?;
+        match &self.content {
+            Content::Statement { y1, y2 } => {
+                Ok((from_element::<G>(&y1.0)?, from_element::<G>(&y2.0)?))
+            }
+            _ => Err(ProtocolError::UnexpectedContent.into()),
+        }
+    }
+
+    pub fn to_secret<G: CyclicGroup>(&self) -> Result<G::Scalar> {
+        self.check_group::<G>()?;
+        match &self.content {
+            Content::Secret { x } => from_scalar::<G>(x.0.expose()),
+            _ => Err(ProtocolError::UnexpectedContent.into()),
+        }
+    }
+
+    pub fn to_commitment<G: CyclicGroup>(&self) -> Result<(G::Element, G::Element)> {
+        self.check_group::<G>()?;
+        match &self.content {
+            Content::Commitment { r1, r2 } => {
+                Ok((from_element::<G>(&r1.0)?, from_element::<G>(&r2.0)?))
+            }
+            _ => Err(ProtocolError::UnexpectedContent.into()),
+        }
+    }
+
+    pub fn to_interactive_proof<G: CyclicGroup>(&self) -> Result<InteractiveProof<G>> {
+        self.check_group::<G>()?;
+        match &self.content {
+            Content::InteractiveProof {
+                y1,
+                y2,
+                r1,
+                r2,
+                c,
+                s,
+            } => Ok(InteractiveProof {
+                y1: from_element::<G>(&y1.0)?,
+                y2: from_element::<G>(&y2.0)?,
+                r1: from_element::<G>(&r1.0)?,
+                r2: from_element::<G>(&r2.0)?,
+                c: from_scalar::<G>(&c.0)?,
+                s: from_scalar::<G>(&s.0)?,
+            }),
+            _ => Err(ProtocolError::UnexpectedContent.into()),
+        }
+    }
+
+    pub fn to_proof<P: WireProof>(&self) -> Result<P> {
+        self.check_group::<P::Group>()?;
+        match &self.content {
+            Content::Proof { scheme, data } if scheme == P::SCHEME => {
+                <P as FromBytes<P>>::from(&data.0)
+            }
+            _ => Err(ProtocolError::UnexpectedContent.into()),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str::<Self>(json)?.checked()
+    }
+
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        Ok(serde_cbor::to_vec(self)?)
+    }
+
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        serde_cbor::from_slice::<Self>(bytes)?.checked()
+    }
+
+    /// The compact binary form of the envelope.
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.extend_from_slice(&self.version.to_be_bytes());
+        out.push(match self.flavor {
+            Flavor::DiscreteLog => 0,
+            Flavor::EllipticCurve => 1,
+        });
+        out.push(match self.curve {
+            None => 0,
+            Some(EllipticCurve::Pallas) => 1,
+            Some(EllipticCurve::Vesta) => 2,
+        });
+        let (kind, fields): (u8, Vec<&[u8]>) = match &self.content {
+            Content::Params { g, h, p, q } => (0, vec![&g.0, &h.0, &p.0, &q.0]),
+            Content::Statement { y1, y2 } => (1, vec![&y1.0, &y2.0]),
+            Content::Commitment { r1, r2 } => (2, vec![&r1.0, &r2.0]),
+            Content::InteractiveProof {
+                y1,
+                y2,
+                r1,
+                r2,
+                c,
+                s,
+            } => (3, vec![&y1.0, &y2.0, &r1.0, &r2.0, &c.0, &s.0]),
+            Content::Proof { scheme, data } => (4, vec![scheme.as_bytes(), &data.0]),
+            Content::Secret { x } => (5, vec![x.0.expose()]),
+        };
+        out.push(kind);
+        fields
+            .iter()
+            .for_each(|field| encoding::put_bytes(&mut out, field));
+        out
+    }
+
+    pub fn from_binary(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < MAGIC.len() + 5 {
+            return Err(ProtocolError::TruncatedBytes.into());
+        }
+        let (magic, rest) = bytes.split_at(MAGIC.len());
+        if magic != MAGIC {
+            return Err(ProtocolError::InvalidEncoding.into());
+        }
+        let version = u16::from_be_bytes([rest[0], rest[1]]);
+        let flavor = match rest[2] {
+            0 => Flavor::DiscreteLog,
+            1 => Flavor::EllipticCurve,
+            _ => return Err(ProtocolError::InvalidEncoding.into()),
+        };
+        let curve = match rest[3] {
+            0 => None,
+            1 => Some(EllipticCurve::Pallas),
+            2 => Some(EllipticCurve::Vesta),
+            _ => return Err(ProtocolError::InvalidEncoding.into()),
+        };
+        let mut reader = Reader::new(&rest[5..]);
+        let mut field = || -> Result<Bytes> { Ok(Bytes(reader.take_bytes()?.to_vec())) };
+        let content = match rest[4] {
+            0 => Content::Params {
+                g: field()?,
+                h: field()?,
+                p: field()?,
+                q: field()?,
+            },
+            1 => Content::Statement {
+                y1: field()?,
+                y2: field()?,
+            },
+            2 => Content::Commitment {
+                r1: field()?,
+                r2: field()?,
+            },
+            3 => Content::InteractiveProof {
+                y1: field()?,
+                y2: field()?,
+                r1: field()?,
+                r2: field()?,
+                c: field()?,
+                s: field()?,
+            },
+            4 => Content::Proof {
+                scheme: String::from_utf8(field()?.0)
+                    .map_err(|_| ProtocolError::InvalidEncoding)?,
+                data: field()?,
+            },
+            5 => Content::Secret {
+                x: SecretBytes(Secret::new(field()?.0)),
+            },
+            _ => return Err(ProtocolError::InvalidEncoding.into()),
+        };
+        reader.finish()?;
+        Envelope {
+            version,
+            flavor,
+            curve,
+            content,
+        }
+        .checked()
+    }
+
+    /// Rejects envelopes of another version, and curves given for the discrete log flavor or missing for elliptic curves.
+    fn checked(self) -> Result<Self> {
+        if self.version != WIRE_VERSION {
+            return Err(ProtocolError::UnsupportedVersion.into());
+        }
+        if (self.flavor == Flavor::EllipticCurve) != self.curve.is_some() {
+            return Err(ProtocolError::InvalidEncoding.into());
+        }
+        Ok(self)
+    }
+}
+
+fn element<G: CyclicGroup>(element: &G::Element) -> Bytes {
+    Bytes(G::Element::to(element))
+}
+
+fn from_element<G: CyclicGroup>(bytes: &[u8]) -> Result<G::Element> {
+    <G::Element as FromBytes<G::Element>>::from(bytes)
+}
+
+fn from_scalar<G: CyclicGroup>(bytes: &[u8]) -> Result<G::Scalar> {
+    <G::Scalar as FromBytes<G::Scalar>>::from(bytes)
+}
+
+impl Serialize for Bytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex::encode(&self.0))
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        // tagged enums buffer their content, which loses the human-readable flag,
+        // so both representations are accepted
+        deserializer.deserialize_any(BytesVisitor)
+    }
+}
+
+impl Clone for SecretBytes {
+    fn clone(&self) -> Self {
+        SecretBytes(Secret::new(self.0.expose().clone()))
+    }
+}
+
+impl PartialEq for SecretBytes {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose() == other.0.expose()
+    }
+}
+
+impl Serialize for SecretBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(Secret::new(hex::encode(self.0.expose())).expose())
+        } else {
+            serializer.serialize_bytes(self.0.expose())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Bytes::deserialize(deserializer).map(|bytes| SecretBytes(Secret::new(bytes.0)))
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Bytes;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a hex string or a byte string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<Bytes, E> {
+        hex::decode(v).map(Bytes).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> std::result::Result<Bytes, E> {
+        Ok(Bytes(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> std::result::Result<Bytes, E> {
+        Ok(Bytes(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<Bytes, A::Error> {
+        let mut bytes = Vec::new();
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(Bytes(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::constants::PALLAS_GROUP_PARAMS;
+    use crate::protocol::discrete_log::DiscreteLog;
+    use crate::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+    use crate::protocol::elliptic_curves::vesta::VestaEllipticCurve;
+
+    #[test]
+    fn wire_rejects_other_groups_and_versions() {
+        let params = &PALLAS_GROUP_PARAMS;
+        let envelope = Envelope::statement::<PallasEllipticCurve>(&params.g, &params.h);
+        assert!(envelope.to_statement::<VestaEllipticCurve>().is_err());
+        assert!(envelope.to_statement::<DiscreteLog>().is_err());
+
+        let json = envelope.to_json().unwrap();
+        assert!(json.contains("\"flavor\": \"elliptic_curve\""));
+        assert!(json.contains("\"curve\": \"pallas\""));
+        assert!(json.contains("\"kind\": \"statement\""));
+
+        let mut future = envelope.clone();
+        future.version = WIRE_VERSION + 1;
+        assert!(Envelope::from_json(&future.to_json().unwrap()).is_err());
+        assert!(Envelope::from_binary(&future.to_binary()).is_err());
+
+        // an elliptic curve flavor without a curve is ambiguous
+        let mut ambiguous = envelope.clone();
+        ambiguous.curve = None;
+        assert!(Envelope::from_cbor(&ambiguous.to_cbor().unwrap()).is_err());
+        ambiguous.flavor = Flavor::DiscreteLog;
+        assert!(Envelope::from_cbor(&ambiguous.to_cbor().unwrap()).is_ok());
+
+        let binary = envelope.to_binary();
+        assert!(Envelope::from_binary(&binary[..binary.len() - 1]).is_err());
+        assert!(Envelope::from_binary(&[binary.as_slice(), &[0]].concat()).is_err());
+    }
+}
@@ -0,0 +1,42 @@
+use chaum_pedersen::enums::TextEncoding;
+use chaum_pedersen::protocol::armor::{decode, encode};
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::schnorr::Signature;
+use chaum_pedersen::protocol::wire::Envelope;
+use chaum_pedersen::protocol::GroupParams;
+
+#[macro_use]
+mod run;
+
+const ENCODINGS: [TextEncoding; 3] = [
+    TextEncoding::Armor,
+    TextEncoding::Hex,
+    TextEncoding::Base64Url,
+];
+
+fn run_armor<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let x = G::random_scalar(params);
+    let y1 = G::exp(params, &params.g, &x);
+    let y2 = G::exp(params, &params.h, &x);
+    let signature = Signature::<G>::sign(params, &x, b"payload");
+
+    for encoding in ENCODINGS {
+        let text = encode(&Envelope::secret::<G>(&x), encoding);
+        assert_eq!(decode(&text).unwrap().to_secret::<G>().unwrap(), x);
+
+        let text = encode(&Envelope::statement::<G>(&y1, &y2), encoding);
+        assert_eq!(
+            decode(&text).unwrap().to_statement::<G>().unwrap(),
+            (y1.clone(), y2.clone())
+        );
+
+        let text = encode(&Envelope::params::<G>(params), encoding);
+        assert_eq!(decode(&text).unwrap().to_params::<G>().unwrap().g, params.g);
+
+        let text = encode(&Envelope::proof(&signature), encoding);
+        let decoded = decode(&text).unwrap().to_proof::<Signature<G>>().unwrap();
+        assert!(decoded.verify(params, &y1, b"payload"));
+    }
+}
+
+group_tests!(armor: run_armor);
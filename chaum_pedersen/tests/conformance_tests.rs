@@ -0,0 +1,41 @@
+use chaum_pedersen::protocol::discrete_log::DiscreteLog;
+use chaum_pedersen::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+use chaum_pedersen::protocol::elliptic_curves::vesta::VestaEllipticCurve;
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::vectors::TestVectors;
+
+/// Replays the checked-in vectors, regenerated with `cargo run --example test_vectors`.
+fn replay<G: CyclicGroup>(json: &str) {
+    let vectors: TestVectors = serde_json::from_str(json).unwrap();
+    assert!(!vectors.vectors.is_empty());
+    vectors.check::<G>().unwrap();
+
+    // altered values and encodings are caught
+    let mut altered = vectors.clone();
+    altered.vectors[0].s.0[0] ^= 1;
+    assert!(altered.check::<G>().is_err());
+    let mut altered = vectors.clone();
+    altered.vectors[0].encodings[2].binary.0[12] ^= 1;
+    assert!(altered.check::<G>().is_err());
+}
+
+#[test]
+fn dlog_conformance() {
+    replay::<DiscreteLog>(include_str!("vectors/discrete_log.json"));
+}
+
+#[test]
+fn pallas_conformance() {
+    replay::<PallasEllipticCurve>(include_str!("vectors/pallas.json"));
+}
+
+#[test]
+fn vesta_conformance() {
+    replay::<VestaEllipticCurve>(include_str!("vectors/vesta.json"));
+}
+
+#[test]
+fn vectors_are_bound_to_their_group() {
+    let vectors: TestVectors = serde_json::from_str(include_str!("vectors/pallas.json")).unwrap();
+    assert!(vectors.check::<VestaEllipticCurve>().is_err());
+}
@@ -0,0 +1,59 @@
+use chaum_pedersen::protocol::constants::{
+    DLOG_GROUP_PARAMS, PALLAS_GROUP_PARAMS, VESTA_GROUP_PARAMS,
+};
+use chaum_pedersen::protocol::cross_group::CrossGroupProof;
+use chaum_pedersen::protocol::discrete_log::DiscreteLog;
+use chaum_pedersen::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+use chaum_pedersen::protocol::elliptic_curves::vesta::VestaEllipticCurve;
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::transcript::Transcript;
+use chaum_pedersen::protocol::GroupParams;
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+
+fn run_cross_group<G1: CyclicGroup, G2: CyclicGroup>(
+    params1: &GroupParams<G1::Element>,
+    params2: &GroupParams<G2::Element>,
+) {
+    // a secret of the supported bit length
+    let x1 = loop {
+        let x = G1::random_scalar(params1);
+        if CrossGroupProof::<G1, G2>::convert_secret(params1, params2, &x).is_some() {
+            break x;
+        }
+    };
+    let x2 = CrossGroupProof::<G1, G2>::convert_secret(params1, params2, &x1).unwrap();
+    let y1 = G1::exp(params1, &params1.g, &x1);
+    let y2 = G2::exp(params2, &params2.g, &x2);
+
+    let proof =
+        CrossGroupProof::<G1, G2>::prove(params1, params2, &mut Transcript::new(b"test"), &x1)
+            .unwrap();
+    assert!(proof.verify(params1, params2, &mut Transcript::new(b"test"), &y1, &y2));
+
+    // ensure reversability of (de)serialization operations
+    let recovered =
+        <CrossGroupProof<G1, G2> as FromBytes<_>>::from(&CrossGroupProof::to(&proof)).unwrap();
+    assert_eq!(proof, recovered);
+
+    // keys of distinct secrets are rejected
+    let other = G2::exp(params2, &params2.g, &G2::random_scalar(params2));
+    assert!(!proof.verify(params1, params2, &mut Transcript::new(b"test"), &y1, &other));
+
+    // a tampered bit proof is rejected
+    let mut tampered = proof.clone();
+    tampered.bits[3].c[0][0] ^= 1;
+    assert!(!tampered.verify(params1, params2, &mut Transcript::new(b"test"), &y1, &y2));
+}
+
+#[test]
+fn pallas_vesta_cross_group() {
+    run_cross_group::<PallasEllipticCurve, VestaEllipticCurve>(
+        &PALLAS_GROUP_PARAMS,
+        &VESTA_GROUP_PARAMS,
+    );
+}
+
+#[test]
+fn dlog_pallas_cross_group() {
+    run_cross_group::<DiscreteLog, PallasEllipticCurve>(&DLOG_GROUP_PARAMS, &PALLAS_GROUP_PARAMS);
+}
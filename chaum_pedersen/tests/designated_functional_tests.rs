@@ -0,0 +1,71 @@
+use chaum_pedersen::protocol::designated::DesignatedProof;
+use chaum_pedersen::protocol::dleq::DleqStatement;
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::transcript::Transcript;
+use chaum_pedersen::protocol::GroupParams;
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+
+#[macro_use]
+mod run;
+
+fn run_designated<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let x = G::random_scalar(params);
+    let statement = DleqStatement::<G> {
+        g1: params.g.clone(),
+        y1: G::exp(params, &params.g, &x),
+        g2: params.h.clone(),
+        y2: G::exp(params, &params.h, &x),
+    };
+    let verifier_secret = G::random_scalar(params);
+    let verifier_key = G::exp(params, &params.g, &verifier_secret);
+
+    let proof = DesignatedProof::prove(
+        params,
+        &mut Transcript::new(b"test"),
+        &statement,
+        &verifier_key,
+        &x,
+    )
+    .unwrap();
+    assert!(proof.verify(
+        params,
+        &mut Transcript::new(b"test"),
+        &statement,
+        &verifier_key
+    ));
+
+    // ensure reversability of (de)serialization operations
+    let recovered =
+        <DesignatedProof<G> as FromBytes<_>>::from(&DesignatedProof::to(&proof)).unwrap();
+    assert_eq!(proof, recovered);
+
+    // the proof is bound to its verifier
+    let other_key = G::exp(params, &params.g, &G::random_scalar(params));
+    assert!(!proof.verify(
+        params,
+        &mut Transcript::new(b"test"),
+        &statement,
+        &other_key
+    ));
+
+    // the verifier can forge proofs of false statements, so third parties learn nothing
+    let false_statement = DleqStatement {
+        y2: G::op(params, &statement.y2, &params.h),
+        ..statement.clone()
+    };
+    let forged = DesignatedProof::simulate(
+        params,
+        &mut Transcript::new(b"test"),
+        &false_statement,
+        &verifier_secret,
+    )
+    .unwrap();
+    assert!(forged.verify(
+        params,
+        &mut Transcript::new(b"test"),
+        &false_statement,
+        &verifier_key
+    ));
+}
+
+group_tests!(designated: run_designated);
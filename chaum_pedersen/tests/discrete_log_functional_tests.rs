@@ -1,5 +1,6 @@
 use chaum_pedersen::protocol::constants::DLOG_GROUP_PARAMS;
 use chaum_pedersen::protocol::discrete_log::DiscreteLog;
+use chaum_pedersen::protocol::secret::{Nonce, Secret};
 use chaum_pedersen::protocol::{GroupParams, Protocol};
 use chaum_pedersen::traits::Random;
 use num_bigint::{BigUint, RandBigInt};
@@ -13,7 +14,7 @@ use run::run_protocol;
 fn dlog_success() {
     let params = DLOG_GROUP_PARAMS.to_owned();
     let mut rng = OsRng;
-    let x = rng.gen_biguint_below(&params.p);
+    let x = Secret::new(rng.gen_biguint_below(&params.p));
     assert!(run_protocol::<DiscreteLog>(&params, &x));
 }
 
@@ -21,7 +22,7 @@ fn dlog_success() {
 fn dlog_fail() {
     let params = DLOG_GROUP_PARAMS.to_owned();
     let mut rng = OsRng;
-    let x = rng.gen_biguint_below(&params.p);
+    let x = Secret::new(rng.gen_biguint_below(&params.p));
     let (cp, _) = DiscreteLog::commitment(&params, &x);
     let c = DiscreteLog::challenge(&params);
     let fake_response = BigUint::random().unwrap();
@@ -48,8 +49,8 @@ fn test_verify() {
         <BigUint as From<u32>>::from(3u32),
     );
     // client calculates response
-    let x = <BigUint as From<u32>>::from(10u32);
-    let k = <BigUint as From<u32>>::from(17u32);
+    let x = Secret::new(<BigUint as From<u32>>::from(10u32));
+    let k = Nonce::new(<BigUint as From<u32>>::from(17u32));
     let c = <BigUint as From<u32>>::from(0u32);
     let s = DiscreteLog::challenge_response(&params, &k, &c, &x);
     // server verifies
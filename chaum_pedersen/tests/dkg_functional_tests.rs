@@ -0,0 +1,83 @@
+use chaum_pedersen::protocol::dkg::{simulate, DkgOutput, Faults};
+use chaum_pedersen::protocol::dleq::DleqProof;
+use chaum_pedersen::protocol::elgamal::Ciphertext;
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::shamir::{self, Share};
+use chaum_pedersen::protocol::transcript::Transcript;
+use chaum_pedersen::protocol::GroupParams;
+
+#[macro_use]
+mod run;
+
+/// Checks that all the parties agree on the key and that `t` of their shares recover its secret.
+fn check_outputs<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    outputs: &[DkgOutput<G>],
+    qualified: &[u64],
+) {
+    for output in outputs {
+        assert_eq!(output.qualified, qualified);
+        assert_eq!(output.public_key, outputs[0].public_key);
+        assert_eq!(output.y2, outputs[0].y2);
+        let index = output.key_share.index as usize;
+        assert_eq!(output.public_key.shares[index - 1], output.key_share.keys.y);
+    }
+
+    let shares: Vec<Share<G>> = outputs[1..3]
+        .iter()
+        .map(|output| Share {
+            index: output.key_share.index,
+            value: output.key_share.keys.x.clone(),
+        })
+        .collect();
+    let x = shamir::recover(params, &shares).unwrap();
+    let statement = outputs[0].statement(params);
+    let proof = DleqProof::prove(params, &mut Transcript::new(b"test"), &statement, &x);
+    assert!(proof.verify(params, &mut Transcript::new(b"test"), &statement));
+}
+
+fn run_dkg<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let context = Transcript::new(b"dkg-test");
+    let outputs = simulate::<G>(params, &context, 2, 4, &Faults::default()).unwrap();
+    check_outputs(params, &outputs, &[1, 2, 3, 4]);
+
+    // the generated key is usable for threshold decryption
+    let m = G::exp(params, &params.h, &G::random_scalar(params));
+    let ciphertext = Ciphertext::encrypt(params, &outputs[0].public_key.y, &m);
+    let partials: Vec<_> = outputs[2..]
+        .iter()
+        .map(|output| {
+            output
+                .key_share
+                .partial_decryption(params, &context, &ciphertext)
+        })
+        .collect();
+    let combined = outputs[0]
+        .public_key
+        .combine(params, &context, &ciphertext, &partials)
+        .unwrap();
+    assert_eq!(combined.plaintext, m);
+}
+
+fn run_dkg_with_faults<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let context = Transcript::new(b"dkg-test");
+    // dealer 2 answers the complaint about its corrupted share and stays qualified,
+    // dealer 3 does not and gets disqualified
+    let faults = Faults {
+        corrupted_shares: vec![(2, 1), (3, 4)],
+        silent_dealers: vec![3],
+        ..Faults::default()
+    };
+    let outputs = simulate::<G>(params, &context, 2, 4, &faults).unwrap();
+    check_outputs(params, &outputs, &[1, 2, 4]);
+
+    // dealer 4 sends two different dealings and gets disqualified, although both are valid
+    let faults = Faults {
+        equivocating_dealers: vec![4],
+        ..Faults::default()
+    };
+    let outputs = simulate::<G>(params, &context, 2, 4, &faults).unwrap();
+    check_outputs(params, &outputs, &[1, 2, 3]);
+}
+
+group_tests!(dkg: run_dkg, run_dkg_with_faults);
@@ -0,0 +1,48 @@
+use chaum_pedersen::protocol::elgamal::{discrete_log_bounded, Ciphertext, KeyPair};
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::transcript::Transcript;
+use chaum_pedersen::protocol::GroupParams;
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+
+#[macro_use]
+mod run;
+
+fn run_elgamal<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let keys = KeyPair::<G>::generate(params);
+    let m = G::exp(params, &params.h, &G::random_scalar(params));
+    let ciphertext = Ciphertext::encrypt(params, &keys.y, &m);
+    assert_eq!(keys.decrypt(params, &ciphertext), m);
+
+    let rerandomized = ciphertext.rerandomize(params, &keys.y);
+    assert_ne!(rerandomized, ciphertext);
+    assert_eq!(keys.decrypt(params, &rerandomized), m);
+
+    // scalars encrypted in the exponent add up
+    let a = Ciphertext::encrypt_scalar(params, &keys.y, &G::scalar_from_u64(params, 20));
+    let b = Ciphertext::encrypt_scalar(params, &keys.y, &G::scalar_from_u64(params, 22));
+    let sum = keys.decrypt(params, &a.add(params, &b));
+    assert_eq!(discrete_log_bounded::<G>(params, &sum, 100), Some(42));
+
+    // ensure reversability of (de)serialization operations
+    let bytes = Ciphertext::to(&ciphertext);
+    let recovered = <Ciphertext<G> as FromBytes<_>>::from(&bytes).unwrap();
+    assert_eq!(ciphertext, recovered);
+}
+
+fn run_verifiable_decryption<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let keys = KeyPair::<G>::generate(params);
+    let other = KeyPair::<G>::generate(params);
+    let m = G::exp(params, &params.g, &G::random_scalar(params));
+    let ciphertext = Ciphertext::encrypt(params, &keys.y, &m);
+
+    let share = keys.decryption_share(params, &mut Transcript::new(b"test"), &ciphertext);
+    let decrypted =
+        ciphertext.decrypt_with_share(params, &mut Transcript::new(b"test"), &keys.y, &share);
+    assert_eq!(decrypted, Some(m));
+
+    // a share computed with another key is rejected
+    let forged = other.decryption_share(params, &mut Transcript::new(b"test"), &ciphertext);
+    assert!(!forged.verify(params, &mut Transcript::new(b"test"), &keys.y, &ciphertext));
+}
+
+group_tests!(elgamal: run_elgamal, run_verifiable_decryption);
@@ -0,0 +1,205 @@
+use chaum_pedersen::enums::TextEncoding;
+use chaum_pedersen::protocol::armor;
+use chaum_pedersen::protocol::constants::{
+    DLOG_GROUP_PARAMS, PALLAS_GROUP_PARAMS, VESTA_GROUP_PARAMS,
+};
+use chaum_pedersen::protocol::discrete_log::DiscreteLog;
+use chaum_pedersen::protocol::dleq::{DleqProof, DleqStatement};
+use chaum_pedersen::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+use chaum_pedersen::protocol::elliptic_curves::vesta::VestaEllipticCurve;
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::group_params::{decode, fingerprint, load};
+use chaum_pedersen::protocol::transcript::Transcript;
+use chaum_pedersen::protocol::wire::Envelope;
+use chaum_pedersen::protocol::GroupParams;
+use num_bigint::BigUint;
+use std::fs;
+use std::str::FromStr;
+
+/// A 2048-bit prime `p = k·q + 1` with a 256-bit `q`, the generators being `2^k` and `3^k`.
+fn custom_dlog_params() -> GroupParams<BigUint> {
+    GroupParams {
+        p: BigUint::parse_bytes(
+            concat!(
+                "889ffd2f3c91dad2f6f6fad15bfa4e82239098b1efdf8ed8a55f0c6f276129b1",
+                "6d550e538e3a008ed10f8d5645caa8dec823d1d7bedae6715d403ac587f540de",
+                "ad2c5087dfe351bcfdfdc547ba5585bb9b1586c0af17677dfc93acddf9384391",
+                "48521f6ae6d0ceb7b2a6e6b883f7dd52cf15ba8d628dd398f1cf2b996e7d49ad",
+                "b7506b937901c16e35b1d622fb9b790a2111cc0650c32590ed0a113df705a0b0",
+                "f31915d94165ceb8c8a55da674d48ae4da9b2067ac5913ebc3d4523217c398e1",
+                "d5f474d4d8559df24950f4ffb96503e47fa42eab73c6b24a07c44cfa68100cff",
+                "a09fa5de879b722dbd876ecc0f9910c127a3562468b8ae2e2379c5b8412cde39",
+            )
+            .as_bytes(),
+            16,
+        )
+        .unwrap(),
+        q: BigUint::parse_bytes(
+            b"8f1973df0f870e6a26cfb3f3a8dba797dc7d4bc2c5bd42feb6fbb997aaa581bb",
+            16,
+        )
+        .unwrap(),
+        g: BigUint::parse_bytes(
+            concat!(
+                "525f99a528989ce97612b25a6e4ab4d5a9a88f2d8729e9fa57adee65d16a957d",
+                "85f3d6eb6d05fbd050ceaf320de7dba8843f498da11bb52f6e89b50ddbf826f4",
+                "e53e2a37f39dfe843a6fd1d6848f4bfb7cf64e9379f70dd92b446868da313c98",
+                "24399166621c20168bc4d5c447edb82b3c558e5fd631635b2aa79e0f93b76cf4",
+                "fe5f25036b9872fce5ce65e580bcd020470baf9e15e1d5738790b877153fed7e",
+                "17cb97fef1f36c3e872c17c1a125ebf6b3c017f01181a0b434d8e694805859d0",
+                "73c9f7b8346cdda3e8d9f1832c783af05937bab53ee1ebfddb98461600dbfaf4",
+                "2d01b8611a10d3d3cb64215a38c970761ff77db6ae7ea3a4cc60ebc2f1495785",
+            )
+            .as_bytes(),
+            16,
+        )
+        .unwrap(),
+        h: BigUint::parse_bytes(
+            concat!(
+                "7b8922c0344e8f4274238a688b5f2e6fef9d467285cc16cbe1b0426af684216c",
+                "724a3b1eda88d8efade280cbbdc4de73eff52657f0c6a5e1cdeadae14e674ea3",
+                "deede65266938ad199e856dc8207472e42781fe34fb69065ea94dfe4d8c0e8fa",
+                "1717e85bfab51e5c5bf381e91fba74014c381a3f426b1e0a6d4abc8cc3d533ab",
+                "0202e4d101d65ee17596d8bf6b4bfce11e7f699af514eba031010ecb557107c6",
+                "c5064182025dadb381f0315ecd92c7b48cf7e0e6f38f15f289913d478e94886e",
+                "ba11a32c5a7478aba1653ad0e6feff5f9a545ae283c4cd916fbeedce8a96930a",
+                "34b32d6365ed904c7166927cec9766df131429b8088568981bc097ecddadd5f2",
+            )
+            .as_bytes(),
+            16,
+        )
+        .unwrap(),
+    }
+}
+
+/// Groups passing every check but the sizes.
+fn toy_dlog_params() -> [GroupParams<BigUint>; 3] {
+    [
+        GroupParams {
+            p: BigUint::from(7u32),
+            q: BigUint::from(3u32),
+            g: BigUint::from(2u32),
+            h: BigUint::from(4u32),
+        },
+        GroupParams {
+            p: BigUint::from_str("221705653048864131931963548010584689843").unwrap(),
+            q: BigUint::from_str("110852826524432065965981774005292344921").unwrap(),
+            g: BigUint::from(4u32),
+            h: BigUint::from(25u32),
+        },
+        DLOG_GROUP_PARAMS.clone(),
+    ]
+}
+
+fn run_group_params<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    assert!(G::is_valid_params(params));
+    let envelope = Envelope::params::<G>(params);
+    for text in [
+        envelope.to_json().unwrap(),
+        armor::encode(&envelope, TextEncoding::Armor),
+        armor::encode(&envelope, TextEncoding::Base64Url),
+    ] {
+        let decoded = decode::<G>(&text).unwrap();
+        assert_eq!(fingerprint::<G>(&decoded), fingerprint::<G>(params));
+    }
+
+    // the loaded parameters are usable by the protocols
+    let path = std::env::temp_dir().join(format!("params-{}.pem", fingerprint::<G>(params)));
+    fs::write(&path, armor::encode(&envelope, TextEncoding::Armor)).unwrap();
+    let loaded = load::<G>(Some(&path), params).unwrap();
+    fs::remove_file(&path).unwrap();
+    let x = G::random_scalar(&loaded);
+    let statement = DleqStatement::<G> {
+        g1: loaded.g.clone(),
+        y1: G::exp(&loaded, &loaded.g, &x),
+        g2: loaded.h.clone(),
+        y2: G::exp(&loaded, &loaded.h, &x),
+    };
+    let proof = DleqProof::prove(&loaded, &mut Transcript::new(b"test"), &statement, &x);
+    assert!(proof.verify(&loaded, &mut Transcript::new(b"test"), &statement));
+
+    // without a file the defaults are used
+    let default = load::<G>(None, params).unwrap();
+    assert_eq!(fingerprint::<G>(&default), fingerprint::<G>(params));
+    assert!(load::<G>(Some(&path), params).is_err());
+}
+
+#[test]
+fn dlog_group_params() {
+    run_group_params::<DiscreteLog>(&custom_dlog_params());
+    assert_ne!(
+        fingerprint::<DiscreteLog>(&DLOG_GROUP_PARAMS),
+        fingerprint::<DiscreteLog>(&custom_dlog_params())
+    );
+    for params in toy_dlog_params() {
+        assert!(!DiscreteLog::is_valid_params(&params));
+        let text = Envelope::params::<DiscreteLog>(&params).to_json().unwrap();
+        assert!(decode::<DiscreteLog>(&text).is_err());
+    }
+}
+
+#[test]
+fn pallas_group_params() {
+    run_group_params::<PallasEllipticCurve>(&PALLAS_GROUP_PARAMS);
+}
+
+#[test]
+fn vesta_group_params() {
+    run_group_params::<VestaEllipticCurve>(&VESTA_GROUP_PARAMS);
+}
+
+#[test]
+fn invalid_group_params_are_rejected() {
+    let reject = |params: GroupParams<BigUint>| {
+        let text = Envelope::params::<DiscreteLog>(&params).to_json().unwrap();
+        assert!(decode::<DiscreteLog>(&text).is_err());
+    };
+    let valid = custom_dlog_params();
+    // composite modulus
+    reject(GroupParams {
+        p: &valid.p + 2u32,
+        ..valid.clone()
+    });
+    // composite order
+    reject(GroupParams {
+        q: &valid.q + 2u32,
+        ..valid.clone()
+    });
+    // order not dividing p - 1
+    reject(GroupParams {
+        q: BigUint::from(3u32),
+        ..valid.clone()
+    });
+    // trivial, equal and out of subgroup generators
+    reject(GroupParams {
+        g: BigUint::from(1u32),
+        ..valid.clone()
+    });
+    reject(GroupParams {
+        h: valid.g.clone(),
+        ..valid.clone()
+    });
+    reject(GroupParams {
+        h: &valid.p - 1u32,
+        ..valid.clone()
+    });
+
+    let mut params = PALLAS_GROUP_PARAMS.to_owned();
+    params.h = params.g;
+    let text = armor::encode(
+        &Envelope::params::<PallasEllipticCurve>(&params),
+        TextEncoding::Hex,
+    );
+    assert!(decode::<PallasEllipticCurve>(&text).is_err());
+
+    // `p` and `q` are fixed by the curve, so they cannot change the fingerprint
+    let mut params = VESTA_GROUP_PARAMS.to_owned();
+    params.q = params.g;
+    assert!(!VestaEllipticCurve::is_valid_params(&params));
+
+    // parameters of another group
+    let text = Envelope::params::<PallasEllipticCurve>(&PALLAS_GROUP_PARAMS)
+        .to_json()
+        .unwrap();
+    assert!(decode::<VestaEllipticCurve>(&text).is_err());
+}
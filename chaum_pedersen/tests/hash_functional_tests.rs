@@ -0,0 +1,22 @@
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::GroupParams;
+use chaum_pedersen::traits::IntoBytes;
+
+#[macro_use]
+mod run;
+
+fn run_hash_to_scalar<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let x = G::hash_to_scalar(params, "test", b"correct horse");
+    assert_eq!(x, G::hash_to_scalar(params, "test", b"correct horse"));
+    assert_ne!(x, G::hash_to_scalar(params, "test", b"battery staple"));
+    assert_ne!(x, G::hash_to_scalar(params, "other", b"correct horse"));
+    // the domain and the message are not simply concatenated
+    assert_ne!(
+        G::hash_to_scalar(params, "ab", b"c"),
+        G::hash_to_scalar(params, "a", b"bc")
+    );
+    // the scalar is reduced, so its encoding is no longer than the order
+    assert!(G::Scalar::to(&x).len() <= G::order_bits(params).div_ceil(8));
+}
+
+group_tests!(hash_to_scalar: run_hash_to_scalar);
@@ -0,0 +1,50 @@
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::hd::{DerivationPath, HdKey, MIN_SEED_LEN};
+use chaum_pedersen::protocol::schnorr::Signature;
+use chaum_pedersen::protocol::GroupParams;
+
+#[macro_use]
+mod run;
+
+fn run_hd<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let seed = HdKey::generate_seed();
+    let root = HdKey::from_seed(seed.expose()).unwrap();
+    let secret = |server: &str, user: &str| {
+        root.derive(&DerivationPath::login::<G>(server, user))
+            .secret::<G>(params)
+    };
+    let x = secret("example.org:50051", "peggy");
+
+    // the same seed and path give the same secret
+    let again = HdKey::from_seed(seed.expose())
+        .unwrap()
+        .derive(&DerivationPath::login::<G>("example.org:50051", "peggy"))
+        .secret::<G>(params);
+    assert!(x.expose() == again.expose());
+
+    // every server and username has its own secret
+    assert!(x.expose() != secret("example.org:50052", "peggy").expose());
+    assert!(x.expose() != secret("example.org:50051", "victor").expose());
+    let other = HdKey::from_seed(HdKey::generate_seed().expose())
+        .unwrap()
+        .derive(&DerivationPath::login::<G>("example.org:50051", "peggy"))
+        .secret::<G>(params);
+    assert!(x.expose() != other.expose());
+
+    // deriving step by step reaches the same node as deriving the path at once
+    let server = root.child("server", "example.org:50051");
+    let path = DerivationPath::login::<G>("example.org:50051", "peggy");
+    let stepwise = path.steps()[1..]
+        .iter()
+        .fold(server, |node, step| node.child(&step.label, &step.value));
+    assert!(stepwise.secret::<G>(params).expose() == x.expose());
+
+    // the derived secret signs under its key
+    let y1 = G::exp(params, &params.g, x.expose());
+    let signature = Signature::<G>::sign(params, x.expose(), b"payload");
+    assert!(signature.verify(params, &y1, b"payload"));
+
+    assert!(HdKey::from_seed(&seed.expose()[..MIN_SEED_LEN - 1]).is_err());
+}
+
+group_tests!(hd: run_hd);
@@ -0,0 +1,69 @@
+use chaum_pedersen::enums::KdfAlgorithm;
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::kdf::KdfParams;
+use chaum_pedersen::protocol::schnorr::Signature;
+use chaum_pedersen::protocol::GroupParams;
+
+#[macro_use]
+mod run;
+
+/// Low costs, to keep the tests fast.
+fn cheap_params(algorithm: KdfAlgorithm) -> KdfParams {
+    match algorithm {
+        KdfAlgorithm::Argon2id => KdfParams::with_costs(algorithm, 64, 1, 1),
+        KdfAlgorithm::Scrypt => KdfParams::with_costs(algorithm, 8, 8, 1),
+    }
+}
+
+fn run_algorithm<G: CyclicGroup>(params: &GroupParams<G::Element>, algorithm: KdfAlgorithm) {
+    let kdf = cheap_params(algorithm);
+    let x = kdf.derive_secret::<G>(params, b"correct horse").unwrap();
+
+    // the same password, salt and costs give the same secret
+    let again = kdf.derive_secret::<G>(params, b"correct horse").unwrap();
+    assert!(x.expose() == again.expose());
+
+    // another password, salt or cost gives another secret
+    let other = kdf.derive_secret::<G>(params, b"battery staple").unwrap();
+    assert!(x.expose() != other.expose());
+    let salted = KdfParams {
+        salt: cheap_params(algorithm).salt,
+        ..kdf.clone()
+    };
+    let other = salted.derive_secret::<G>(params, b"correct horse").unwrap();
+    assert!(x.expose() != other.expose());
+    let costlier = KdfParams {
+        time_cost: kdf.time_cost + 1,
+        ..kdf.clone()
+    };
+    let other = costlier
+        .derive_secret::<G>(params, b"correct horse")
+        .unwrap();
+    assert!(x.expose() != other.expose());
+
+    // the derived secret signs under its key
+    let y1 = G::exp(params, &params.g, x.expose());
+    let signature = Signature::<G>::sign(params, x.expose(), b"payload");
+    assert!(signature.verify(params, &y1, b"payload"));
+
+    // parameters out of bounds, e.g. handed out by a malicious server, are refused
+    let short_salt = KdfParams {
+        salt: vec![0; 8],
+        ..kdf.clone()
+    };
+    assert!(short_salt
+        .derive_secret::<G>(params, b"correct horse")
+        .is_err());
+    let greedy = KdfParams {
+        memory_cost: u32::MAX,
+        ..kdf
+    };
+    assert!(greedy.derive_secret::<G>(params, b"correct horse").is_err());
+}
+
+fn run_kdf<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    run_algorithm::<G>(params, KdfAlgorithm::Argon2id);
+    run_algorithm::<G>(params, KdfAlgorithm::Scrypt);
+}
+
+group_tests!(kdf: run_kdf);
@@ -1,5 +1,6 @@
 use chaum_pedersen::protocol::constants::PALLAS_GROUP_PARAMS;
 use chaum_pedersen::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+use chaum_pedersen::protocol::secret::Secret;
 use chaum_pedersen::protocol::Protocol;
 use pasta_curves::group::ff::Field;
 use pasta_curves::group::GroupEncoding;
@@ -13,7 +14,7 @@ use run::run_protocol;
 #[test]
 fn pallas_success_verification() {
     let mut rng = OsRng;
-    let x = <Scalar as Field>::random(&mut rng);
+    let x = Secret::new(<Scalar as Field>::random(&mut rng));
     let params = PALLAS_GROUP_PARAMS.to_owned();
     let gb = params.g.to_bytes();
     let restored_g = Point::from_bytes(&gb).unwrap();
@@ -27,7 +28,7 @@ fn pallas_success_verification() {
 #[test]
 fn pallas_fail_verification() {
     let mut rng = OsRng;
-    let x = <Scalar as Field>::random(&mut rng);
+    let x = Secret::new(<Scalar as Field>::random(&mut rng));
     let params = PALLAS_GROUP_PARAMS.to_owned();
     let (cp, _) = PallasEllipticCurve::commitment(&params, &x);
     let c = PallasEllipticCurve::challenge(&params);
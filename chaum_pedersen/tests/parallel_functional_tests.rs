@@ -0,0 +1,60 @@
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::parallel::{self, ParallelConfig};
+use chaum_pedersen::protocol::secret::Secret;
+use chaum_pedersen::protocol::GroupParams;
+
+#[macro_use]
+mod run;
+
+fn run_parallel<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let config = ParallelConfig::for_soundness(8, 80).unwrap();
+    let x = Secret::new(G::random_scalar(params));
+    let y1 = G::exp(params, &params.g, x.expose());
+    let y2 = G::exp(params, &params.h, x.expose());
+
+    let (commitments, nonces) = parallel::commit::<G>(params, &config);
+    let challenges = config.challenges();
+    let responses = parallel::respond::<G>(params, &config, &nonces, &challenges, &x).unwrap();
+    assert!(parallel::verify::<G>(
+        params,
+        &config,
+        (&y1, &y2),
+        &commitments,
+        &challenges,
+        &responses
+    ));
+
+    // a wrong secret is rejected, unless all the challenges happen to be zero
+    let other = Secret::new(G::random_scalar(params));
+    let challenges = loop {
+        let challenges = config.challenges();
+        if challenges.iter().any(|c| *c != 0) {
+            break challenges;
+        }
+    };
+    let responses = parallel::respond::<G>(params, &config, &nonces, &challenges, &other).unwrap();
+    assert!(!parallel::verify::<G>(
+        params,
+        &config,
+        (&y1, &y2),
+        &commitments,
+        &challenges,
+        &responses
+    ));
+
+    // fewer rounds than configured, or oversized challenges, are rejected
+    let responses = parallel::respond::<G>(params, &config, &nonces, &challenges, &x).unwrap();
+    assert!(!parallel::verify::<G>(
+        params,
+        &config,
+        (&y1, &y2),
+        &commitments[1..],
+        &challenges[1..],
+        &responses[1..]
+    ));
+    let mut oversized = challenges.clone();
+    oversized[0] |= 1 << 8;
+    assert!(parallel::respond::<G>(params, &config, &nonces, &oversized, &x).is_err());
+}
+
+group_tests!(repetition: run_parallel);
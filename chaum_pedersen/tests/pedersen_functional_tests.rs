@@ -0,0 +1,44 @@
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::pedersen::{EqualityProof, PedersenCommitment};
+use chaum_pedersen::protocol::transcript::Transcript;
+use chaum_pedersen::protocol::GroupParams;
+
+#[macro_use]
+mod run;
+
+fn run_commitments<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let v1 = G::random_scalar(params);
+    let v2 = G::random_scalar(params);
+    let (c1, opening1) = PedersenCommitment::<G>::commit(params, &v1);
+    let (c2, opening2) = PedersenCommitment::<G>::commit(params, &v2);
+    assert!(c1.verify_opening(params, &opening1));
+    assert!(!c1.verify_opening(params, &opening2));
+
+    // the sum of the commitments opens to the sum of the values
+    let sum = c1.add(params, &c2);
+    let opening = opening1.add(params, &opening2);
+    assert_eq!(opening.v, G::scalar_add(params, &v1, &v2));
+    assert!(sum.verify_opening(params, &opening));
+
+    // a second commitment to `v1` hides the same value as `c1`, `c2` does not
+    let (c3, opening3) = PedersenCommitment::<G>::commit(params, &v1);
+    let proof = EqualityProof::prove(
+        params,
+        &mut Transcript::new(b"test"),
+        (&c1, &opening1),
+        (&c3, &opening3),
+    )
+    .unwrap();
+    assert!(proof.verify(params, &mut Transcript::new(b"test"), &c1, &c3));
+
+    let fake = EqualityProof::prove(
+        params,
+        &mut Transcript::new(b"test"),
+        (&c1, &opening1),
+        (&c2, &opening2),
+    )
+    .unwrap();
+    assert!(!fake.verify(params, &mut Transcript::new(b"test"), &c1, &c2));
+}
+
+group_tests!(commitments: run_commitments);
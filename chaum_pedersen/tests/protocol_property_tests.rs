@@ -0,0 +1,248 @@
+//! Properties every `Protocol` implementation has to satisfy, checked on random and edge values.
+//!
+//! The properties are generic over the backend, `protocol_properties!` instantiates them
+//! as tests for one, so a new backend only needs one more line at the bottom of this file.
+
+use chaum_pedersen::protocol::constants::{
+    DLOG_GROUP_PARAMS, PALLAS_GROUP_PARAMS, VESTA_GROUP_PARAMS,
+};
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::secret::{Nonce, Secret};
+use chaum_pedersen::protocol::wire::{Envelope, InteractiveProof};
+use chaum_pedersen::protocol::{GroupParams, Protocol};
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestRunner};
+
+/// The raw material of a scalar: `0`, `1`, `q - 1`, or the reduction of 64 random bytes.
+type ScalarSeed = (u8, Vec<u8>);
+
+fn scalar_seed() -> impl Strategy<Value = ScalarSeed> {
+    (0u8..6, vec(any::<u8>(), 64))
+}
+
+fn scalar<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    (kind, bytes): &ScalarSeed,
+) -> G::Scalar {
+    match kind {
+        0 => G::scalar_zero(params),
+        1 => G::scalar_from_u64(params, 1),
+        2 => G::scalar_neg(params, &G::scalar_from_u64(params, 1)),
+        _ => {
+            let mut wide = [0u8; 64];
+            wide.copy_from_slice(bytes);
+            G::scalar_from_wide_bytes(params, &wide)
+        }
+    }
+}
+
+/// A `Protocol` implementation over the arithmetic of its `CyclicGroup`.
+trait Backend:
+    CyclicGroup
+    + Protocol<
+        Secret = Secret<<Self as CyclicGroup>::Scalar>,
+        Response = <Self as CyclicGroup>::Scalar,
+        Challenge = <Self as CyclicGroup>::Scalar,
+        CommitmentRandom = Nonce<<Self as CyclicGroup>::Scalar>,
+        GroupParameters = GroupParams<<Self as CyclicGroup>::Element>,
+        CommitParameters = CommitParameters<Self>,
+    >
+{
+}
+
+impl<P> Backend for P where
+    P: CyclicGroup
+        + Protocol<
+            Secret = Secret<P::Scalar>,
+            Response = P::Scalar,
+            Challenge = P::Scalar,
+            CommitmentRandom = Nonce<P::Scalar>,
+            GroupParameters = GroupParams<P::Element>,
+            CommitParameters = CommitParameters<P>,
+        >
+{
+}
+
+/// The commitment parameters `(y1, y2, r1, r2)`.
+type CommitParameters<P> = (
+    <P as CyclicGroup>::Element,
+    <P as CyclicGroup>::Element,
+    <P as CyclicGroup>::Element,
+    <P as CyclicGroup>::Element,
+);
+
+fn runner() -> TestRunner {
+    TestRunner::new(Config {
+        cases: 64,
+        ..Config::default()
+    })
+}
+
+/// One honest run: the commitment parameters `(y1, y2, r1, r2)` and the response to `c`.
+fn honest_run<P: Backend>(
+    params: &GroupParams<P::Element>,
+    x: &P::Scalar,
+    c: &P::Scalar,
+) -> (CommitParameters<P>, P::Scalar) {
+    let x = Secret::new(x.clone());
+    let (cp, k) = <P as Protocol>::commitment(params, &x);
+    let s = P::challenge_response(params, &k, c, &x);
+    (cp, s)
+}
+
+fn serialization_round_trips<P: Backend>(params: &GroupParams<P::Element>) {
+    runner()
+        .run(&(scalar_seed(), scalar_seed()), |(x, c)| {
+            let (x, c) = (scalar::<P>(params, &x), scalar::<P>(params, &c));
+            prop_assert_eq!(
+                &<P::Scalar as FromBytes<P::Scalar>>::from(&P::Scalar::to(&x)).unwrap(),
+                &x
+            );
+
+            let ((y1, y2, r1, r2), s) = honest_run::<P>(params, &x, &c);
+            for element in [&y1, &y2, &r1, &r2] {
+                prop_assert_eq!(
+                    &<P::Element as FromBytes<P::Element>>::from(&P::Element::to(element)).unwrap(),
+                    element
+                );
+            }
+            let proof = InteractiveProof::<P> {
+                y1,
+                y2,
+                r1,
+                r2,
+                c,
+                s,
+            };
+            let envelope = Envelope::interactive_proof(&proof);
+            let decoded = [
+                Envelope::from_json(&envelope.to_json().unwrap()).unwrap(),
+                Envelope::from_cbor(&envelope.to_cbor().unwrap()).unwrap(),
+                Envelope::from_binary(&envelope.to_binary()).unwrap(),
+            ];
+            for decoded in decoded {
+                let decoded = decoded.to_interactive_proof::<P>().unwrap();
+                prop_assert!(<P as Protocol>::verify(
+                    params,
+                    &decoded.s,
+                    &decoded.c,
+                    &(decoded.y1, decoded.y2, decoded.r1, decoded.r2)
+                ));
+            }
+            Ok(())
+        })
+        .unwrap();
+}
+
+fn completeness<P: Backend>(params: &GroupParams<P::Element>) {
+    runner()
+        .run(&(scalar_seed(), scalar_seed()), |(x, c)| {
+            let (x, c) = (scalar::<P>(params, &x), scalar::<P>(params, &c));
+            let (cp, s) = honest_run::<P>(params, &x, &c);
+            prop_assert!(<P as Protocol>::verify(params, &s, &c, &cp));
+
+            // and with a challenge sampled by the verifier
+            let c = <P as Protocol>::challenge(params);
+            let (cp, s) = honest_run::<P>(params, &x, &c);
+            prop_assert!(<P as Protocol>::verify(params, &s, &c, &cp));
+            Ok(())
+        })
+        .unwrap();
+}
+
+fn tampering_is_rejected<P: Backend>(params: &GroupParams<P::Element>) {
+    runner()
+        .run(
+            &(scalar_seed(), scalar_seed(), scalar_seed(), 0usize..6),
+            |(x, c, delta, target)| {
+                let (x, c) = (scalar::<P>(params, &x), scalar::<P>(params, &c));
+                let delta = scalar::<P>(params, &delta);
+                let zero = P::scalar_zero(params);
+                prop_assume!(delta != zero);
+                // with `x = 0` the challenge does not enter the verification,
+                // and with `c = 0` the statement does not
+                prop_assume!(target != 1 || x != zero);
+                prop_assume!(!(2..4).contains(&target) || c != zero);
+
+                let ((y1, y2, r1, r2), s) = honest_run::<P>(params, &x, &c);
+                let (mut cp, mut s, mut c) = ((y1, y2, r1, r2), s, c);
+                let shift = P::exp(params, &params.g, &delta);
+                match target {
+                    0 => s = P::scalar_add(params, &s, &delta),
+                    1 => c = P::scalar_add(params, &c, &delta),
+                    2 => cp.0 = P::op(params, &cp.0, &shift),
+                    3 => cp.1 = P::op(params, &cp.1, &shift),
+                    4 => cp.2 = P::op(params, &cp.2, &shift),
+                    _ => cp.3 = P::op(params, &cp.3, &shift),
+                }
+                prop_assert!(!<P as Protocol>::verify(params, &s, &c, &cp));
+                Ok(())
+            },
+        )
+        .unwrap();
+}
+
+fn wrong_secret_is_rejected<P: Backend>(params: &GroupParams<P::Element>) {
+    runner()
+        .run(
+            &(scalar_seed(), scalar_seed(), scalar_seed()),
+            |(x, wrong, c)| {
+                let (x, wrong) = (scalar::<P>(params, &x), scalar::<P>(params, &wrong));
+                let c = scalar::<P>(params, &c);
+                prop_assume!(x != wrong && c != P::scalar_zero(params));
+
+                // commits to the statement of `x` but answers with `wrong`
+                let (cp, k) = <P as Protocol>::commitment(params, &Secret::new(x));
+                let s = P::challenge_response(params, &k, &c, &Secret::new(wrong));
+                prop_assert!(!<P as Protocol>::verify(params, &s, &c, &cp));
+                Ok(())
+            },
+        )
+        .unwrap();
+}
+
+macro_rules! protocol_properties {
+    ($name:ident, $protocol:ty, $params:expr) => {
+        mod $name {
+            use super::*;
+
+            #[test]
+            fn serialization_round_trips() {
+                super::serialization_round_trips::<$protocol>(&$params);
+            }
+
+            #[test]
+            fn completeness() {
+                super::completeness::<$protocol>(&$params);
+            }
+
+            #[test]
+            fn tampering_is_rejected() {
+                super::tampering_is_rejected::<$protocol>(&$params);
+            }
+
+            #[test]
+            fn wrong_secret_is_rejected() {
+                super::wrong_secret_is_rejected::<$protocol>(&$params);
+            }
+        }
+    };
+}
+
+protocol_properties!(
+    dlog,
+    chaum_pedersen::protocol::discrete_log::DiscreteLog,
+    DLOG_GROUP_PARAMS
+);
+protocol_properties!(
+    pallas,
+    chaum_pedersen::protocol::elliptic_curves::pallas::PallasEllipticCurve,
+    PALLAS_GROUP_PARAMS
+);
+protocol_properties!(
+    vesta,
+    chaum_pedersen::protocol::elliptic_curves::vesta::VestaEllipticCurve,
+    VESTA_GROUP_PARAMS
+);
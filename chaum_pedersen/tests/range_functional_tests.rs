@@ -0,0 +1,49 @@
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::pedersen::{Opening, PedersenCommitment};
+use chaum_pedersen::protocol::range::RangeProof;
+use chaum_pedersen::protocol::transcript::Transcript;
+use chaum_pedersen::protocol::GroupParams;
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+
+#[macro_use]
+mod run;
+
+/// Proves that `v` lies in `[0, 2^8)` and returns whether the proof verifies.
+fn prove_range<G: CyclicGroup>(params: &GroupParams<G::Element>, v: u64) -> bool {
+    let (commitment, opening) =
+        PedersenCommitment::<G>::commit(params, &G::scalar_from_u64(params, v));
+    let proof = RangeProof::prove(params, &mut Transcript::new(b"test"), &opening, 8).unwrap();
+
+    // ensure reversability of (de)serialization operations
+    let bytes = RangeProof::to(&proof);
+    let recovered = <RangeProof<G> as FromBytes<_>>::from(&bytes).unwrap();
+    assert_eq!(proof, recovered);
+
+    recovered.verify(params, &mut Transcript::new(b"test"), &commitment, 8)
+}
+
+fn run_out_of_range<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    // an honest prover can not prove a value above the bound
+    let (_, opening) = PedersenCommitment::<G>::commit(params, &G::scalar_from_u64(params, 256));
+    assert!(RangeProof::prove(params, &mut Transcript::new(b"test"), &opening, 8).is_err());
+
+    // a proof for one commitment does not verify against another
+    let (_, opening) = PedersenCommitment::<G>::commit(params, &G::scalar_from_u64(params, 18));
+    let proof = RangeProof::prove(params, &mut Transcript::new(b"test"), &opening, 8).unwrap();
+    let other = PedersenCommitment::open(
+        params,
+        &Opening {
+            v: G::scalar_from_u64(params, 300),
+            r: opening.r.clone(),
+        },
+    );
+    assert!(!proof.verify(params, &mut Transcript::new(b"test"), &other, 8));
+}
+
+fn run_range<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    for v in [0, 1, 18, 255] {
+        assert!(prove_range::<G>(params, v));
+    }
+}
+
+group_tests!(range: run_range, run_out_of_range);
@@ -0,0 +1,45 @@
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::representation::RepresentationProof;
+use chaum_pedersen::protocol::transcript::Transcript;
+use chaum_pedersen::protocol::GroupParams;
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+
+#[macro_use]
+mod run;
+
+/// Proves knowledge of a random representation in `g`, `h` and `g·h`, optionally with a wrong secret.
+fn prove_representation<G: CyclicGroup>(params: &GroupParams<G::Element>, tamper: bool) -> bool {
+    let generators = vec![
+        params.g.clone(),
+        params.h.clone(),
+        G::op(params, &params.g, &params.h),
+    ];
+    let mut secrets: Vec<G::Scalar> = (0..3).map(|_| G::random_scalar(params)).collect();
+    let y = RepresentationProof::<G>::statement(params, &generators, &secrets).unwrap();
+    if tamper {
+        secrets[1] = G::scalar_add(params, &secrets[1], &G::scalar_from_u64(params, 1));
+    }
+
+    let proof = RepresentationProof::<G>::prove(
+        params,
+        &mut Transcript::new(b"test"),
+        &generators,
+        &y,
+        &secrets,
+    )
+    .unwrap();
+
+    // ensure reversability of (de)serialization operations
+    let bytes = RepresentationProof::<G>::to(&proof);
+    let recovered = <RepresentationProof<G> as FromBytes<_>>::from(&bytes).unwrap();
+    assert_eq!(proof, recovered);
+
+    recovered.verify(params, &mut Transcript::new(b"test"), &generators, &y)
+}
+
+fn run_representation<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    assert!(prove_representation::<G>(params, false));
+    assert!(!prove_representation::<G>(params, true));
+}
+
+group_tests!(representation: run_representation);
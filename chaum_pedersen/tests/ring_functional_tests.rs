@@ -0,0 +1,55 @@
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::ring::RingSignature;
+use chaum_pedersen::protocol::GroupParams;
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+
+#[macro_use]
+mod run;
+
+fn run_ring<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let secrets: Vec<G::Scalar> = (0..4).map(|_| G::random_scalar(params)).collect();
+    let ring: Vec<G::Element> = secrets
+        .iter()
+        .map(|x| G::exp(params, &params.g, x))
+        .collect();
+
+    let first =
+        RingSignature::<G>::sign(params, &ring, 2, &secrets[2], b"poll", b"great talk").unwrap();
+    assert!(first.verify(params, &ring, b"poll", b"great talk"));
+
+    // ensure reversability of (de)serialization operations
+    let recovered = <RingSignature<G> as FromBytes<_>>::from(&RingSignature::to(&first)).unwrap();
+    assert_eq!(first, recovered);
+
+    // signatures of the same member are linked, those of distinct members are not
+    let second =
+        RingSignature::<G>::sign(params, &ring, 2, &secrets[2], b"poll", b"boring talk").unwrap();
+    assert!(second.verify(params, &ring, b"poll", b"boring talk"));
+    assert!(first.is_linked(&second));
+    let third =
+        RingSignature::<G>::sign(params, &ring, 0, &secrets[0], b"poll", b"great talk").unwrap();
+    assert!(!first.is_linked(&third));
+
+    // the signature does not hold for another topic, message or ring
+    assert!(!first.verify(params, &ring, b"other poll", b"great talk"));
+    assert!(!first.verify(params, &ring, b"poll", b"boring talk"));
+    assert!(!first.verify(params, &ring[..3], b"poll", b"great talk"));
+    let mut other_ring = ring.clone();
+    other_ring[1] = G::exp(params, &params.g, &G::random_scalar(params));
+    assert!(!first.verify(params, &other_ring, b"poll", b"great talk"));
+
+    // a key image unrelated to the signer's key is rejected
+    let forged = RingSignature {
+        key_image: G::exp(params, &params.h, &secrets[2]),
+        ..first
+    };
+    assert!(!forged.verify(params, &ring, b"poll", b"great talk"));
+
+    // a non member can not sign
+    let outsider = G::random_scalar(params);
+    let forged =
+        RingSignature::<G>::sign(params, &ring, 1, &outsider, b"poll", b"great talk").unwrap();
+    assert!(!forged.verify(params, &ring, b"poll", b"great talk"));
+}
+
+group_tests!(ring: run_ring);
@@ -1,6 +1,7 @@
 use chaum_pedersen::protocol::Protocol;
 
 /// Runs the Chaum-Pedersen protocol using a generic implementation.
+#[allow(dead_code)]
 pub fn run_protocol<T>(params: &T::GroupParameters, x: &T::Secret) -> bool
 where
     T: Protocol,
@@ -12,8 +13,39 @@ where
     let c = T::challenge(params);
 
     // The client calculates the response.
-    let s = T::challenge_response(params, &k, &c, &x);
+    let s = T::challenge_response(params, &k, &c, x);
 
     // The server verifies the response.
     T::verify(params, &s, &c, &cp)
 }
+
+/// Declares the module `$name` with a test per backend, calling every `$run::<G>(params)` with the
+/// built-in parameters of the group.
+#[allow(unused_macros)]
+macro_rules! group_tests {
+    ($name:ident: $($run:ident),+ $(,)?) => {
+        mod $name {
+            use chaum_pedersen::protocol::constants::{
+                DLOG_GROUP_PARAMS, PALLAS_GROUP_PARAMS, VESTA_GROUP_PARAMS,
+            };
+            use chaum_pedersen::protocol::discrete_log::DiscreteLog;
+            use chaum_pedersen::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+            use chaum_pedersen::protocol::elliptic_curves::vesta::VestaEllipticCurve;
+
+            #[test]
+            fn dlog() {
+                $(super::$run::<DiscreteLog>(&DLOG_GROUP_PARAMS);)+
+            }
+
+            #[test]
+            fn pallas() {
+                $(super::$run::<PallasEllipticCurve>(&PALLAS_GROUP_PARAMS);)+
+            }
+
+            #[test]
+            fn vesta() {
+                $(super::$run::<VestaEllipticCurve>(&VESTA_GROUP_PARAMS);)+
+            }
+        }
+    };
+}
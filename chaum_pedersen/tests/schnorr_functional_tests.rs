@@ -0,0 +1,44 @@
+use chaum_pedersen::protocol::contexts;
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::schnorr::Signature;
+use chaum_pedersen::protocol::GroupParams;
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+
+#[macro_use]
+mod run;
+
+fn run_schnorr<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let x = G::random_scalar(params);
+    let y1 = G::exp(params, &params.g, &x);
+
+    let signature = Signature::<G>::sign(params, &x, b"transfer 10 to bob");
+    assert!(signature.verify(params, &y1, b"transfer 10 to bob"));
+
+    // ensure reversability of (de)serialization operations
+    let recovered = <Signature<G> as FromBytes<_>>::from(&Signature::to(&signature)).unwrap();
+    assert_eq!(signature, recovered);
+
+    // the signature does not hold for another message or key
+    assert!(!signature.verify(params, &y1, b"transfer 99 to bob"));
+    let other = G::exp(params, &params.g, &G::random_scalar(params));
+    assert!(!signature.verify(params, &other, b"transfer 10 to bob"));
+    let tampered = Signature {
+        s: G::scalar_add(params, &signature.s, &G::scalar_from_u64(params, 1)),
+        ..signature
+    };
+    assert!(!tampered.verify(params, &y1, b"transfer 10 to bob"));
+
+    // a signature in context only holds for the same user and nonce
+    let context = contexts::action("peggy", "nonce-1");
+    let signature = Signature::<G>::sign_in_context(params, &context, &x, b"transfer 10 to bob");
+    assert!(signature.verify_in_context(params, &context, &y1, b"transfer 10 to bob"));
+    assert!(!signature.verify(params, &y1, b"transfer 10 to bob"));
+    for other in [
+        contexts::action("victor", "nonce-1"),
+        contexts::action("peggy", "nonce-2"),
+    ] {
+        assert!(!signature.verify_in_context(params, &other, &y1, b"transfer 10 to bob"));
+    }
+}
+
+group_tests!(schnorr: run_schnorr);
@@ -0,0 +1,89 @@
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::sigma::{LinearRelation, SigmaProof};
+use chaum_pedersen::protocol::transcript::Transcript;
+use chaum_pedersen::protocol::GroupParams;
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+
+#[macro_use]
+mod run;
+
+/// `y1 = g^x, y2 = h^x, C = g^x · h^r`.
+fn declare<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    y1: G::Element,
+    y2: G::Element,
+    c: G::Element,
+) -> LinearRelation<G> {
+    let mut relation = LinearRelation::new();
+    let x = relation.scalar();
+    let r = relation.scalar();
+    relation.equation(y1, &[(x, params.g.clone())]).unwrap();
+    relation.equation(y2, &[(x, params.h.clone())]).unwrap();
+    relation
+        .equation(c, &[(x, params.g.clone()), (r, params.h.clone())])
+        .unwrap();
+    relation
+}
+
+fn run_sigma<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let x = G::random_scalar(params);
+    let r = G::random_scalar(params);
+    let witness = vec![x.clone(), r.clone()];
+    let relation = declare::<G>(
+        params,
+        G::exp(params, &params.g, &x),
+        G::exp(params, &params.h, &x),
+        G::multi_exp(params, &[params.g.clone(), params.h.clone()], &[x, r]),
+    );
+    assert!(relation.is_satisfied(params, &witness));
+
+    // interactive run
+    let (commitments, nonces) = relation.commit(params);
+    let c = G::random_scalar(params);
+    let responses = relation.respond(params, &nonces, &c, &witness).unwrap();
+    assert!(relation.check(params, &commitments, &c, &responses));
+
+    // the simulator produces accepting transcripts without the witness
+    let (simulated, responses) = relation.simulate(params, &c);
+    assert!(relation.check(params, &simulated, &c, &responses));
+
+    // Fiat-Shamir
+    let proof =
+        SigmaProof::prove(params, &mut Transcript::new(b"test"), &relation, &witness).unwrap();
+    assert!(proof.verify(params, &mut Transcript::new(b"test"), &relation));
+
+    // ensure reversability of (de)serialization operations
+    let recovered = <SigmaProof<G> as FromBytes<_>>::from(&SigmaProof::to(&proof)).unwrap();
+    assert_eq!(proof, recovered);
+
+    // a witness breaking one of the equations does not convince
+    let y2 = G::exp(params, &params.h, &G::random_scalar(params));
+    let false_relation = declare::<G>(
+        params,
+        relation.equations()[0].lhs.clone(),
+        y2,
+        relation.equations()[2].lhs.clone(),
+    );
+    assert!(!false_relation.is_satisfied(params, &witness));
+    let proof = SigmaProof::prove(
+        params,
+        &mut Transcript::new(b"test"),
+        &false_relation,
+        &witness,
+    )
+    .unwrap();
+    assert!(!proof.verify(params, &mut Transcript::new(b"test"), &false_relation));
+
+    // variables of another relation are rejected
+    let mut other = LinearRelation::<G>::new();
+    assert!(other
+        .equation(params.g.clone(), &[(relation_var::<G>(), params.g.clone())])
+        .is_err());
+}
+
+fn relation_var<G: CyclicGroup>() -> chaum_pedersen::protocol::sigma::ScalarVar {
+    let mut relation = LinearRelation::<G>::new();
+    relation.scalar()
+}
+
+group_tests!(sigma: run_sigma);
@@ -0,0 +1,44 @@
+use chaum_pedersen::protocol::elgamal::{Ciphertext, KeyPair};
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::threshold::{deal, PartialDecryption};
+use chaum_pedersen::protocol::transcript::Transcript;
+use chaum_pedersen::protocol::GroupParams;
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+
+#[macro_use]
+mod run;
+
+fn run_threshold<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let context = Transcript::new(b"election-1");
+    let (public_key, shares) = deal::<G>(params, 3, 5).unwrap();
+    let m = G::exp(params, &params.g, &G::random_scalar(params));
+    let ciphertext = Ciphertext::encrypt(params, &public_key.y, &m);
+
+    let mut partials: Vec<PartialDecryption<G>> = shares
+        .iter()
+        .skip(1)
+        .map(|share| share.partial_decryption(params, &context, &ciphertext))
+        .collect();
+
+    // ensure reversability of (de)serialization operations
+    let bytes = PartialDecryption::to(&partials[0]);
+    let recovered = <PartialDecryption<G> as FromBytes<_>>::from(&bytes).unwrap();
+    assert_eq!(partials[0], recovered);
+
+    // a trustee publishing a share computed with a wrong key is discarded
+    let cheater = KeyPair::<G>::generate(params);
+    partials[0].share = cheater.decryption_share(params, &mut context.clone(), &ciphertext);
+
+    let combined = public_key
+        .combine(params, &context, &ciphertext, &partials)
+        .unwrap();
+    assert_eq!(combined.plaintext, m);
+    assert_eq!(combined.rejected, vec![2]);
+
+    // two honest shares are not enough
+    assert!(public_key
+        .combine(params, &context, &ciphertext, &partials[..3])
+        .is_err());
+}
+
+group_tests!(threshold: run_threshold);
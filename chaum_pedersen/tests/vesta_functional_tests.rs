@@ -1,4 +1,5 @@
 use chaum_pedersen::protocol::elliptic_curves::vesta::VestaEllipticCurve;
+use chaum_pedersen::protocol::secret::Secret;
 use chaum_pedersen::protocol::Protocol;
 use pasta_curves::group::ff::Field;
 use pasta_curves::group::GroupEncoding;
@@ -14,7 +15,7 @@ use run::run_protocol;
 #[test]
 fn vesta_success_verification() {
     let mut rng = OsRng;
-    let x = <Scalar as Field>::random(&mut rng);
+    let x = Secret::new(<Scalar as Field>::random(&mut rng));
     let params = VESTA_GROUP_PARAMS.to_owned();
     let hb = params.h.to_bytes();
     let restored_h = Point::from_bytes(&hb).unwrap();
@@ -25,7 +26,7 @@ fn vesta_success_verification() {
 #[test]
 fn vesta_fail_verification() {
     let mut rng = OsRng;
-    let x = <Scalar as Field>::random(&mut rng);
+    let x = Secret::new(<Scalar as Field>::random(&mut rng));
     let params = VESTA_GROUP_PARAMS.to_owned();
     let (cp, _) = VestaEllipticCurve::commitment(&params, &x);
     let c = VestaEllipticCurve::challenge(&params);
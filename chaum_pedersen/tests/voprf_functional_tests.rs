@@ -0,0 +1,54 @@
+use chaum_pedersen::protocol::elgamal::KeyPair;
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::transcript::Transcript;
+use chaum_pedersen::protocol::voprf::{self, Evaluation};
+use chaum_pedersen::protocol::GroupParams;
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+
+#[macro_use]
+mod run;
+
+fn run_voprf<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let context = Transcript::new(b"voprf-test");
+    let key = KeyPair::<G>::generate(params);
+
+    // hashing to the group is deterministic and lands in the prime order subgroup
+    let p = G::hash_to_element(params, voprf::HASH_TO_GROUP_DOMAIN, b"alice");
+    assert!(G::contains(params, &p));
+    assert_eq!(
+        p,
+        G::hash_to_element(params, voprf::HASH_TO_GROUP_DOMAIN, b"alice")
+    );
+    assert_ne!(p, G::hash_to_element(params, "other-domain", b"alice"));
+
+    let blinded = vec![
+        voprf::blind::<G>(params, b"alice"),
+        voprf::blind::<G>(params, b"bob"),
+        voprf::blind::<G>(params, b"alice"),
+    ];
+    // the same input is blinded differently every time
+    assert_ne!(blinded[0].element, blinded[2].element);
+
+    let request: Vec<G::Element> = blinded.iter().map(|b| b.element.clone()).collect();
+    let evaluation = voprf::blind_evaluate(params, &context, &key, &request).unwrap();
+    let outputs = voprf::finalize(params, &context, &key.y, &blinded, &evaluation).unwrap();
+    assert_eq!(outputs[0], outputs[2]);
+    assert_ne!(outputs[0], outputs[1]);
+    assert_eq!(outputs[0], voprf::evaluate(params, &key, b"alice"));
+
+    // ensure reversability of (de)serialization operations
+    let recovered = <Evaluation<G> as FromBytes<_>>::from(&Evaluation::to(&evaluation)).unwrap();
+    assert_eq!(evaluation, recovered);
+
+    // a server using another key for one of the elements is caught
+    let other = KeyPair::<G>::generate(params);
+    let mut tampered = evaluation.clone();
+    tampered.elements[1] = G::exp(params, &request[1], &other.x);
+    assert!(voprf::finalize(params, &context, &key.y, &blinded, &tampered).is_err());
+    assert!(voprf::finalize(params, &context, &other.y, &blinded, &evaluation).is_err());
+
+    // the identity is never evaluated
+    assert!(voprf::blind_evaluate(params, &context, &key, &[G::identity(params)]).is_err());
+}
+
+group_tests!(oprf: run_voprf);
@@ -0,0 +1,38 @@
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::vrf::VrfProof;
+use chaum_pedersen::protocol::GroupParams;
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+
+#[macro_use]
+mod run;
+
+fn run_vrf<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    // the same secret as the one registered for authentication, y1 = g^x
+    let x = G::random_scalar(params);
+    let y = G::exp(params, &params.g, &x);
+
+    let (output, proof) = VrfProof::<G>::prove(params, &x, b"round 1");
+    assert_eq!(proof.verify(params, &y, b"round 1").unwrap(), output);
+
+    // the output is unique per message
+    let (again, _) = VrfProof::<G>::prove(params, &x, b"round 1");
+    assert_eq!(again, output);
+    let (other, _) = VrfProof::<G>::prove(params, &x, b"round 2");
+    assert_ne!(other, output);
+
+    // ensure reversability of (de)serialization operations
+    let recovered = <VrfProof<G> as FromBytes<_>>::from(&VrfProof::to(&proof)).unwrap();
+    assert_eq!(proof, recovered);
+
+    // the proof does not hold for another message, key or output
+    assert!(proof.verify(params, &y, b"round 2").is_err());
+    let y_other = G::exp(params, &params.g, &G::random_scalar(params));
+    assert!(proof.verify(params, &y_other, b"round 1").is_err());
+    let tampered = VrfProof {
+        gamma: G::op(params, &proof.gamma, &params.g),
+        ..proof
+    };
+    assert!(tampered.verify(params, &y, b"round 1").is_err());
+}
+
+group_tests!(vrf: run_vrf);
@@ -0,0 +1,44 @@
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::shamir::{self, Share};
+use chaum_pedersen::protocol::vss::{FeldmanVss, PedersenVss};
+use chaum_pedersen::protocol::GroupParams;
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+
+#[macro_use]
+mod run;
+
+fn run_feldman<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let secret = G::random_scalar(params);
+    let (vss, mut shares) = FeldmanVss::<G>::deal(params, &secret, 3, 5).unwrap();
+    assert!(shares.iter().all(|share| vss.verify_share(params, share)));
+    assert_eq!(vss.public_key(), Some(&G::exp(params, &params.g, &secret)));
+    assert_eq!(shamir::recover(params, &shares[2..]).unwrap(), secret);
+
+    // ensure reversability of (de)serialization operations
+    let recovered = <FeldmanVss<G> as FromBytes<_>>::from(&FeldmanVss::to(&vss)).unwrap();
+    assert_eq!(vss, recovered);
+
+    // a corrupted share is detected by its holder
+    shares[0].value = G::scalar_add(params, &shares[0].value, &G::scalar_from_u64(params, 1));
+    assert!(!vss.verify_share(params, &shares[0]));
+}
+
+fn run_pedersen<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let secret = G::random_scalar(params);
+    let (vss, mut shares) = PedersenVss::<G>::deal(params, &secret, 2, 4).unwrap();
+    assert!(shares.iter().all(|share| vss.verify_share(params, share)));
+
+    let plain: Vec<Share<G>> = shares
+        .iter()
+        .map(|share| Share {
+            index: share.index,
+            value: share.value.clone(),
+        })
+        .collect();
+    assert_eq!(shamir::recover(params, &plain[1..3]).unwrap(), secret);
+
+    shares[3].blinding = G::random_scalar(params);
+    assert!(!vss.verify_share(params, &shares[3]));
+}
+
+group_tests!(vss: run_feldman, run_pedersen);
@@ -0,0 +1,89 @@
+use chaum_pedersen::protocol::dleq::{DleqProof, DleqStatement};
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::schnorr::Signature;
+use chaum_pedersen::protocol::transcript::Transcript;
+use chaum_pedersen::protocol::wire::{Envelope, InteractiveProof};
+use chaum_pedersen::protocol::GroupParams;
+
+#[macro_use]
+mod run;
+
+/// Checks that the envelope survives every encoding.
+fn round_trip(envelope: &Envelope) {
+    let json = envelope.to_json().unwrap();
+    assert_eq!(&Envelope::from_json(&json).unwrap(), envelope);
+    let cbor = envelope.to_cbor().unwrap();
+    assert_eq!(&Envelope::from_cbor(&cbor).unwrap(), envelope);
+    let binary = envelope.to_binary();
+    assert_eq!(&Envelope::from_binary(&binary).unwrap(), envelope);
+    assert!(binary.len() < cbor.len());
+}
+
+fn run_wire<G: CyclicGroup>(params: &GroupParams<G::Element>) {
+    let envelope = Envelope::params::<G>(params);
+    round_trip(&envelope);
+    let decoded = envelope.to_params::<G>().unwrap();
+    assert_eq!(
+        (decoded.g, decoded.h, decoded.p, decoded.q),
+        (
+            params.g.clone(),
+            params.h.clone(),
+            params.p.clone(),
+            params.q.clone()
+        )
+    );
+
+    let x = G::random_scalar(params);
+    let y1 = G::exp(params, &params.g, &x);
+    let y2 = G::exp(params, &params.h, &x);
+    let envelope = Envelope::statement::<G>(&y1, &y2);
+    round_trip(&envelope);
+    assert_eq!(
+        envelope.to_statement::<G>().unwrap(),
+        (y1.clone(), y2.clone())
+    );
+    // a statement is not a commitment
+    assert!(envelope.to_commitment::<G>().is_err());
+
+    let k = G::random_scalar(params);
+    let r1 = G::exp(params, &params.g, &k);
+    let r2 = G::exp(params, &params.h, &k);
+    let envelope = Envelope::commitment::<G>(&r1, &r2);
+    round_trip(&envelope);
+    assert_eq!(
+        envelope.to_commitment::<G>().unwrap(),
+        (r1.clone(), r2.clone())
+    );
+
+    let c = G::random_scalar(params);
+    let s = G::scalar_add(params, &k, &G::scalar_mul(params, &c, &x));
+    let proof = InteractiveProof::<G> {
+        y1: y1.clone(),
+        y2: y2.clone(),
+        r1,
+        r2,
+        c,
+        s,
+    };
+    let envelope = Envelope::interactive_proof(&proof);
+    round_trip(&envelope);
+    let decoded = envelope.to_interactive_proof::<G>().unwrap();
+    assert_eq!(decoded, proof);
+    assert!(decoded.verify(params));
+
+    let statement = DleqStatement::<G> {
+        g1: params.g.clone(),
+        y1,
+        g2: params.h.clone(),
+        y2,
+    };
+    let proof = DleqProof::prove(params, &mut Transcript::new(b"test"), &statement, &x);
+    let envelope = Envelope::proof(&proof);
+    round_trip(&envelope);
+    let decoded = envelope.to_proof::<DleqProof<G>>().unwrap();
+    assert!(decoded.verify(params, &mut Transcript::new(b"test"), &statement));
+    // the scheme name is checked
+    assert!(envelope.to_proof::<Signature<G>>().is_err());
+}
+
+group_tests!(envelopes: run_wire);
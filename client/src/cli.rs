@@ -1,4 +1,5 @@
-use chaum_pedersen::enums::{EllipticCurve, Flavor};
+use chaum_pedersen::enums::{EllipticCurve, Flavor, KdfAlgorithm, TextEncoding};
+use std::path::PathBuf;
 use structopt::StructOpt;
 use strum::VariantNames;
 
@@ -11,6 +12,29 @@ pub struct Cli {
     pub port: i32,
     #[structopt(short, long)]
     pub secret: Option<String>,
+    /// File holding an encoded secret key, used instead of `--secret`.
+    #[structopt(long, parse(from_os_str))]
+    pub secret_key: Option<PathBuf>,
+    /// File holding the hex encoded master seed, the secret being derived from it for the host,
+    /// port, user and flavor. Takes precedence over `--secret`.
+    #[structopt(long, parse(from_os_str))]
+    pub seed: Option<PathBuf>,
+    /// Write a fresh master seed to the `--seed` file, and exit without connecting.
+    #[structopt(long, requires = "seed")]
+    pub generate_seed: bool,
+    /// Only derive the secret from `--secret` with this memory-hard function.
+    #[structopt(long, possible_values = KdfAlgorithm::VARIANTS)]
+    pub kdf: Option<KdfAlgorithm>,
+    /// Smallest memory of the derivation accepted, in KiB for argon2id or as log2(N) for scrypt.
+    /// Costs below the recommended ones of the algorithm are never accepted.
+    #[structopt(long, requires = "kdf")]
+    pub kdf_memory_cost: Option<u32>,
+    /// Smallest number of iterations of argon2id, or block size r of scrypt, accepted.
+    #[structopt(long, requires = "kdf")]
+    pub kdf_time_cost: Option<u32>,
+    /// Smallest number of lanes of argon2id, or parallelization p of scrypt, accepted.
+    #[structopt(long, requires = "kdf")]
+    pub kdf_parallelism: Option<u32>,
     #[structopt(short, long, default_value = "peggy")]
     pub user: String,
     /// Underlying type of the Chaum-Pedersen protocol to use.
@@ -19,4 +43,30 @@ pub struct Cli {
     /// Elliptic curve type for the Elliptic Curve implementation.
     #[structopt(short, long, possible_values = EllipticCurve::VARIANTS, default_value = "pallas", required_if("flavor", "elliptic_curve"))]
     pub curve: EllipticCurve,
+    /// File holding custom group parameters for the flavor, in JSON or any text encoding.
+    #[structopt(long, env = "ZKP_PARAMS", parse(from_os_str))]
+    pub params: Option<PathBuf>,
+    /// Action payload to sign and have authorized by the server after logging in.
+    #[structopt(short, long)]
+    pub action: Option<String>,
+    /// After logging in, migrate the registration to the other curve of the Pasta cycle, served at
+    /// this address (host:port, see `server --migration-port`), and log in there. The secret stays
+    /// the same, it has to be below the orders of both curves.
+    #[structopt(short, long)]
+    pub migrate: Option<String>,
+    /// Migrate to a fresh secret of the other curve instead, which the current one signs over to.
+    /// Secrets of any size can be re-keyed, and passwords and seeds derive the new secret again.
+    #[structopt(long, requires = "migrate")]
+    pub rekey: bool,
+    /// Write the secret key of the other curve to this new file when migrating, to log in there
+    /// with `--secret-key` afterwards. Required unless re-keying a password or a seed.
+    #[structopt(long, requires = "migrate", parse(from_os_str))]
+    pub migrated_key: Option<PathBuf>,
+    /// Authenticate in parallel rounds with short challenges, if the server supports it.
+    #[structopt(long)]
+    pub parallel: bool,
+    /// Print the secret and public keys in this text encoding, and exit. Connects only to fetch the
+    /// key derivation parameters of `--secret`.
+    #[structopt(long, possible_values = TextEncoding::VARIANTS)]
+    pub export: Option<TextEncoding>,
 }
@@ -3,19 +3,87 @@ pub mod service;
 pub mod utils;
 
 use crate::cli::Cli;
-use crate::utils::hash_or_generate_random;
+use crate::utils::{
+    export_keys, fetch_kdf_params, generate_seed, load_secret, load_seed, rekeyed_secret,
+    write_secret_key, KdfCosts, SecretSource,
+};
 use chaum_pedersen::enums::{EllipticCurve, Flavor};
 use chaum_pedersen::protocol::constants::DLOG_GROUP_PARAMS;
 use chaum_pedersen::protocol::constants::PALLAS_GROUP_PARAMS;
 use chaum_pedersen::protocol::constants::VESTA_GROUP_PARAMS;
+use chaum_pedersen::protocol::cross_group::CrossGroupProof;
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::group_params;
+use chaum_pedersen::protocol::secret::Secret;
 use chaum_pedersen::protocol::{
     discrete_log::DiscreteLog, elliptic_curves::pallas::PallasEllipticCurve,
     elliptic_curves::vesta::VestaEllipticCurve,
 };
-use service::run_protocol;
+use chaum_pedersen::protocol::{GroupParams, Protocol};
+use chaum_pedersen::traits::{FromBytes, IntoBytes, Random};
 use service::AuthClientService;
+use service::{
+    authorize_action, login, migrate_user, rekey_user, run_parallel_protocol, run_protocol,
+};
+use std::path::Path;
 use structopt::StructOpt;
 
+/// Where and how to migrate the registration after logging in.
+struct Migration<'a> {
+    /// Address of the server of the other curve.
+    target: &'a str,
+    /// Whether to re-key to a fresh secret instead of keeping it.
+    rekey: bool,
+    /// File to write the secret key of the other curve to.
+    key: Option<&'a Path>,
+}
+
+/// Migrates the registration from `G1` to `G2`, keeping the secret or re-keying, and logs in on
+/// the server of `G2`.
+async fn migrate_and_login<G1, G2>(
+    params1: &GroupParams<G1::Element>,
+    params2: &GroupParams<G2::Element>,
+    x: &Secret<G1::Scalar>,
+    source: &SecretSource<'_>,
+    user: &str,
+    migration: &Migration<'_>,
+    client: &mut AuthClientService,
+) -> anyhow::Result<()>
+where
+    G1: CyclicGroup,
+    G2: CyclicGroup
+        + Protocol<
+            Secret = Secret<G2::Scalar>,
+            GroupParameters = GroupParams<G2::Element>,
+            CommitParameters = (G2::Element, G2::Element, G2::Element, G2::Element),
+            Response = G2::Scalar,
+            Challenge = G2::Scalar,
+        >,
+    G2::Element: FromBytes<G2::Element> + IntoBytes<G2::Element> + Random<G2::Element>,
+    G2::Scalar: FromBytes<G2::Scalar> + IntoBytes<G2::Scalar> + Random<G2::Scalar>,
+{
+    let x2 = if migration.rekey {
+        rekeyed_secret::<G2>(params2, source, migration.target)?
+    } else {
+        CrossGroupProof::<G1, G2>::convert_secret(params1, params2, x.expose())
+            .map(Secret::new)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Secret is too large to be migrated, re-key it with --rekey")
+            })?
+    };
+    // kept before migrating, so that a failure to write it does not lose the new registration
+    if let Some(path) = migration.key {
+        write_secret_key::<G2>(path, &x2)?;
+    }
+    if migration.rekey {
+        rekey_user::<G1, G2>(params1, params2, x, &x2, user, client).await?;
+    } else {
+        migrate_user::<G1, G2>(params1, params2, x, &x2, user, client).await?;
+    }
+    let mut target = AuthClientService::connect(format!("http://{}", migration.target)).await?;
+    login::<G2, _, _>(params2, &x2, user, &mut target).await
+}
+
 /// This starts a client to interact with a server implementing the Chaum-Pedersen protocol through a CLI.
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -24,10 +92,86 @@ async fn main() -> anyhow::Result<()> {
         port,
         flavor,
         curve,
+        params,
         user,
         secret,
+        secret_key,
+        seed,
+        generate_seed: new_seed,
+        kdf,
+        kdf_memory_cost,
+        kdf_time_cost,
+        kdf_parallelism,
+        action,
+        migrate,
+        rekey,
+        migrated_key,
+        parallel,
+        export,
     } = Cli::from_args();
 
+    if let (Some(path), true) = (&seed, new_seed) {
+        return generate_seed(path);
+    }
+    let seed = seed.as_deref().map(load_seed).transpose()?;
+    // the secret derived from a seed belongs to the server at this address
+    let server = format!("{}:{}", host, port);
+    let mut source = SecretSource::pick(
+        secret_key.as_deref(),
+        seed.as_ref(),
+        secret.as_deref(),
+        &server,
+        &user,
+    );
+    // only a re-keyed password or seed derives the secret of the other curve again
+    let rederived = rekey
+        && matches!(
+            source,
+            SecretSource::Seed { .. } | SecretSource::Password { .. }
+        );
+    if migrate.is_some() && migrated_key.is_none() && !rederived {
+        anyhow::bail!("Migrating needs --migrated-key to keep the secret key of the other curve");
+    }
+    let migration = migrate.as_deref().map(|target| Migration {
+        target,
+        rekey,
+        key: migrated_key.as_deref(),
+    });
+    let params_file = params.as_deref();
+    // a password is stretched with the salt and costs of the user's registration, which the server
+    // holds, so even an export needs to connect for it
+    let mut client = None;
+    if let SecretSource::Password { kdf: params, .. } = &mut source {
+        let costs = KdfCosts {
+            algorithm: kdf,
+            memory_cost: kdf_memory_cost,
+            time_cost: kdf_time_cost,
+            parallelism: kdf_parallelism,
+        };
+        let mut connected = AuthClientService::connect(format!("http://{}:{}", host, port)).await?;
+        *params = Some(fetch_kdf_params(&mut connected, &user, costs).await?);
+        client = Some(connected);
+    }
+    if let Some(encoding) = export {
+        return match (flavor, curve) {
+            (Flavor::DiscreteLog, _) => export_keys::<DiscreteLog>(
+                &group_params::load::<DiscreteLog>(params_file, &DLOG_GROUP_PARAMS)?,
+                &source,
+                encoding,
+            ),
+            (Flavor::EllipticCurve, EllipticCurve::Pallas) => export_keys::<PallasEllipticCurve>(
+                &group_params::load::<PallasEllipticCurve>(params_file, &PALLAS_GROUP_PARAMS)?,
+                &source,
+                encoding,
+            ),
+            (Flavor::EllipticCurve, EllipticCurve::Vesta) => export_keys::<VestaEllipticCurve>(
+                &group_params::load::<VestaEllipticCurve>(params_file, &VESTA_GROUP_PARAMS)?,
+                &source,
+                encoding,
+            ),
+        };
+    }
+
     println!(" Starting client ");
     println!("      host: {}", host);
     println!("      port: {}", port);
@@ -37,39 +181,130 @@ async fn main() -> anyhow::Result<()> {
     }
     println!("      user: {}", user);
 
-    let mut client = AuthClientService::connect(format!("http://{}:{}", host, port)).await?;
+    let mut client = match client {
+        Some(client) => client,
+        None => AuthClientService::connect(format!("http://{}:{}", host, port)).await?,
+    };
+    let kdf = source.kdf();
     match flavor {
         Flavor::DiscreteLog => {
-            let dl_params = &DLOG_GROUP_PARAMS;
-            run_protocol::<DiscreteLog, _, _>(
-                &dl_params,
-                &hash_or_generate_random(secret.as_ref())?,
-                &user,
-                &mut client,
-            )
-            .await?
+            let dl_params = &group_params::load::<DiscreteLog>(params_file, &DLOG_GROUP_PARAMS)?;
+            println!(
+                "      params: {}",
+                group_params::describe::<DiscreteLog>(dl_params, params_file)
+            );
+            let x = load_secret::<DiscreteLog>(dl_params, &source)?;
+            if parallel {
+                run_parallel_protocol::<DiscreteLog>(dl_params, &x, &user, kdf, &mut client)
+                    .await?;
+            } else {
+                run_protocol::<DiscreteLog, _, _>(dl_params, &x, &user, kdf, &mut client).await?;
+            }
+            if let Some(action) = &action {
+                authorize_action::<DiscreteLog>(dl_params, &x, &user, action, &mut client).await?
+            }
         }
         Flavor::EllipticCurve => match curve {
             EllipticCurve::Pallas => {
-                let ec_params = PALLAS_GROUP_PARAMS.to_owned();
-                run_protocol::<PallasEllipticCurve, _, _>(
-                    &ec_params,
-                    &hash_or_generate_random(secret.as_ref())?,
-                    &user,
-                    &mut client,
-                )
-                .await?
+                let ec_params =
+                    group_params::load::<PallasEllipticCurve>(params_file, &PALLAS_GROUP_PARAMS)?;
+                println!(
+                    "      params: {}",
+                    group_params::describe::<PallasEllipticCurve>(&ec_params, params_file)
+                );
+                let x = load_secret::<PallasEllipticCurve>(&ec_params, &source)?;
+                if parallel {
+                    run_parallel_protocol::<PallasEllipticCurve>(
+                        &ec_params,
+                        &x,
+                        &user,
+                        kdf,
+                        &mut client,
+                    )
+                    .await?;
+                } else {
+                    run_protocol::<PallasEllipticCurve, _, _>(
+                        &ec_params,
+                        &x,
+                        &user,
+                        kdf,
+                        &mut client,
+                    )
+                    .await?;
+                }
+                if let Some(action) = &action {
+                    authorize_action::<PallasEllipticCurve>(
+                        &ec_params,
+                        &x,
+                        &user,
+                        action,
+                        &mut client,
+                    )
+                    .await?
+                }
+                if let Some(migration) = &migration {
+                    migrate_and_login::<PallasEllipticCurve, VestaEllipticCurve>(
+                        &ec_params,
+                        &VESTA_GROUP_PARAMS,
+                        &x,
+                        &source,
+                        &user,
+                        migration,
+                        &mut client,
+                    )
+                    .await?;
+                }
             }
 
             EllipticCurve::Vesta => {
-                let ec_params = VESTA_GROUP_PARAMS.to_owned();
-                run_protocol::<VestaEllipticCurve, _, _>(
-                    &ec_params,
-                    &hash_or_generate_random(secret.as_ref())?,
-                    &user,
-                    &mut client,
-                )
-                .await?
+                let ec_params =
+                    group_params::load::<VestaEllipticCurve>(params_file, &VESTA_GROUP_PARAMS)?;
+                println!(
+                    "      params: {}",
+                    group_params::describe::<VestaEllipticCurve>(&ec_params, params_file)
+                );
+                let x = load_secret::<VestaEllipticCurve>(&ec_params, &source)?;
+                if parallel {
+                    run_parallel_protocol::<VestaEllipticCurve>(
+                        &ec_params,
+                        &x,
+                        &user,
+                        kdf,
+                        &mut client,
+                    )
+                    .await?;
+                } else {
+                    run_protocol::<VestaEllipticCurve, _, _>(
+                        &ec_params,
+                        &x,
+                        &user,
+                        kdf,
+                        &mut client,
+                    )
+                    .await?;
+                }
+                if let Some(action) = &action {
+                    authorize_action::<VestaEllipticCurve>(
+                        &ec_params,
+                        &x,
+                        &user,
+                        action,
+                        &mut client,
+                    )
+                    .await?
+                }
+                if let Some(migration) = &migration {
+                    migrate_and_login::<VestaEllipticCurve, PallasEllipticCurve>(
+                        &ec_params,
+                        &PALLAS_GROUP_PARAMS,
+                        &x,
+                        &source,
+                        &user,
+                        migration,
+                        &mut client,
+                    )
+                    .await?;
+                }
             }
         },
     }
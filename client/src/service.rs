@@ -1,3 +1,12 @@
+use chaum_pedersen::protocol::contexts;
+use chaum_pedersen::protocol::cross_group::CrossGroupProof;
+use chaum_pedersen::protocol::dleq::{DleqProof, DleqStatement};
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::kdf::KdfParams;
+use chaum_pedersen::protocol::parallel::{self, ParallelConfig};
+use chaum_pedersen::protocol::rekey::RekeyProof;
+use chaum_pedersen::protocol::schnorr::Signature;
+use chaum_pedersen::protocol::secret::Secret;
 use chaum_pedersen::protocol::GroupParams;
 use chaum_pedersen::protocol::Protocol;
 use chaum_pedersen::traits::Random;
@@ -13,7 +22,9 @@ pub mod zkp_auth {
 // Importing specific structures from the `zkp_auth` module.
 use zkp_auth::{
     auth_client::AuthClient, AuthenticationAnswerRequest, AuthenticationChallengeRequest,
-    RegisterRequest,
+    KdfParamsRequest, MigrateUserRequest, NonceRequest, ParallelAnswerRequest,
+    ParallelChallengeRequest, ParallelConfigRequest, RegisterRequest, RekeyUserRequest,
+    SignedActionRequest,
 };
 
 pub struct AuthClientService {
@@ -31,13 +42,37 @@ impl AuthClientService {
         Ok(Self { client })
     }
 
+    /// Fetches the key derivation parameters the server holds for the user, or would hand to a new one.
+    pub async fn get_kdf_params(&mut self, user: String) -> anyhow::Result<KdfParams> {
+        let kdf = self
+            .client
+            .get_kdf_params(KdfParamsRequest { user })
+            .await?
+            .into_inner();
+        Ok(KdfParams {
+            algorithm: kdf.algorithm.parse()?,
+            salt: kdf.salt,
+            memory_cost: kdf.memory_cost,
+            time_cost: kdf.time_cost,
+            parallelism: kdf.parallelism,
+        })
+    }
+
     pub async fn register(
         &mut self,
         user: String,
         y1: Vec<u8>,
         y2: Vec<u8>,
+        kdf: Option<&KdfParams>,
     ) -> Result<(), tonic::Status> {
-        let request = RegisterRequest { user, y1, y2 };
+        let kdf = kdf.map(|kdf| zkp_auth::KdfParams {
+            algorithm: kdf.algorithm.to_string(),
+            salt: kdf.salt.clone(),
+            memory_cost: kdf.memory_cost,
+            time_cost: kdf.time_cost,
+            parallelism: kdf.parallelism,
+        });
+        let request = RegisterRequest { user, y1, y2, kdf };
         self.client.register(request).await?;
         Ok(())
     }
@@ -63,13 +98,82 @@ impl AuthClientService {
         let response = self.client.verify_authentication(request).await?;
         Ok(response.into_inner().session_id)
     }
+
+    /// Fetches the server's parallel mode configuration.
+    pub async fn get_parallel_config(&mut self) -> anyhow::Result<ParallelConfig> {
+        let response = self
+            .client
+            .get_parallel_config(ParallelConfigRequest {})
+            .await?;
+        let inner = response.into_inner();
+        ParallelConfig::new(inner.challenge_bits, usize::try_from(inner.rounds)?)
+    }
+
+    pub async fn create_parallel_challenge(
+        &mut self,
+        user: String,
+        r1: Vec<Vec<u8>>,
+        r2: Vec<Vec<u8>>,
+    ) -> Result<(Vec<u64>, String, f64), tonic::Status> {
+        let request = ParallelChallengeRequest { user, r1, r2 };
+        let response = self.client.create_parallel_challenge(request).await?;
+        let inner = response.into_inner();
+        Ok((inner.c, inner.auth_id, inner.soundness_error))
+    }
+
+    pub async fn verify_parallel_authentication(
+        &mut self,
+        auth_id: String,
+        s: Vec<Vec<u8>>,
+    ) -> Result<String, tonic::Status> {
+        let request = ParallelAnswerRequest { auth_id, s };
+        let response = self.client.verify_parallel_authentication(request).await?;
+        Ok(response.into_inner().session_id)
+    }
+
+    /// Asks the server for a one-time nonce to bind a signed request of the user to.
+    pub async fn create_nonce(&mut self, user: String) -> Result<String, tonic::Status> {
+        let response = self.client.create_nonce(NonceRequest { user }).await?;
+        Ok(response.into_inner().nonce)
+    }
+
+    pub async fn verify_signed_action(
+        &mut self,
+        user: String,
+        payload: Vec<u8>,
+        signature: Vec<u8>,
+        nonce: String,
+    ) -> Result<(), tonic::Status> {
+        let request = SignedActionRequest {
+            user,
+            payload,
+            signature,
+            nonce,
+        };
+        self.client.verify_signed_action(request).await?;
+        Ok(())
+    }
+
+    pub async fn migrate_user(&mut self, request: MigrateUserRequest) -> Result<(), tonic::Status> {
+        self.client.migrate_user(request).await?;
+        Ok(())
+    }
+
+    pub async fn rekey_user(&mut self, request: RekeyUserRequest) -> Result<(), tonic::Status> {
+        self.client.rekey_user(request).await?;
+        Ok(())
+    }
 }
 
 /// Runs the Chaum-Pedersen protocol for client authentication.
+///
+/// The key derivation parameters are registered along with the keys when the secret was derived
+/// from a password.
 pub async fn run_protocol<T, P, S>(
     params: &GroupParams<P>,
     x: &T::Secret,
-    user: &String,
+    user: &str,
+    kdf: Option<&KdfParams>,
     client: &mut AuthClientService,
 ) -> anyhow::Result<()>
 where
@@ -82,26 +186,191 @@ where
     P: FromBytes<P> + IntoBytes<P> + Random<P>,
     S: FromBytes<S> + IntoBytes<S> + Random<S>,
 {
-    let ((y1, y2, r1, r2), k) = T::commitment(params, x);
+    let ((y1, y2, _, _), _) = T::commitment(params, x);
 
     client
-        .register(user.clone(), P::to(&y1), P::to(&y2))
+        .register(user.to_string(), P::to(&y1), P::to(&y2), kdf)
         .await?;
 
+    login::<T, P, S>(params, x, user, client).await
+}
+
+/// Runs the Chaum-Pedersen protocol with the keys already registered for the user, e.g. after a
+/// migration.
+pub async fn login<T, P, S>(
+    params: &GroupParams<P>,
+    x: &T::Secret,
+    user: &str,
+    client: &mut AuthClientService,
+) -> anyhow::Result<()>
+where
+    T: Protocol<
+        GroupParameters = GroupParams<P>,
+        CommitParameters = (P, P, P, P),
+        Response = S,
+        Challenge = S,
+    >,
+    P: FromBytes<P> + IntoBytes<P> + Random<P>,
+    S: FromBytes<S> + IntoBytes<S> + Random<S>,
+{
+    let ((y1, y2, r1, r2), k) = T::commitment(params, x);
+
     let (c, auth_id) = client
-        .create_authentication_challenge(user.clone(), P::to(&r1), P::to(&r2))
+        .create_authentication_challenge(user.to_string(), P::to(&r1), P::to(&r2))
         .await?;
 
     let challenge = S::from(&c)?;
 
-    let s = T::challenge_response(&params, &k, &challenge, &x);
+    let s = T::challenge_response(params, &k, &challenge, x);
 
     let session_id = client.verify_authentication(auth_id, S::to(&s)).await?;
 
     println!("Authentication was successful!");
     println!("Session ID: {}", session_id);
 
-    T::verify(&params, &s, &challenge, &(y1, y2, r1, r2));
+    T::verify(params, &s, &challenge, &(y1, y2, r1, r2));
+
+    Ok(())
+}
+
+/// Runs the parallel mode of the protocol, with the server's short challenges.
+pub async fn run_parallel_protocol<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    x: &Secret<G::Scalar>,
+    user: &str,
+    kdf: Option<&KdfParams>,
+    client: &mut AuthClientService,
+) -> anyhow::Result<()> {
+    let y1 = G::exp(params, &params.g, x.expose());
+    let y2 = G::exp(params, &params.h, x.expose());
+    client
+        .register(
+            user.to_string(),
+            G::Element::to(&y1),
+            G::Element::to(&y2),
+            kdf,
+        )
+        .await?;
+
+    let config = client.get_parallel_config().await?;
+    let (commitments, nonces) = parallel::commit::<G>(params, &config);
+    let (r1, r2) = commitments
+        .iter()
+        .map(|commitment| {
+            (
+                G::Element::to(&commitment.r1),
+                G::Element::to(&commitment.r2),
+            )
+        })
+        .unzip();
+    let (challenges, auth_id, soundness_error) = client
+        .create_parallel_challenge(user.to_string(), r1, r2)
+        .await?;
+
+    let s = parallel::respond::<G>(params, &config, &nonces, &challenges, x)?;
+    let session_id = client
+        .verify_parallel_authentication(auth_id, s.iter().map(G::Scalar::to).collect())
+        .await?;
+
+    println!("Authentication was successful!");
+    println!(
+        "Rounds: {} of {}-bit challenges, soundness error: {:e}",
+        config.rounds, config.challenge_bits, soundness_error
+    );
+    println!("Session ID: {}", session_id);
+
+    Ok(())
+}
+
+/// Signs `action` with the registered secret and has the server check it against the stored `y1`.
+pub async fn authorize_action<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    x: &Secret<G::Scalar>,
+    user: &str,
+    action: &str,
+    client: &mut AuthClientService,
+) -> anyhow::Result<()> {
+    let nonce = client.create_nonce(user.to_string()).await?;
+    let context = contexts::action(user, &nonce);
+    let signature =
+        Signature::<G>::sign_in_context(params, &context, x.expose(), action.as_bytes());
+    client
+        .verify_signed_action(
+            user.to_string(),
+            action.as_bytes().to_vec(),
+            Signature::to(&signature),
+            nonce,
+        )
+        .await?;
+
+    println!("Action was authorized: {}", action);
+
+    Ok(())
+}
+
+/// Moves the user's registration from the group `G1` to `G2`, proving that the keys of `x2` in
+/// `G2` hold the same secret as `x`.
+pub async fn migrate_user<G1: CyclicGroup, G2: CyclicGroup>(
+    params1: &GroupParams<G1::Element>,
+    params2: &GroupParams<G2::Element>,
+    x: &Secret<G1::Scalar>,
+    x2: &Secret<G2::Scalar>,
+    user: &str,
+    client: &mut AuthClientService,
+) -> anyhow::Result<()> {
+    let statement = DleqStatement::<G2> {
+        g1: params2.g.clone(),
+        y1: G2::exp(params2, &params2.g, x2.expose()),
+        g2: params2.h.clone(),
+        y2: G2::exp(params2, &params2.h, x2.expose()),
+    };
+
+    let nonce = client.create_nonce(user.to_string()).await?;
+    let mut transcript = contexts::migration(user, &nonce);
+    let proof = CrossGroupProof::<G1, G2>::prove(params1, params2, &mut transcript, x.expose())?;
+    let dleq_proof = DleqProof::prove(params2, &mut transcript, &statement, x2.expose());
+
+    client
+        .migrate_user(MigrateUserRequest {
+            user: user.to_string(),
+            y1: G2::Element::to(&statement.y1),
+            y2: G2::Element::to(&statement.y2),
+            proof: CrossGroupProof::to(&proof),
+            dleq_proof: DleqProof::to(&dleq_proof),
+            nonce,
+        })
+        .await?;
+
+    println!("Migration was accepted!");
+
+    Ok(())
+}
+
+/// Moves the user's registration from the group `G1` to the fresh keys of `x2` in `G2`, which the
+/// current secret `x` signs over to.
+pub async fn rekey_user<G1: CyclicGroup, G2: CyclicGroup>(
+    params1: &GroupParams<G1::Element>,
+    params2: &GroupParams<G2::Element>,
+    x: &Secret<G1::Scalar>,
+    x2: &Secret<G2::Scalar>,
+    user: &str,
+    client: &mut AuthClientService,
+) -> anyhow::Result<()> {
+    let nonce = client.create_nonce(user.to_string()).await?;
+    let context = contexts::rekey(user, &nonce);
+    let proof = RekeyProof::<G1, G2>::prove(params1, params2, &context, x.expose(), x2.expose());
+
+    client
+        .rekey_user(RekeyUserRequest {
+            user: user.to_string(),
+            y1: G2::Element::to(&G2::exp(params2, &params2.g, x2.expose())),
+            y2: G2::Element::to(&G2::exp(params2, &params2.h, x2.expose())),
+            proof: RekeyProof::to(&proof),
+            nonce,
+        })
+        .await?;
+
+    println!("Re-keying was accepted!");
 
     Ok(())
 }
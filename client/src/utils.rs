@@ -1,19 +1,225 @@
-use anyhow::Result;
-use chaum_pedersen::traits::Random;
-use chaum_pedersen::traits::{FromBytes, IntoBytes};
-use sha2::{Digest, Sha512};
-
-/// Hashes the provided secret string or generates a random value.
-pub fn hash_or_generate_random<T: FromBytes<T> + IntoBytes<T> + Random<T>>(
-    secret: Option<&String>,
-) -> Result<T> {
-    match secret {
-        Some(s) => {
-            let mut hasher = Sha512::new();
-            hasher.update(s);
-            let result = hasher.finalize();
-            T::from(&result).map_err(|error| error.context("Failed to convert hash to target type"))
+use crate::service::AuthClientService;
+use anyhow::{Context, Result};
+use chaum_pedersen::enums::{KdfAlgorithm, TextEncoding};
+use chaum_pedersen::protocol::armor;
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::hd::{DerivationPath, HdKey};
+use chaum_pedersen::protocol::kdf::KdfParams;
+use chaum_pedersen::protocol::secret::Secret;
+use chaum_pedersen::protocol::wire::Envelope;
+use chaum_pedersen::protocol::GroupParams;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// The smallest key derivation costs accepted from the server, pinned on the command line. They
+/// are never below the recommended costs of the algorithm.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KdfCosts {
+    /// The only algorithm accepted, if set.
+    pub algorithm: Option<KdfAlgorithm>,
+    pub memory_cost: Option<u32>,
+    pub time_cost: Option<u32>,
+    pub parallelism: Option<u32>,
+}
+
+impl KdfCosts {
+    /// Whether the parameters are at least as costly as pinned.
+    pub fn accepts(&self, kdf: &KdfParams) -> bool {
+        if self
+            .algorithm
+            .is_some_and(|algorithm| algorithm != kdf.algorithm)
+        {
+            return false;
+        }
+        let recommended = KdfParams::generate(kdf.algorithm);
+        let minimum = KdfParams {
+            memory_cost: recommended.memory_cost.max(self.memory_cost.unwrap_or(0)),
+            time_cost: recommended.time_cost.max(self.time_cost.unwrap_or(0)),
+            parallelism: recommended.parallelism.max(self.parallelism.unwrap_or(0)),
+            ..recommended
+        };
+        kdf.is_at_least(&minimum)
+    }
+}
+
+/// The key derivation parameters the server hands out for the user, refused if they are invalid or
+/// cheaper than pinned, since a server lowering them would make the password easier to guess.
+pub async fn fetch_kdf_params(
+    client: &mut AuthClientService,
+    user: &str,
+    costs: KdfCosts,
+) -> Result<KdfParams> {
+    let kdf = client.get_kdf_params(user.to_string()).await?;
+    kdf.check()
+        .context("Invalid key derivation parameters from the server")?;
+    if !costs.accepts(&kdf) {
+        anyhow::bail!(
+            "Key derivation parameters from the server do not meet the pinned minimum: {} with costs {}, {}, {}",
+            kdf.algorithm,
+            kdf.memory_cost,
+            kdf.time_cost,
+            kdf.parallelism
+        );
+    }
+    Ok(kdf)
+}
+
+/// Where the secret comes from.
+#[derive(Debug)]
+pub enum SecretSource<'a> {
+    /// An encoded secret key file.
+    Key(&'a Path),
+    /// A master seed, the secret being derived for the user on the server.
+    Seed {
+        seed: &'a HdKey,
+        server: &'a str,
+        user: &'a str,
+    },
+    /// A secret string, stretched with the key derivation parameters the server holds for it.
+    Password {
+        secret: &'a str,
+        kdf: Option<KdfParams>,
+    },
+    Random,
+}
+
+impl<'a> SecretSource<'a> {
+    /// The first of the secret key file, the master seed and the secret string that is given.
+    pub fn pick(
+        secret_key: Option<&'a Path>,
+        seed: Option<&'a HdKey>,
+        secret: Option<&'a str>,
+        server: &'a str,
+        user: &'a str,
+    ) -> Self {
+        match (secret_key, seed, secret) {
+            (Some(path), _, _) => SecretSource::Key(path),
+            (None, Some(seed), _) => SecretSource::Seed { seed, server, user },
+            (None, None, Some(secret)) => SecretSource::Password { secret, kdf: None },
+            (None, None, None) => SecretSource::Random,
+        }
+    }
+
+    /// The key derivation parameters to register along with the keys.
+    pub fn kdf(&self) -> Option<&KdfParams> {
+        match self {
+            SecretSource::Password { kdf, .. } => kdf.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// Reads, derives or generates the secret.
+pub fn load_secret<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    source: &SecretSource,
+) -> Result<Secret<G::Scalar>> {
+    match source {
+        SecretSource::Key(path) => {
+            let text = Secret::new(
+                fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read secret key {}", path.display()))?,
+            );
+            armor::decode(text.expose())?
+                .to_secret::<G>()
+                .map(Secret::new)
+        }
+        SecretSource::Seed { seed, server, user } => {
+            let path = DerivationPath::login::<G>(server, user);
+            Ok(seed.derive(&path).secret::<G>(params))
         }
-        None => T::random().map_err(|error| error.context("Failed to generate random value")),
+        SecretSource::Password {
+            secret,
+            kdf: Some(kdf),
+        } => kdf
+            .derive_secret::<G>(params, secret.as_bytes())
+            .context("Failed to derive the secret from the password"),
+        SecretSource::Password { kdf: None, .. } => {
+            anyhow::bail!("Key derivation parameters of the password were not fetched")
+        }
+        SecretSource::Random => Ok(Secret::new(G::random_scalar(params))),
     }
 }
+
+/// A fresh secret of the user on the other group of a re-keying to the server at `target`. A
+/// password keeps its key derivation parameters and a seed is derived for the target, so both give
+/// the same secret again when logging in there. Any other secret is replaced by a random one.
+pub fn rekeyed_secret<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    source: &SecretSource,
+    target: &str,
+) -> Result<Secret<G::Scalar>> {
+    match source {
+        SecretSource::Seed { seed, user, .. } => load_secret::<G>(
+            params,
+            &SecretSource::Seed {
+                seed,
+                server: target,
+                user,
+            },
+        ),
+        SecretSource::Password { .. } => load_secret::<G>(params, source),
+        SecretSource::Key(_) | SecretSource::Random => Ok(Secret::new(G::random_scalar(params))),
+    }
+}
+
+/// Creates a new file only its owner can read.
+fn create_private(path: &Path, what: &str) -> Result<fs::File> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(path)
+        .with_context(|| format!("Failed to create {} file {}", what, path.display()))
+}
+
+/// Writes the secret key, armored, to a new file only its owner can read, to be used as
+/// `--secret-key`.
+pub fn write_secret_key<G: CyclicGroup>(path: &Path, x: &Secret<G::Scalar>) -> Result<()> {
+    let mut file = create_private(path, "secret key")?;
+    let text = Secret::new(
+        armor::encode(&Envelope::secret::<G>(x.expose()), TextEncoding::Armor).into_bytes(),
+    );
+    file.write_all(text.expose())?;
+    println!("Secret key written to {}", path.display());
+    Ok(())
+}
+
+/// Writes a fresh master seed, hex encoded, to a new file only its owner can read.
+pub fn generate_seed(path: &Path) -> Result<()> {
+    let mut file = create_private(path, "seed")?;
+    let seed = HdKey::generate_seed();
+    let text = Secret::new(hex::encode(seed.expose()).into_bytes());
+    file.write_all(text.expose())?;
+    println!("Master seed written to {}", path.display());
+    Ok(())
+}
+
+/// Reads the master seed from a file written by `generate_seed`.
+pub fn load_seed(path: &Path) -> Result<HdKey> {
+    let text = Secret::new(
+        fs::read(path).with_context(|| format!("Failed to read seed file {}", path.display()))?,
+    );
+    let seed = Secret::new(hex::decode(text.expose().trim_ascii()).context("Malformed seed file")?);
+    HdKey::from_seed(seed.expose())
+}
+
+/// Prints the secret key and the public key `(y1, y2)` in the given text encoding.
+pub fn export_keys<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    source: &SecretSource,
+    encoding: TextEncoding,
+) -> Result<()> {
+    let x = load_secret::<G>(params, source)?;
+    let y1 = G::exp(params, &params.g, x.expose());
+    let y2 = G::exp(params, &params.h, x.expose());
+    let text = Secret::new(armor::encode(&Envelope::secret::<G>(x.expose()), encoding));
+    println!("{}", text.expose());
+    println!(
+        "{}",
+        armor::encode(&Envelope::statement::<G>(&y1, &y2), encoding)
+    );
+    Ok(())
+}
@@ -0,0 +1,28 @@
+//! Writes the seed corpus of every fuzz target, from valid inputs of every group.
+//!
+//! ```text
+//! cargo run --manifest-path fuzz/Cargo.toml --example seed_corpus -- fuzz/corpus
+//! ```
+
+use chaum_pedersen_fuzz::{auth, envelope, from_bytes, group_of, GROUPS};
+use std::path::PathBuf;
+use std::{env, fs};
+
+fn main() -> std::io::Result<()> {
+    let dir = PathBuf::from(env::args().nth(1).unwrap_or_else(|| "corpus".to_string()));
+    for group in 0..GROUPS.len() as u8 {
+        let targets = group_of!(group, G, params => [
+            ("from_bytes", from_bytes::seeds::<G>(group, params)),
+            ("envelope", envelope::seeds::<G>(group, params)),
+            ("auth", auth::seeds::<G>(group, params)),
+        ]);
+        for (target, seeds) in targets {
+            fs::create_dir_all(dir.join(target))?;
+            for (name, input) in seeds {
+                fs::write(dir.join(target).join(name), input)?;
+            }
+        }
+    }
+    println!("wrote {}", dir.display());
+    Ok(())
+}
@@ -0,0 +1,297 @@
+//! The register, challenge and verify handlers of the authentication service, with the in-memory storage.
+//!
+//! An input is the group byte followed by calls, each a tag byte, a user byte and fields prefixed
+//! with their length byte. Honest calls run the whole protocol and have to be accepted, whatever
+//! the calls before them left in the storage.
+
+use crate::{group_of, GROUPS};
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::secret::{Nonce, Secret};
+use chaum_pedersen::protocol::{GroupParams, Protocol};
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+use server::service::zkp_auth::auth_server::Auth;
+use server::service::zkp_auth::{
+    AuthenticationAnswerRequest, AuthenticationChallengeRequest, RegisterRequest,
+};
+use server::service::AuthService;
+use tonic::Request;
+
+/// Usernames are taken modulo this, so that calls collide on the same users.
+const USERS: u8 = 4;
+
+/// One call to the service.
+#[derive(Clone, Debug)]
+pub enum Call {
+    Register {
+        user: u8,
+        y1: Vec<u8>,
+        y2: Vec<u8>,
+    },
+    Challenge {
+        user: u8,
+        r1: Vec<u8>,
+        r2: Vec<u8>,
+    },
+    /// Answers the `challenge`-th challenge created so far, or an unknown one.
+    Verify {
+        challenge: u8,
+        s: Vec<u8>,
+    },
+    /// Registers `user` with a secret derived from `secret`, and logs in.
+    Honest {
+        user: u8,
+        secret: u8,
+    },
+}
+
+impl Call {
+    pub fn parse(mut bytes: &[u8]) -> Vec<Call> {
+        let mut calls = Vec::new();
+        while let Some(call) = Call::take(&mut bytes) {
+            calls.push(call);
+        }
+        calls
+    }
+
+    fn take(bytes: &mut &[u8]) -> Option<Call> {
+        let [tag, user] = [take_byte(bytes)?, take_byte(bytes)?];
+        let call = match tag % 4 {
+            0 => Call::Register {
+                user,
+                y1: take_field(bytes)?,
+                y2: take_field(bytes)?,
+            },
+            1 => Call::Challenge {
+                user,
+                r1: take_field(bytes)?,
+                r2: take_field(bytes)?,
+            },
+            2 => Call::Verify {
+                challenge: user,
+                s: take_field(bytes)?,
+            },
+            _ => Call::Honest {
+                user,
+                secret: take_byte(bytes)?,
+            },
+        };
+        Some(call)
+    }
+
+    pub fn encode(calls: &[Call]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for call in calls {
+            match call {
+                Call::Register { user, y1, y2 } => {
+                    out.extend([0, *user]);
+                    put_field(&mut out, y1);
+                    put_field(&mut out, y2);
+                }
+                Call::Challenge { user, r1, r2 } => {
+                    out.extend([1, *user]);
+                    put_field(&mut out, r1);
+                    put_field(&mut out, r2);
+                }
+                Call::Verify { challenge, s } => {
+                    out.extend([2, *challenge]);
+                    put_field(&mut out, s);
+                }
+                Call::Honest { user, secret } => out.extend([3, *user, *secret]),
+            }
+        }
+        out
+    }
+}
+
+fn take_byte(bytes: &mut &[u8]) -> Option<u8> {
+    let (&byte, rest) = bytes.split_first()?;
+    *bytes = rest;
+    Some(byte)
+}
+
+fn take_field(bytes: &mut &[u8]) -> Option<Vec<u8>> {
+    let len = take_byte(bytes)? as usize;
+    if bytes.len() < len {
+        return None;
+    }
+    let (field, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Some(field.to_vec())
+}
+
+fn put_field(out: &mut Vec<u8>, field: &[u8]) {
+    // fields longer than a length byte are truncated
+    let field = &field[..field.len().min(u8::MAX as usize)];
+    out.push(field.len() as u8);
+    out.extend_from_slice(field);
+}
+
+fn username(user: u8) -> String {
+    format!("user-{}", user % USERS)
+}
+
+pub fn run(input: &[u8]) {
+    let [group, calls @ ..] = input else {
+        return;
+    };
+    let calls = Call::parse(calls);
+    group_of!(*group, G, params => serve::<G>(params, &calls));
+}
+
+fn serve<C>(params: &GroupParams<C::Element>, calls: &[Call])
+where
+    C: CyclicGroup
+        + Protocol<
+            Secret = Secret<C::Scalar>,
+            Response = C::Scalar,
+            Challenge = C::Scalar,
+            CommitmentRandom = Nonce<C::Scalar>,
+            GroupParameters = GroupParams<C::Element>,
+            CommitParameters = (C::Element, C::Element, C::Element, C::Element),
+        > + Send
+        + Sync
+        + 'static,
+    C::Element: Send + Sync + 'static,
+    C::Scalar: Send + Sync + 'static,
+{
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("failed to start the runtime");
+    runtime.block_on(async {
+        let service = AuthService::<C, C::Element, C::Scalar>::new(params.clone());
+        let mut auth_ids: Vec<String> = Vec::new();
+        for call in calls {
+            match call {
+                Call::Register { user, y1, y2 } => {
+                    let request = RegisterRequest {
+                        user: username(*user),
+                        y1: y1.clone(),
+                        y2: y2.clone(),
+                        kdf: None,
+                    };
+                    let _ = service.register(Request::new(request)).await;
+                }
+                Call::Challenge { user, r1, r2 } => {
+                    let request = AuthenticationChallengeRequest {
+                        user: username(*user),
+                        r1: r1.clone(),
+                        r2: r2.clone(),
+                    };
+                    if let Ok(response) = service
+                        .create_authentication_challenge(Request::new(request))
+                        .await
+                    {
+                        auth_ids.push(response.into_inner().auth_id);
+                    }
+                }
+                Call::Verify { challenge, s } => {
+                    // one past the created challenges stands for an unknown one
+                    let auth_id = auth_ids
+                        .get(*challenge as usize % (auth_ids.len() + 1))
+                        .cloned()
+                        .unwrap_or_default();
+                    let request = AuthenticationAnswerRequest {
+                        auth_id,
+                        s: s.clone(),
+                    };
+                    let _ = service.verify_authentication(Request::new(request)).await;
+                }
+                Call::Honest { user, secret } => {
+                    let x = Secret::new(C::scalar_from_u64(params, *secret as u64 + 1));
+                    let ((y1, y2, r1, r2), k) = <C as Protocol>::commitment(params, &x);
+                    let request = RegisterRequest {
+                        user: username(*user),
+                        y1: C::Element::to(&y1),
+                        y2: C::Element::to(&y2),
+                        kdf: None,
+                    };
+                    service
+                        .register(Request::new(request))
+                        .await
+                        .expect("an honest registration is rejected");
+                    let request = AuthenticationChallengeRequest {
+                        user: username(*user),
+                        r1: C::Element::to(&r1),
+                        r2: C::Element::to(&r2),
+                    };
+                    let response = service
+                        .create_authentication_challenge(Request::new(request))
+                        .await
+                        .expect("an honest commitment is rejected")
+                        .into_inner();
+                    let c = <C::Scalar as FromBytes<C::Scalar>>::from(&response.c)
+                        .expect("the challenge fails to decode");
+                    let s = C::challenge_response(params, &k, &c, &x);
+                    let request = AuthenticationAnswerRequest {
+                        auth_id: response.auth_id,
+                        s: C::Scalar::to(&s),
+                    };
+                    service
+                        .verify_authentication(Request::new(request))
+                        .await
+                        .expect("an honest login is rejected");
+                }
+            }
+        }
+    })
+}
+
+/// Honest logins, and the calls that used to crash the service, as seeds of the corpus.
+pub fn seeds<G: CyclicGroup>(
+    group: u8,
+    params: &GroupParams<G::Element>,
+) -> Vec<(String, Vec<u8>)> {
+    let name = GROUPS[group as usize];
+    let (g, h) = (G::Element::to(&params.g), G::Element::to(&params.h));
+    let sequences = [
+        ("honest", vec![Call::Honest { user: 0, secret: 1 }]),
+        (
+            "garbage",
+            vec![
+                Call::Register {
+                    user: 1,
+                    y1: vec![0xff; 32],
+                    y2: vec![0x01; 32],
+                },
+                Call::Challenge {
+                    user: 1,
+                    r1: vec![0x02; 32],
+                    r2: vec![0xff; 32],
+                },
+                Call::Verify {
+                    challenge: 0,
+                    s: vec![0x03; 32],
+                },
+                Call::Honest { user: 1, secret: 2 },
+            ],
+        ),
+        (
+            // registering again between the challenge and the answer drops the commitment
+            "reregistered",
+            vec![
+                Call::Honest { user: 2, secret: 3 },
+                Call::Challenge {
+                    user: 2,
+                    r1: g.clone(),
+                    r2: h.clone(),
+                },
+                Call::Register {
+                    user: 2,
+                    y1: g,
+                    y2: h,
+                },
+                Call::Verify {
+                    challenge: 0,
+                    s: vec![0x08; 32],
+                },
+            ],
+        ),
+    ];
+    sequences
+        .into_iter()
+        .map(|(sequence, calls)| {
+            let input = [&[group][..], &Call::encode(&calls)].concat();
+            (format!("{}-{}", name, sequence), input)
+        })
+        .collect()
+}
@@ -0,0 +1,94 @@
+//! Decoding of envelopes in every wire and text encoding, and of the artefacts they hold.
+
+use crate::{group_of, GROUPS};
+use chaum_pedersen::protocol::armor;
+use chaum_pedersen::protocol::designated::DesignatedProof;
+use chaum_pedersen::protocol::dleq::DleqProof;
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::or_proof::OrProof;
+use chaum_pedersen::protocol::range::RangeProof;
+use chaum_pedersen::protocol::representation::RepresentationProof;
+use chaum_pedersen::protocol::ring::RingSignature;
+use chaum_pedersen::protocol::schnorr::Signature;
+use chaum_pedersen::protocol::sigma::SigmaProof;
+use chaum_pedersen::protocol::vectors::TestVectors;
+use chaum_pedersen::protocol::vrf::VrfProof;
+use chaum_pedersen::protocol::wire::{Envelope, WireProof};
+use chaum_pedersen::protocol::GroupParams;
+
+pub fn run(input: &[u8]) {
+    let mut envelopes = vec![Envelope::from_binary(input), Envelope::from_cbor(input)];
+    if let Ok(text) = std::str::from_utf8(input) {
+        envelopes.push(Envelope::from_json(text));
+        envelopes.push(armor::decode(text));
+    }
+    for envelope in envelopes.into_iter().flatten() {
+        let binary = envelope.to_binary();
+        assert_eq!(
+            Envelope::from_binary(&binary).expect("an encoded envelope fails to decode"),
+            envelope
+        );
+        for group in 0..GROUPS.len() {
+            group_of!(group, G, params => extract::<G>(&envelope, params));
+        }
+    }
+}
+
+/// Extracts every kind of artefact, and checks the interactive proofs.
+fn extract<G: CyclicGroup>(envelope: &Envelope, params: &GroupParams<G::Element>) {
+    let _ = envelope
+        .to_params::<G>()
+        .map(|params| G::is_valid_params(&params));
+    let _ = envelope.to_statement::<G>();
+    let _ = envelope.to_secret::<G>();
+    let _ = envelope.to_commitment::<G>();
+    let _ = envelope
+        .to_interactive_proof::<G>()
+        .map(|proof| proof.verify(params));
+    proof::<DleqProof<G>>(envelope);
+    proof::<Signature<G>>(envelope);
+    proof::<SigmaProof<G>>(envelope);
+    proof::<OrProof<G>>(envelope);
+    proof::<RangeProof<G>>(envelope);
+    proof::<RepresentationProof<G>>(envelope);
+    proof::<VrfProof<G>>(envelope);
+    proof::<DesignatedProof<G>>(envelope);
+    proof::<RingSignature<G>>(envelope);
+}
+
+fn proof<P: WireProof>(envelope: &Envelope) {
+    let _ = envelope.to_proof::<P>();
+}
+
+/// Every encoding of the artefacts of the test vectors, as seeds of the corpus.
+pub fn seeds<G: CyclicGroup>(
+    group: u8,
+    params: &GroupParams<G::Element>,
+) -> Vec<(String, Vec<u8>)> {
+    let vectors = TestVectors::generate::<G>(params, &["seed"]);
+    vectors
+        .vectors
+        .iter()
+        .flat_map(|vector| &vector.encodings)
+        .flat_map(|encodings| {
+            let name = format!("{}-{}", GROUPS[group as usize], encodings.kind);
+            [
+                (
+                    format!("{}.json", name),
+                    encodings.json.to_string().into_bytes(),
+                ),
+                (format!("{}.cbor", name), encodings.cbor.0.clone()),
+                (format!("{}.bin", name), encodings.binary.0.clone()),
+                (format!("{}.hex", name), encodings.hex.clone().into_bytes()),
+                (
+                    format!("{}.b64", name),
+                    encodings.base64url.clone().into_bytes(),
+                ),
+                (
+                    format!("{}.pem", name),
+                    encodings.armor.clone().into_bytes(),
+                ),
+            ]
+        })
+        .collect()
+}
@@ -0,0 +1,190 @@
+//! Decoding of every `FromBytes` implementation.
+//!
+//! The first byte of an input picks the group, the second one the type, and the rest is decoded.
+//! Whatever decodes has to re-encode to bytes that decode again to the same encoding.
+
+use crate::{group_of, GROUPS};
+use chaum_pedersen::protocol::cross_group::{BitProof, CrossGroupProof};
+use chaum_pedersen::protocol::designated::DesignatedProof;
+use chaum_pedersen::protocol::dkg::{Complaint, Dealing, Justification};
+use chaum_pedersen::protocol::dleq::{DleqProof, DleqStatement};
+use chaum_pedersen::protocol::elgamal::{Ciphertext, DecryptionShare, KeyPair};
+use chaum_pedersen::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::or_proof::OrProof;
+use chaum_pedersen::protocol::pedersen::{EqualityProof, Opening, PedersenCommitment};
+use chaum_pedersen::protocol::range::RangeProof;
+use chaum_pedersen::protocol::representation::RepresentationProof;
+use chaum_pedersen::protocol::ring::RingSignature;
+use chaum_pedersen::protocol::schnorr::Signature;
+use chaum_pedersen::protocol::shamir::Share;
+use chaum_pedersen::protocol::sigma::SigmaProof;
+use chaum_pedersen::protocol::threshold::PartialDecryption;
+use chaum_pedersen::protocol::transcript::Transcript;
+use chaum_pedersen::protocol::voprf::Evaluation;
+use chaum_pedersen::protocol::vrf::VrfProof;
+use chaum_pedersen::protocol::vss::{FeldmanVss, PedersenShare, PedersenVss};
+use chaum_pedersen::protocol::GroupParams;
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+
+/// The decoded types, in the order of the type byte.
+pub const KINDS: [&str; 26] = [
+    "element",
+    "scalar",
+    "dleq_proof",
+    "signature",
+    "sigma_proof",
+    "or_proof",
+    "range_proof",
+    "representation_proof",
+    "ring_signature",
+    "vrf_proof",
+    "designated_proof",
+    "evaluation",
+    "pedersen_commitment",
+    "opening",
+    "equality_proof",
+    "share",
+    "feldman_vss",
+    "pedersen_vss",
+    "pedersen_share",
+    "ciphertext",
+    "decryption_share",
+    "partial_decryption",
+    "dealing",
+    "complaint",
+    "justification",
+    "cross_group_proof",
+];
+
+pub fn run(input: &[u8]) {
+    let [group, kind, data @ ..] = input else {
+        return;
+    };
+    group_of!(*group, G, _params => decode::<G>(*kind as usize % KINDS.len(), data));
+}
+
+fn decode<G: CyclicGroup>(kind: usize, data: &[u8]) {
+    match kind {
+        0 => round_trip::<G::Element>(data),
+        1 => round_trip::<G::Scalar>(data),
+        2 => round_trip::<DleqProof<G>>(data),
+        3 => round_trip::<Signature<G>>(data),
+        4 => round_trip::<SigmaProof<G>>(data),
+        5 => round_trip::<OrProof<G>>(data),
+        6 => round_trip::<RangeProof<G>>(data),
+        7 => round_trip::<RepresentationProof<G>>(data),
+        8 => round_trip::<RingSignature<G>>(data),
+        9 => round_trip::<VrfProof<G>>(data),
+        10 => round_trip::<DesignatedProof<G>>(data),
+        11 => round_trip::<Evaluation<G>>(data),
+        12 => round_trip::<PedersenCommitment<G>>(data),
+        13 => round_trip::<Opening<G>>(data),
+        14 => round_trip::<EqualityProof<G>>(data),
+        15 => round_trip::<Share<G>>(data),
+        16 => round_trip::<FeldmanVss<G>>(data),
+        17 => round_trip::<PedersenVss<G>>(data),
+        18 => round_trip::<PedersenShare<G>>(data),
+        19 => round_trip::<Ciphertext<G>>(data),
+        20 => round_trip::<DecryptionShare<G>>(data),
+        21 => round_trip::<PartialDecryption<G>>(data),
+        22 => round_trip::<Dealing<G>>(data),
+        23 => round_trip::<Complaint>(data),
+        24 => round_trip::<Justification<G>>(data),
+        25 => {
+            round_trip::<BitProof<G, PallasEllipticCurve>>(data);
+            round_trip::<CrossGroupProof<G, PallasEllipticCurve>>(data)
+        }
+        _ => unreachable!("the kind is taken modulo the number of kinds"),
+    }
+}
+
+fn round_trip<T: IntoBytes<T> + FromBytes<T>>(data: &[u8]) {
+    let Ok(decoded) = T::from(data) else {
+        return;
+    };
+    // decoding may normalise, e.g. reduce scalars, but only once
+    let encoded = T::to(&decoded);
+    let decoded = T::from(&encoded).expect("an encoding fails to decode");
+    assert_eq!(T::to(&decoded), encoded, "the encoding is not canonical");
+}
+
+/// Valid encodings of the types that are simple to produce, as seeds of the corpus.
+pub fn seeds<G: CyclicGroup>(
+    group: u8,
+    params: &GroupParams<G::Element>,
+) -> Vec<(String, Vec<u8>)> {
+    let x = G::scalar_from_u64(params, 7);
+    let y1 = G::exp(params, &params.g, &x);
+    let y2 = G::exp(params, &params.h, &x);
+    let statement = DleqStatement::<G> {
+        g1: params.g.clone(),
+        y1: y1.clone(),
+        g2: params.h.clone(),
+        y2,
+    };
+    let (commitment, opening) =
+        PedersenCommitment::<G>::commit(params, &G::scalar_from_u64(params, 5));
+    let ring = [params.h.clone(), y1.clone()];
+
+    let mut seeds = vec![
+        seed::<G::Element>("element", &y1),
+        seed::<G::Scalar>("scalar", &x),
+        seed(
+            "dleq_proof",
+            &DleqProof::prove(params, &mut Transcript::new(b"seed"), &statement, &x),
+        ),
+        seed("signature", &Signature::<G>::sign(params, &x, b"seed")),
+        seed("vrf_proof", &VrfProof::<G>::prove(params, &x, b"seed").1),
+        seed("pedersen_commitment", &commitment),
+        seed("opening", &opening),
+        seed(
+            "share",
+            &Share::<G> {
+                index: 1,
+                value: x.clone(),
+            },
+        ),
+    ];
+    if let Ok(signature) = RingSignature::<G>::sign(params, &ring, 1, &x, b"seed", b"seed") {
+        seeds.push(seed("ring_signature", &signature));
+    }
+    if let Ok(proof) = DesignatedProof::prove(
+        params,
+        &mut Transcript::new(b"seed"),
+        &statement,
+        &params.h,
+        &x,
+    ) {
+        seeds.push(seed("designated_proof", &proof));
+    }
+    if let Ok(proof) = RangeProof::prove(params, &mut Transcript::new(b"seed"), &opening, 8) {
+        seeds.push(seed("range_proof", &proof));
+    }
+    if let Ok((vss, _)) = FeldmanVss::<G>::deal(params, &x, 2, 3) {
+        seeds.push(seed("feldman_vss", &vss));
+    }
+    let ciphertext = Ciphertext::encrypt(params, &y1, &params.h);
+    let share = KeyPair::<G>::from_secret(params, x.clone()).decryption_share(
+        params,
+        &mut Transcript::new(b"seed"),
+        &ciphertext,
+    );
+    seeds.push(seed("ciphertext", &ciphertext));
+    seeds.push(seed("decryption_share", &share));
+    seeds
+        .into_iter()
+        .map(|(kind, bytes)| {
+            let index = KINDS
+                .iter()
+                .position(|name| *name == kind)
+                .unwrap_or_default();
+            let input = [&[group, index as u8][..], &bytes].concat();
+            (format!("{}-{}", GROUPS[group as usize], kind), input)
+        })
+        .collect()
+}
+
+fn seed<T: IntoBytes<T>>(kind: &'static str, t: &T) -> (&'static str, Vec<u8>) {
+    (kind, T::to(t))
+}
@@ -0,0 +1,40 @@
+//! Fuzz targets of the decoders and of the authentication service, see `fuzz_targets`.
+//!
+//! The logic lives here so that the seed corpus can be written from valid inputs, see
+//! `examples/seed_corpus.rs`.
+
+pub mod auth;
+pub mod envelope;
+pub mod from_bytes;
+
+/// The groups picked by the group byte of an input, in order.
+pub const GROUPS: [&str; 3] = ["discrete_log", "pallas", "vesta"];
+
+/// Evaluates `$body` with the type `$G` and the parameters `$params` of the group picked by `$selector`.
+#[macro_export]
+macro_rules! group_of {
+    ($selector:expr, $G:ident, $params:ident => $body:expr) => {{
+        use ::chaum_pedersen::protocol::constants::{
+            DLOG_GROUP_PARAMS, PALLAS_GROUP_PARAMS, VESTA_GROUP_PARAMS,
+        };
+        use ::chaum_pedersen::protocol::group::CyclicGroup;
+        use ::chaum_pedersen::protocol::GroupParams;
+        match $selector as usize % $crate::GROUPS.len() {
+            0 => {
+                type $G = ::chaum_pedersen::protocol::discrete_log::DiscreteLog;
+                let $params: &GroupParams<<$G as CyclicGroup>::Element> = &DLOG_GROUP_PARAMS;
+                $body
+            }
+            1 => {
+                type $G = ::chaum_pedersen::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+                let $params: &GroupParams<<$G as CyclicGroup>::Element> = &PALLAS_GROUP_PARAMS;
+                $body
+            }
+            _ => {
+                type $G = ::chaum_pedersen::protocol::elliptic_curves::vesta::VestaEllipticCurve;
+                let $params: &GroupParams<<$G as CyclicGroup>::Element> = &VESTA_GROUP_PARAMS;
+                $body
+            }
+        }
+    }};
+}
@@ -1,3 +1,5 @@
+use chaum_pedersen::protocol::kdf::KdfParams;
+
 #[derive(Debug, Clone)]
 pub struct User<T> {
     pub username: String,
@@ -5,6 +7,8 @@ pub struct User<T> {
     pub y2: T,
     pub r1: Option<T>,
     pub r2: Option<T>,
+    /// The salt and costs the secret was derived with from a password, if it was.
+    pub kdf: Option<KdfParams>,
 }
 
 #[derive(Debug, Clone)]
@@ -14,6 +18,15 @@ pub struct AuthChallenge<S> {
     pub c: S,
 }
 
+/// A pending challenge of the parallel mode, with the commitments `(r1, r2)` of every round.
+#[derive(Debug, Clone)]
+pub struct ParallelChallenge<T> {
+    pub id: String,
+    pub user: String,
+    pub commitments: Vec<(T, T)>,
+    pub c: Vec<u64>,
+}
+
 /// Trait defining the API for User struct.
 ///
 /// This trait abstracts the CRUD (Create, Read, Update, Delete) operations
@@ -23,13 +36,35 @@ pub trait UserAPI<T, S> {
 
     fn read(&mut self, username: &str) -> Option<User<T>>;
 
-    fn update(&mut self, name: &String, user: User<T>) -> Option<()>;
+    fn update(&mut self, name: &str, user: User<T>) -> Option<()>;
+
+    fn delete(&mut self, name: &str) -> Option<User<T>>;
+
+    fn create_auth_challenge(&mut self, user: &str, c: &S) -> String;
+
+    fn delete_auth_challenge(&mut self, id: &str);
+
+    fn get_auth_challenge(&mut self, id: &str) -> Option<AuthChallenge<S>>;
+
+    /// Stores a challenge of the parallel mode, `None` if too many are outstanding.
+    fn create_parallel_challenge(
+        &mut self,
+        user: &str,
+        commitments: Vec<(T, T)>,
+        c: Vec<u64>,
+    ) -> Option<String>;
 
-    fn delete(&mut self, name: &String) -> Option<User<T>>;
+    /// Removes the challenge, returns it if it was neither answered nor expired.
+    fn take_parallel_challenge(&mut self, id: &str) -> Option<ParallelChallenge<T>>;
 
-    fn create_auth_challenge(&mut self, user: &String, c: &S) -> String;
+    /// Issues a one-time nonce for a signed request of the user, `None` if too many are
+    /// outstanding.
+    fn create_nonce(&mut self, user: &str) -> Option<String>;
 
-    fn delete_auth_challenge(&mut self, id: &String);
+    /// Consumes the nonce, returns the user it was issued for if it was neither used nor expired.
+    fn take_nonce(&mut self, nonce: &str) -> Option<String>;
 
-    fn get_auth_challenge(&mut self, id: &String) -> Option<AuthChallenge<S>>;
+    /// Records the key image of a ring signature on `topic`, returns `false` if it was already
+    /// seen on that topic.
+    fn record_key_image(&mut self, topic: &str, key_image: &[u8]) -> bool;
 }
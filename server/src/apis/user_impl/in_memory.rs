@@ -1,15 +1,94 @@
 use crate::apis::user::User;
 use crate::apis::user::UserAPI;
 use chaum_pedersen::traits::{FromBytes, IntoBytes};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
-use crate::apis::user::AuthChallenge;
+use crate::apis::user::{AuthChallenge, ParallelChallenge};
+
+/// How long an issued nonce or parallel challenge can be used.
+pub const PENDING_TTL: Duration = Duration::from_secs(300);
+/// Most nonces, or parallel challenges, outstanding for one user, the oldest being dropped for a
+/// new one.
+pub const MAX_PENDING_PER_USER: usize = 16;
+/// Most nonces, or parallel challenges, outstanding for all users, none being issued beyond it
+/// until some expire.
+pub const MAX_PENDING: usize = 65536;
+
+/// Values issued to users and used at most once, which expire and are bounded in number so that
+/// requests nobody follows up on cannot exhaust the memory.
+struct Pending<V> {
+    entries: HashMap<String, (String, Instant, V)>,
+    /// Ids in the order they were issued, including some already used.
+    issued: VecDeque<(Instant, String)>,
+    /// Ids of every user in the order they were issued, including some already used.
+    by_user: HashMap<String, VecDeque<String>>,
+}
+
+impl<V> Pending<V> {
+    fn new() -> Self {
+        Pending {
+            entries: HashMap::new(),
+            issued: VecDeque::new(),
+            by_user: HashMap::new(),
+        }
+    }
+
+    /// Issues the value to the user under a fresh id, `None` if too many are outstanding.
+    fn insert(&mut self, user: &str, value: V) -> Option<String> {
+        let now = Instant::now();
+        self.expire(now);
+        let ids = self.by_user.entry(user.to_string()).or_default();
+        ids.retain(|id| self.entries.contains_key(id));
+        if ids.len() >= MAX_PENDING_PER_USER {
+            if let Some(oldest) = ids.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+        if self.entries.len() >= MAX_PENDING {
+            return None;
+        }
+        let id = Uuid::new_v4().to_string();
+        ids.push_back(id.clone());
+        self.issued.push_back((now, id.clone()));
+        self.entries
+            .insert(id.clone(), (user.to_string(), now, value));
+        // ids used up early stay queued until they expire, drop them once they dominate
+        if self.issued.len() > 2 * MAX_PENDING {
+            self.issued.retain(|(_, id)| self.entries.contains_key(id));
+        }
+        Some(id)
+    }
+
+    /// Removes the value, returns it with its user unless it expired.
+    fn take(&mut self, id: &str) -> Option<(String, V)> {
+        let (user, issued, value) = self.entries.remove(id)?;
+        (issued.elapsed() < PENDING_TTL).then_some((user, value))
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some((issued, id)) = self.issued.front() {
+            if now.duration_since(*issued) < PENDING_TTL {
+                break;
+            }
+            self.entries.remove(id);
+            self.issued.pop_front();
+        }
+    }
+}
+
+/// The commitments and challenges of the rounds of a parallel challenge.
+type Rounds<T> = (Vec<(T, T)>, Vec<u64>);
 
 /// This struct for an in-memory implementation of the `UserAPI` trait using hash maps.
 pub struct InMemoryUserAPI<T, S> {
     users: HashMap<String, User<T>>,
     auth_challenges: HashMap<String, AuthChallenge<S>>,
+    parallel_challenges: Pending<Rounds<T>>,
+    nonces: Pending<()>,
+    /// Key images seen so far, per topic.
+    key_images: HashMap<String, HashSet<Vec<u8>>>,
 }
 
 impl<T, S> InMemoryUserAPI<T, S> {
@@ -17,10 +96,19 @@ impl<T, S> InMemoryUserAPI<T, S> {
         InMemoryUserAPI {
             users: HashMap::new(),
             auth_challenges: HashMap::new(),
+            parallel_challenges: Pending::new(),
+            nonces: Pending::new(),
+            key_images: HashMap::new(),
         }
     }
 }
 
+impl<T, S> Default for InMemoryUserAPI<T, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T, S> UserAPI<T, S> for InMemoryUserAPI<T, S>
 where
     T: Send + Sync + 'static + Clone + FromBytes<T> + IntoBytes<T>,
@@ -34,7 +122,7 @@ where
         self.users.get(username).cloned()
     }
 
-    fn update(&mut self, name: &String, new_user: User<T>) -> Option<()> {
+    fn update(&mut self, name: &str, new_user: User<T>) -> Option<()> {
         if let Some(user) = self.users.get_mut(name) {
             *user = new_user;
             Some(())
@@ -43,26 +131,96 @@ where
         }
     }
 
-    fn delete(&mut self, name: &String) -> Option<User<T>> {
+    fn delete(&mut self, name: &str) -> Option<User<T>> {
         self.users.remove(name)
     }
 
-    fn create_auth_challenge(&mut self, user: &String, c: &S) -> String {
+    fn create_auth_challenge(&mut self, user: &str, c: &S) -> String {
         let uid = Uuid::new_v4().to_string();
         let auth_challenge = AuthChallenge {
             id: uid.clone(),
-            user: user.clone(),
+            user: user.to_string(),
             c: c.clone(),
         };
         self.auth_challenges.insert(uid.clone(), auth_challenge);
         uid
     }
 
-    fn delete_auth_challenge(&mut self, id: &String) {
+    fn delete_auth_challenge(&mut self, id: &str) {
         self.auth_challenges.remove(id);
     }
 
-    fn get_auth_challenge(&mut self, id: &String) -> Option<AuthChallenge<S>> {
+    fn get_auth_challenge(&mut self, id: &str) -> Option<AuthChallenge<S>> {
         self.auth_challenges.get(id).cloned()
     }
+
+    fn create_parallel_challenge(
+        &mut self,
+        user: &str,
+        commitments: Vec<(T, T)>,
+        c: Vec<u64>,
+    ) -> Option<String> {
+        self.parallel_challenges.insert(user, (commitments, c))
+    }
+
+    fn take_parallel_challenge(&mut self, id: &str) -> Option<ParallelChallenge<T>> {
+        let (user, (commitments, c)) = self.parallel_challenges.take(id)?;
+        Some(ParallelChallenge {
+            id: id.to_string(),
+            user,
+            commitments,
+            c,
+        })
+    }
+
+    fn create_nonce(&mut self, user: &str) -> Option<String> {
+        self.nonces.insert(user, ())
+    }
+
+    fn take_nonce(&mut self, nonce: &str) -> Option<String> {
+        self.nonces.take(nonce).map(|(user, ())| user)
+    }
+
+    fn record_key_image(&mut self, topic: &str, key_image: &[u8]) -> bool {
+        self.key_images
+            .entry(topic.to_string())
+            .or_default()
+            .insert(key_image.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigUint;
+
+    #[test]
+    fn pending_values_are_used_once_and_bounded() {
+        let mut pending = Pending::new();
+        let id = pending.insert("peggy", 1).unwrap();
+        assert_eq!(pending.take(&id), Some(("peggy".to_string(), 1)));
+        assert_eq!(pending.take(&id), None);
+
+        // a new value of a user drops the oldest beyond the cap
+        let ids: Vec<_> = (0..=MAX_PENDING_PER_USER)
+            .map(|i| pending.insert("peggy", i).unwrap())
+            .collect();
+        assert_eq!(pending.take(&ids[0]), None);
+        assert_eq!(pending.take(&ids[1]), Some(("peggy".to_string(), 1)));
+
+        // no more values are issued once they are all taken up
+        let mut pending = Pending::new();
+        for i in 0..MAX_PENDING {
+            pending.insert(&(i % 4096).to_string(), ()).unwrap();
+        }
+        assert_eq!(pending.insert("victor", ()), None);
+    }
+
+    #[test]
+    fn key_images_are_recorded_per_topic() {
+        let mut api = InMemoryUserAPI::<BigUint, BigUint>::new();
+        assert!(api.record_key_image("poll-1", b"image"));
+        assert!(!api.record_key_image("poll-1", b"image"));
+        assert!(api.record_key_image("poll-2", b"image"));
+    }
 }
@@ -1,4 +1,5 @@
-use chaum_pedersen::enums::{EllipticCurve, Flavor};
+use chaum_pedersen::enums::{EllipticCurve, Flavor, KdfAlgorithm, TextEncoding};
+use std::path::PathBuf;
 use structopt::StructOpt;
 use strum::VariantNames;
 
@@ -14,4 +15,41 @@ pub struct Cli {
     /// Elliptic curve type, required if one is using elliptic_curve flavor.
     #[structopt(short, long, possible_values = EllipticCurve::VARIANTS, default_value = "pallas", required_if("flavor", "elliptic_curve"))]
     pub curve: EllipticCurve,
+    /// File holding custom group parameters for the flavor, in JSON or any text encoding.
+    #[structopt(long, env = "ZKP_PARAMS", parse(from_os_str))]
+    pub params: Option<PathBuf>,
+    /// Also serve verifiable oblivious PRF evaluations, under the key of `--voprf-key`.
+    #[structopt(long, requires = "voprf-key")]
+    pub voprf: bool,
+    /// File holding the secret key of the VOPRF, as written by `--generate-voprf-key`.
+    #[structopt(long, env = "ZKP_VOPRF_KEY", parse(from_os_str))]
+    pub voprf_key: Option<PathBuf>,
+    /// Write a fresh VOPRF key to the `--voprf-key` file, and exit.
+    #[structopt(long, requires = "voprf-key")]
+    pub generate_voprf_key: bool,
+    /// Memory-hard function of the key derivation parameters handed to users registering a
+    /// password, with the recommended costs.
+    #[structopt(long, possible_values = KdfAlgorithm::VARIANTS, default_value = "argon2id")]
+    pub kdf: KdfAlgorithm,
+    /// Also serve the other curve of the Pasta cycle on this port, users migrating from either
+    /// curve being registered on the other one. The VOPRF is only served for `--curve`.
+    #[structopt(long, conflicts_with = "voprf")]
+    pub migration_port: Option<i32>,
+    /// File holding custom group parameters for the other curve of `--migration-port`.
+    #[structopt(
+        long,
+        env = "ZKP_MIGRATION_PARAMS",
+        requires = "migration-port",
+        parse(from_os_str)
+    )]
+    pub migration_params: Option<PathBuf>,
+    /// Enables the parallel mode, with challenges of this many bits.
+    #[structopt(long)]
+    pub challenge_bits: Option<u32>,
+    /// Soundness target of the parallel mode, in bits, 256 at most.
+    #[structopt(long, default_value = "128")]
+    pub soundness_bits: usize,
+    /// Print the group parameters in this text encoding, and exit.
+    #[structopt(long, possible_values = TextEncoding::VARIANTS)]
+    pub export_params: Option<TextEncoding>,
 }
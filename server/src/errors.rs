@@ -4,4 +4,6 @@ use thiserror::Error;
 pub enum CliError {
     #[error("Failed to parse server address")]
     AddressParsing,
+    #[error("Migrations are only served between the curves of the Pasta cycle")]
+    MigrationFlavor,
 }
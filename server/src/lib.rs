@@ -0,0 +1,10 @@
+//! The authentication service, as a library for the server binary and the fuzz targets.
+
+/// Core services and business logic implementation.
+pub mod service;
+
+/// Verifiable oblivious PRF evaluation service.
+pub mod oprf;
+
+/// CRUD APIs to work with storage.
+pub mod apis;
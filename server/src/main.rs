@@ -1,22 +1,26 @@
-/// Core services and business logic implementation.
-pub mod service;
-
-/// CRUD APIs to work with storage.
-pub mod apis;
-
 pub mod cli;
 pub mod errors;
 
 use anyhow::Result;
-use chaum_pedersen::enums::{EllipticCurve, Flavor};
+use chaum_pedersen::enums::{EllipticCurve, Flavor, TextEncoding};
+use chaum_pedersen::protocol::armor;
 use chaum_pedersen::protocol::constants::{
     DLOG_GROUP_PARAMS, PALLAS_GROUP_PARAMS, VESTA_GROUP_PARAMS,
 };
 use chaum_pedersen::protocol::discrete_log::DiscreteLog;
 use chaum_pedersen::protocol::elliptic_curves::pallas::PallasEllipticCurve;
 use chaum_pedersen::protocol::elliptic_curves::vesta::VestaEllipticCurve;
-use service::zkp_auth::auth_server::AuthServer;
-use service::AuthService;
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::group_params;
+use chaum_pedersen::protocol::parallel::ParallelConfig;
+use chaum_pedersen::protocol::wire::Envelope;
+use chaum_pedersen::protocol::GroupParams;
+use server::oprf::{self, OprfService};
+use server::service::zkp_auth::auth_server::AuthServer;
+use server::service::zkp_auth::oprf_server::OprfServer;
+use server::service::AuthService;
+use std::net::SocketAddr;
+use std::path::Path;
 use structopt::StructOpt;
 use tonic::transport::Server;
 
@@ -31,9 +35,53 @@ async fn main() -> Result<()> {
         port,
         flavor,
         curve,
-        ..
+        params,
+        voprf,
+        voprf_key,
+        generate_voprf_key,
+        kdf,
+        migration_port,
+        migration_params,
+        challenge_bits,
+        soundness_bits,
+        export_params,
     } = Cli::from_args();
 
+    let params_file = params.as_deref();
+    let migration_params_file = migration_params.as_deref();
+    if let Some(encoding) = export_params {
+        return match (flavor, curve) {
+            (Flavor::DiscreteLog, _) => {
+                print_params::<DiscreteLog>(params_file, &DLOG_GROUP_PARAMS, encoding)
+            }
+            (Flavor::EllipticCurve, EllipticCurve::Pallas) => {
+                print_params::<PallasEllipticCurve>(params_file, &PALLAS_GROUP_PARAMS, encoding)
+            }
+            (Flavor::EllipticCurve, EllipticCurve::Vesta) => {
+                print_params::<VestaEllipticCurve>(params_file, &VESTA_GROUP_PARAMS, encoding)
+            }
+        };
+    }
+
+    if let (true, Some(path)) = (generate_voprf_key, voprf_key.as_deref()) {
+        return match (flavor, curve) {
+            (Flavor::DiscreteLog, _) => {
+                generate_key::<DiscreteLog>(params_file, &DLOG_GROUP_PARAMS, path)
+            }
+            (Flavor::EllipticCurve, EllipticCurve::Pallas) => {
+                generate_key::<PallasEllipticCurve>(params_file, &PALLAS_GROUP_PARAMS, path)
+            }
+            (Flavor::EllipticCurve, EllipticCurve::Vesta) => {
+                generate_key::<VestaEllipticCurve>(params_file, &VESTA_GROUP_PARAMS, path)
+            }
+        };
+    }
+    let voprf_key = voprf_key.as_deref().filter(|_| voprf);
+
+    let parallel = challenge_bits
+        .map(|bits| ParallelConfig::for_soundness(bits, soundness_bits))
+        .transpose()?;
+
     println!(" Starting server ");
     println!("       host: {}", host);
     println!("       port: {}", port);
@@ -41,41 +89,191 @@ async fn main() -> Result<()> {
     if flavor == Flavor::EllipticCurve {
         println!("       elliptic curve: {}", curve)
     }
+    if voprf {
+        println!("       voprf: enabled")
+    }
+    if let Some(port) = migration_port {
+        if flavor != Flavor::EllipticCurve {
+            return Err(CliError::MigrationFlavor.into());
+        }
+        println!("       migration port: {}", port)
+    }
+    if let Some(config) = parallel {
+        println!(
+            "       parallel: {} rounds of {}-bit challenges, soundness error 2^-{}",
+            config.rounds,
+            config.challenge_bits,
+            config.soundness_bits()
+        )
+    }
 
     let addr = format!("{}:{}", host, port)
         .parse()
         .map_err(|_| CliError::AddressParsing)?;
+    let other_addr: Option<SocketAddr> = migration_port
+        .map(|port| format!("{}:{}", host, port).parse())
+        .transpose()
+        .map_err(|_| CliError::AddressParsing)?;
 
     // Initialize and start the server based on flavor.
     match flavor {
         Flavor::DiscreteLog => {
-            let params = DLOG_GROUP_PARAMS.to_owned();
-            let auth = AuthService::<DiscreteLog, _, _>::new(params);
+            let params = group_params::load::<DiscreteLog>(params_file, &DLOG_GROUP_PARAMS)?;
+            println!(
+                "       params: {}",
+                group_params::describe::<DiscreteLog>(&params, params_file)
+            );
+            let oprf = oprf_server::<DiscreteLog>(&params, voprf_key)?;
+            let auth = AuthService::<DiscreteLog, _, _>::new(params)
+                .with_parallel(parallel)
+                .with_kdf(kdf);
             Server::builder()
                 .add_service(AuthServer::new(auth))
+                .add_optional_service(oprf)
                 .serve(addr)
                 .await?;
         }
         Flavor::EllipticCurve => match curve {
             EllipticCurve::Pallas => {
-                let params = PALLAS_GROUP_PARAMS.to_owned();
-                let auth = AuthService::<PallasEllipticCurve, _, _>::new(params);
-                Server::builder()
-                    .add_service(AuthServer::new(auth))
-                    .serve(addr)
-                    .await?;
+                let params =
+                    group_params::load::<PallasEllipticCurve>(params_file, &PALLAS_GROUP_PARAMS)?;
+                println!(
+                    "       params: {}",
+                    group_params::describe::<PallasEllipticCurve>(&params, params_file)
+                );
+                let oprf = oprf_server::<PallasEllipticCurve>(&params, voprf_key)?;
+                let auth = AuthService::<PallasEllipticCurve, _, _>::new(params)
+                    .with_parallel(parallel)
+                    .with_kdf(kdf);
+                let Some(other_addr) = other_addr else {
+                    Server::builder()
+                        .add_service(AuthServer::new(auth))
+                        .add_optional_service(oprf)
+                        .serve(addr)
+                        .await?;
+                    return Ok(());
+                };
+                let other_params = group_params::load::<VestaEllipticCurve>(
+                    migration_params_file,
+                    &VESTA_GROUP_PARAMS,
+                )?;
+                println!(
+                    "       migration params: {}",
+                    group_params::describe::<VestaEllipticCurve>(
+                        &other_params,
+                        migration_params_file
+                    )
+                );
+                // users move between the two curves, each service registering the users of the other
+                let other = AuthService::<VestaEllipticCurve, _, _>::new(other_params)
+                    .with_parallel(parallel)
+                    .with_kdf(kdf);
+                let (users, other_users) = (auth.users(), other.users());
+                let auth = auth.with_migration::<VestaEllipticCurve>(other_params, other_users);
+                let other = other.with_migration::<PallasEllipticCurve>(params, users);
+                tokio::try_join!(
+                    Server::builder()
+                        .add_service(AuthServer::new(auth))
+                        .add_optional_service(oprf)
+                        .serve(addr),
+                    Server::builder()
+                        .add_service(AuthServer::new(other))
+                        .serve(other_addr),
+                )?;
             }
 
             EllipticCurve::Vesta => {
-                let params = VESTA_GROUP_PARAMS.to_owned();
-                let auth = AuthService::<VestaEllipticCurve, _, _>::new(params);
-                Server::builder()
-                    .add_service(AuthServer::new(auth))
-                    .serve(addr)
-                    .await?;
+                let params =
+                    group_params::load::<VestaEllipticCurve>(params_file, &VESTA_GROUP_PARAMS)?;
+                println!(
+                    "       params: {}",
+                    group_params::describe::<VestaEllipticCurve>(&params, params_file)
+                );
+                let oprf = oprf_server::<VestaEllipticCurve>(&params, voprf_key)?;
+                let auth = AuthService::<VestaEllipticCurve, _, _>::new(params)
+                    .with_parallel(parallel)
+                    .with_kdf(kdf);
+                let Some(other_addr) = other_addr else {
+                    Server::builder()
+                        .add_service(AuthServer::new(auth))
+                        .add_optional_service(oprf)
+                        .serve(addr)
+                        .await?;
+                    return Ok(());
+                };
+                let other_params = group_params::load::<PallasEllipticCurve>(
+                    migration_params_file,
+                    &PALLAS_GROUP_PARAMS,
+                )?;
+                println!(
+                    "       migration params: {}",
+                    group_params::describe::<PallasEllipticCurve>(
+                        &other_params,
+                        migration_params_file
+                    )
+                );
+                // users move between the two curves, each service registering the users of the other
+                let other = AuthService::<PallasEllipticCurve, _, _>::new(other_params)
+                    .with_parallel(parallel)
+                    .with_kdf(kdf);
+                let (users, other_users) = (auth.users(), other.users());
+                let auth = auth.with_migration::<PallasEllipticCurve>(other_params, other_users);
+                let other = other.with_migration::<VestaEllipticCurve>(params, users);
+                tokio::try_join!(
+                    Server::builder()
+                        .add_service(AuthServer::new(auth))
+                        .add_optional_service(oprf)
+                        .serve(addr),
+                    Server::builder()
+                        .add_service(AuthServer::new(other))
+                        .serve(other_addr),
+                )?;
             }
         },
     }
 
     Ok(())
 }
+
+/// Writes a fresh VOPRF key for the group parameters, custom ones if a file is given.
+fn generate_key<G: CyclicGroup>(
+    params: Option<&Path>,
+    default: &GroupParams<G::Element>,
+    path: &Path,
+) -> Result<()> {
+    let params = group_params::load::<G>(params, default)?;
+    oprf::generate_key::<G>(&params, path)?;
+    println!("VOPRF key written to {}", path.display());
+    Ok(())
+}
+
+/// The VOPRF service under the key of the file, if one is given.
+fn oprf_server<G>(
+    params: &GroupParams<G::Element>,
+    key: Option<&Path>,
+) -> Result<Option<OprfServer<OprfService<G>>>>
+where
+    G: CyclicGroup + Send + Sync + 'static,
+    G::Element: Send + Sync,
+    G::Scalar: Send + Sync,
+{
+    key.map(|path| {
+        let key = oprf::load_key::<G>(params, path)?;
+        Ok(OprfServer::new(OprfService::new(params.clone(), key)))
+    })
+    .transpose()
+}
+
+/// Prints the group parameters, custom ones if a file is given, in the given text encoding.
+fn print_params<G: CyclicGroup>(
+    path: Option<&Path>,
+    default: &GroupParams<G::Element>,
+    encoding: TextEncoding,
+) -> Result<()> {
+    let params = group_params::load::<G>(path, default)?;
+    println!(
+        "{}",
+        armor::encode(&Envelope::params::<G>(&params), encoding)
+    );
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use anyhow::{Context, Result};
+use chaum_pedersen::enums::TextEncoding;
+use chaum_pedersen::protocol::armor;
+use chaum_pedersen::protocol::dleq::DleqProof;
+use chaum_pedersen::protocol::elgamal::KeyPair;
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::secret::Secret;
+use chaum_pedersen::protocol::transcript::Transcript;
+use chaum_pedersen::protocol::voprf;
+use chaum_pedersen::protocol::wire::Envelope;
+use chaum_pedersen::protocol::GroupParams;
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use tonic::{Request, Response, Status};
+
+use crate::service::zkp_auth::{
+    oprf_server::Oprf, BlindEvaluateRequest, BlindEvaluateResponse, OprfPublicKeyRequest,
+    OprfPublicKeyResponse,
+};
+
+/// Label binding the evaluation proofs to this service.
+pub const OPRF_CONTEXT: &[u8] = b"zkp-auth-voprf";
+
+/// Most elements a `BlindEvaluate` request may hold.
+pub const MAX_BATCH_SIZE: usize = 64;
+
+/// A struct representing the VOPRF evaluation service, holding the server key.
+pub struct OprfService<G: CyclicGroup> {
+    params: GroupParams<G::Element>,
+    key: KeyPair<G>,
+}
+
+impl<G: CyclicGroup> OprfService<G> {
+    /// Creates the service with the given key, which must outlive restarts for the outputs to
+    /// stay the same.
+    pub fn new(params: GroupParams<G::Element>, key: KeyPair<G>) -> Self {
+        Self { params, key }
+    }
+}
+
+/// Writes a fresh key, armored, to a new file only its owner can read.
+pub fn generate_key<G: CyclicGroup>(params: &GroupParams<G::Element>, path: &Path) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to create key file {}", path.display()))?;
+    let x = Secret::new(G::random_scalar(params));
+    let text = Secret::new(
+        armor::encode(&Envelope::secret::<G>(x.expose()), TextEncoding::Armor).into_bytes(),
+    );
+    file.write_all(text.expose())?;
+    Ok(())
+}
+
+/// Reads the key from a file written by `generate_key`.
+pub fn load_key<G: CyclicGroup>(
+    params: &GroupParams<G::Element>,
+    path: &Path,
+) -> Result<KeyPair<G>> {
+    let text = Secret::new(
+        fs::read(path).with_context(|| format!("Failed to read key file {}", path.display()))?,
+    );
+    let text = std::str::from_utf8(text.expose()).context("Malformed key file")?;
+    let x = armor::decode(text)?.to_secret::<G>()?;
+    Ok(KeyPair::from_secret(params, x))
+}
+
+#[tonic::async_trait]
+impl<G> Oprf for OprfService<G>
+where
+    G: CyclicGroup + Send + Sync + 'static,
+    G::Element: Send + Sync,
+    G::Scalar: Send + Sync,
+{
+    async fn get_public_key(
+        &self,
+        _: Request<OprfPublicKeyRequest>,
+    ) -> Result<Response<OprfPublicKeyResponse>, Status> {
+        let reply = OprfPublicKeyResponse {
+            pk: G::Element::to(&self.key.y),
+        };
+        Ok(Response::new(reply))
+    }
+
+    async fn blind_evaluate(
+        &self,
+        request: Request<BlindEvaluateRequest>,
+    ) -> Result<Response<BlindEvaluateResponse>, Status> {
+        let req = request.into_inner();
+        if req.blinded.len() > MAX_BATCH_SIZE {
+            return Err(Status::invalid_argument("Too many blinded elements"));
+        }
+
+        let blinded = req
+            .blinded
+            .iter()
+            .map(|b| <G::Element as FromBytes<_>>::from(b))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| Status::invalid_argument("Invalid blinded element"))?;
+        let evaluation = voprf::blind_evaluate(
+            &self.params,
+            &Transcript::new(OPRF_CONTEXT),
+            &self.key,
+            &blinded,
+        )
+        .map_err(|_| Status::invalid_argument("Invalid blinded element"))?;
+
+        let reply = BlindEvaluateResponse {
+            evaluated: evaluation.elements.iter().map(G::Element::to).collect(),
+            proof: DleqProof::to(&evaluation.proof),
+        };
+        Ok(Response::new(reply))
+    }
+}
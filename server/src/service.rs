@@ -1,7 +1,21 @@
 use crate::apis::user_impl::in_memory::InMemoryUserAPI;
 use crate::apis::{user::User, user::UserAPI};
+use chaum_pedersen::enums::KdfAlgorithm;
+use chaum_pedersen::protocol::contexts;
+use chaum_pedersen::protocol::cross_group::CrossGroupProof;
+use chaum_pedersen::protocol::dleq::{DleqProof, DleqStatement};
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::kdf::KdfParams;
+use chaum_pedersen::protocol::parallel::{self, ParallelConfig, RoundCommitment};
+use chaum_pedersen::protocol::rekey::RekeyProof;
+use chaum_pedersen::protocol::ring::RingSignature;
+use chaum_pedersen::protocol::schnorr::Signature;
 use chaum_pedersen::protocol::{GroupParams, Protocol};
 use chaum_pedersen::traits::{FromBytes, IntoBytes};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::marker::PhantomData;
+use std::sync::Arc;
 use tokio::sync::Mutex;
 use tonic::{Request, Response, Status};
 use uuid::Uuid;
@@ -14,14 +28,201 @@ pub mod zkp_auth {
 // Protobuf imports
 use zkp_auth::{
     auth_server::Auth, AuthenticationAnswerRequest, AuthenticationAnswerResponse,
-    AuthenticationChallengeRequest, AuthenticationChallengeResponse, RegisterRequest,
-    RegisterResponse,
+    AuthenticationChallengeRequest, AuthenticationChallengeResponse, KdfParamsRequest,
+    MigrateUserRequest, MigrateUserResponse, NonceRequest, NonceResponse, ParallelAnswerRequest,
+    ParallelChallengeRequest, ParallelChallengeResponse, ParallelConfigRequest,
+    ParallelConfigResponse, RegisterRequest, RegisterResponse, RekeyUserRequest, RekeyUserResponse,
+    RingSignatureRequest, RingSignatureResponse, SignedActionRequest, SignedActionResponse,
 };
 
+/// Most members of a ring `VerifyRingSignature` checks a signature against, since the work is
+/// linear in the size of the ring.
+pub const MAX_RING_SIZE: usize = 256;
+
+/// The registrations of a service, which the service of another group writes migrated users to.
+pub type Users<T, S> = Arc<Mutex<Box<dyn UserAPI<T, S> + Send + Sync>>>;
+
+/// Takes over the users migrating away from a service.
+#[tonic::async_trait]
+trait MigrationTarget<T, S>: Send + Sync {
+    /// Checks that the keys of the request hold the same secret as the `user` of the `source`
+    /// registrations, and moves the user over to them.
+    async fn import(
+        &self,
+        params: &GroupParams<T>,
+        source: &Users<T, S>,
+        user: &User<T>,
+        req: &MigrateUserRequest,
+    ) -> Result<(), Status>;
+
+    /// Checks that the `user` of the `source` registrations hands over to the fresh keys of the
+    /// request, and moves the user over to them with the same key derivation parameters.
+    async fn rekey(
+        &self,
+        params: &GroupParams<T>,
+        source: &Users<T, S>,
+        user: &User<T>,
+        req: &RekeyUserRequest,
+    ) -> Result<(), Status>;
+}
+
+/// The registrations of the group `D` users of the group `C` migrate to.
+struct Target<C, D: CyclicGroup> {
+    params: GroupParams<D::Element>,
+    users: Users<D::Element, D::Scalar>,
+    _source: PhantomData<fn() -> C>,
+}
+
+impl<C, D> Target<C, D>
+where
+    C: CyclicGroup,
+    D: CyclicGroup,
+{
+    /// Moves the `user` of the `source` registrations, whose proof was checked, to the keys
+    /// `(y1, y2)`.
+    async fn move_user(
+        &self,
+        source: &Users<C::Element, C::Scalar>,
+        user: &User<C::Element>,
+        (y1, y2): (D::Element, D::Element),
+        kdf: Option<KdfParams>,
+    ) -> Result<(), Status> {
+        // both services migrate users into each other, so the registrations are locked in the
+        // order of their addresses, and held until the user has moved
+        let source_first = (Arc::as_ptr(source) as *const () as usize)
+            < (Arc::as_ptr(&self.users) as *const () as usize);
+        let (mut from, mut to) = if source_first {
+            let from = source.lock().await;
+            (from, self.users.lock().await)
+        } else {
+            let to = self.users.lock().await;
+            (source.lock().await, to)
+        };
+        // the proof was checked without the locks, against the keys read before
+        let current = from
+            .read(&user.username)
+            .ok_or_else(|| Status::not_found("User not found"))?;
+        if C::Element::to(&current.y1) != C::Element::to(&user.y1)
+            || C::Element::to(&current.y2) != C::Element::to(&user.y2)
+        {
+            return Err(Status::aborted(
+                "User registered again during the migration",
+            ));
+        }
+        if to.read(&user.username).is_some() {
+            return Err(Status::already_exists(
+                "User already registered on the target group",
+            ));
+        }
+        // the keys on the other curve take over, the user can no longer log in here
+        to.create(User {
+            username: user.username.clone(),
+            y1,
+            y2,
+            r1: None,
+            r2: None,
+            kdf,
+        });
+        from.delete(&user.username);
+        Ok(())
+    }
+}
+
+/// Reads the keys of a migration or re-keying request to the group `D`.
+fn target_keys<D: CyclicGroup>(y1: &[u8], y2: &[u8]) -> anyhow::Result<(D::Element, D::Element)> {
+    Ok((
+        <D::Element as FromBytes<_>>::from(y1)?,
+        <D::Element as FromBytes<_>>::from(y2)?,
+    ))
+}
+
+#[tonic::async_trait]
+impl<C, D> MigrationTarget<C::Element, C::Scalar> for Target<C, D>
+where
+    C: CyclicGroup + 'static,
+    C::Element: Send + Sync,
+    C::Scalar: Send + Sync,
+    D: CyclicGroup + 'static,
+    D::Element: Send + Sync,
+    D::Scalar: Send + Sync,
+{
+    async fn import(
+        &self,
+        params: &GroupParams<C::Element>,
+        source: &Users<C::Element, C::Scalar>,
+        user: &User<C::Element>,
+        req: &MigrateUserRequest,
+    ) -> Result<(), Status> {
+        let (y1, y2) = target_keys::<D>(&req.y1, &req.y2)
+            .map_err(|_| Status::invalid_argument("Invalid keys"))?;
+        let valid = {
+            let proof = <CrossGroupProof<C, D> as FromBytes<_>>::from(&req.proof)
+                .map_err(|_| Status::invalid_argument("Invalid migration proof"))?;
+            let dleq_proof = <DleqProof<D> as FromBytes<_>>::from(&req.dleq_proof)
+                .map_err(|_| Status::invalid_argument("Invalid migration proof"))?;
+            let statement = DleqStatement::<D> {
+                g1: self.params.g.clone(),
+                y1: y1.clone(),
+                g2: self.params.h.clone(),
+                y2: y2.clone(),
+            };
+            let mut transcript = contexts::migration(&req.user, &req.nonce);
+            proof.verify(params, &self.params, &mut transcript, &user.y1, &y1)
+                && dleq_proof.verify(&self.params, &mut transcript, &statement)
+        };
+        if !valid {
+            return Err(Status::permission_denied("Invalid migration proof"));
+        }
+        // the secret stays the same, so it is no longer the one derived from a password
+        self.move_user(source, user, (y1, y2), None).await
+    }
+
+    async fn rekey(
+        &self,
+        params: &GroupParams<C::Element>,
+        source: &Users<C::Element, C::Scalar>,
+        user: &User<C::Element>,
+        req: &RekeyUserRequest,
+    ) -> Result<(), Status> {
+        let (y1, y2) = target_keys::<D>(&req.y1, &req.y2)
+            .map_err(|_| Status::invalid_argument("Invalid keys"))?;
+        let valid = {
+            let proof = <RekeyProof<C, D> as FromBytes<_>>::from(&req.proof)
+                .map_err(|_| Status::invalid_argument("Invalid re-keying proof"))?;
+            let context = contexts::rekey(&req.user, &req.nonce);
+            proof.verify(params, &self.params, &context, &user.y1, &y1, &y2)
+        };
+        if !valid {
+            return Err(Status::permission_denied("Invalid re-keying proof"));
+        }
+        self.move_user(source, user, (y1, y2), user.kdf.clone())
+            .await
+    }
+}
+
+/// Reads key derivation parameters of a request, and checks that they are within the bounds.
+fn kdf_params(kdf: zkp_auth::KdfParams) -> anyhow::Result<KdfParams> {
+    let kdf = KdfParams {
+        algorithm: kdf.algorithm.parse()?,
+        salt: kdf.salt,
+        memory_cost: kdf.memory_cost,
+        time_cost: kdf.time_cost,
+        parallelism: kdf.parallelism,
+    };
+    kdf.check()?;
+    Ok(kdf)
+}
+
 /// A struct representing the authentication service.
 pub struct AuthService<C, T, S> {
     params: GroupParams<T>,
-    api: Mutex<Box<dyn UserAPI<T, S> + Send + Sync>>,
+    api: Users<T, S>,
+    migration: Option<Box<dyn MigrationTarget<T, S>>>,
+    parallel: Option<ParallelConfig>,
+    kdf_algorithm: KdfAlgorithm,
+    /// Key of the salts handed out for unknown users. The users are kept in memory, so a key
+    /// drawn at startup lasts as long as the registrations it hides.
+    kdf_key: [u8; 32],
     _type_phantom: std::marker::PhantomData<C>,
     _scalar_phantom: std::marker::PhantomData<S>,
 }
@@ -33,16 +234,60 @@ impl<
     > AuthService<C, T, S>
 {
     pub fn new(params: GroupParams<T>) -> Self {
-        let api = Mutex::new(
+        let api = Arc::new(Mutex::new(
             Box::new(InMemoryUserAPI::<T, S>::new()) as Box<dyn UserAPI<T, S> + Send + Sync>
-        );
+        ));
+        let mut kdf_key = [0u8; 32];
+        OsRng.fill_bytes(&mut kdf_key);
         Self {
             params,
             api,
+            migration: None,
+            parallel: None,
+            kdf_algorithm: KdfAlgorithm::Argon2id,
+            kdf_key,
             _type_phantom: std::marker::PhantomData,
             _scalar_phantom: std::marker::PhantomData,
         }
     }
+
+    /// Enables the parallel mode with short challenges, if configured.
+    pub fn with_parallel(mut self, config: Option<ParallelConfig>) -> Self {
+        self.parallel = config;
+        self
+    }
+
+    /// Sets the memory-hard function of the key derivation parameters handed to new users.
+    pub fn with_kdf(mut self, algorithm: KdfAlgorithm) -> Self {
+        self.kdf_algorithm = algorithm;
+        self
+    }
+
+    /// The registrations of the service, to share with the service users migrate from.
+    pub fn users(&self) -> Users<T, S> {
+        self.api.clone()
+    }
+
+    /// Accepts migrations of users to the group `D`, e.g. the other curve of the Pasta cycle,
+    /// whose service holds the registrations `users`.
+    pub fn with_migration<D>(
+        mut self,
+        target: GroupParams<D::Element>,
+        users: Users<D::Element, D::Scalar>,
+    ) -> Self
+    where
+        C: CyclicGroup<Element = T, Scalar = S> + 'static,
+        D: CyclicGroup + 'static,
+        D::Element: Send + Sync,
+        D::Scalar: Send + Sync,
+    {
+        self.migration = Some(Box::new(Target::<C, D> {
+            params: target,
+            users,
+            _source: PhantomData,
+        }));
+        self
+    }
 }
 
 #[tonic::async_trait]
@@ -52,12 +297,11 @@ where
     S: Send + Sync + 'static + Clone + FromBytes<S> + IntoBytes<S>,
     C: Protocol<
             Response = S,
-            CommitmentRandom = S,
             Challenge = S,
-            Secret = S,
             GroupParameters = GroupParams<T>,
             CommitParameters = (T, T, T, T),
         >
+        + CyclicGroup<Element = T, Scalar = S>
         + 'static
         + std::marker::Sync
         + std::marker::Send,
@@ -68,8 +312,13 @@ where
     ) -> Result<Response<RegisterResponse>, Status> {
         let req = request.into_inner();
 
-        let y1 = T::from(&req.y1).or_else(|_| Err(Status::invalid_argument("Invalid y1")))?;
-        let y2 = T::from(&req.y2).or_else(|_| Err(Status::invalid_argument("Invalid y2")))?;
+        let y1 = T::from(&req.y1).map_err(|_| Status::invalid_argument("Invalid y1"))?;
+        let y2 = T::from(&req.y2).map_err(|_| Status::invalid_argument("Invalid y2"))?;
+        let kdf = req
+            .kdf
+            .map(kdf_params)
+            .transpose()
+            .map_err(|_| Status::invalid_argument("Invalid key derivation parameters"))?;
 
         let user = User {
             username: req.user.clone(),
@@ -77,6 +326,7 @@ where
             y2,
             r1: None,
             r2: None,
+            kdf,
         };
 
         let mut api = self.api.lock().await;
@@ -86,6 +336,30 @@ where
         Ok(Response::new(reply))
     }
 
+    async fn get_kdf_params(
+        &self,
+        request: Request<KdfParamsRequest>,
+    ) -> Result<Response<zkp_auth::KdfParams>, Status> {
+        let req = request.into_inner();
+
+        // users that are unknown or registered without a password get parameters too, so that
+        // the answer does not tell whether a user exists
+        let kdf = {
+            let mut api = self.api.lock().await;
+            api.read(&req.user).and_then(|user| user.kdf)
+        }
+        .unwrap_or_else(|| KdfParams::dummy(self.kdf_algorithm, &self.kdf_key, &req.user));
+
+        let reply = zkp_auth::KdfParams {
+            algorithm: kdf.algorithm.to_string(),
+            salt: kdf.salt,
+            memory_cost: kdf.memory_cost,
+            time_cost: kdf.time_cost,
+            parallelism: kdf.parallelism,
+        };
+        Ok(Response::new(reply))
+    }
+
     async fn create_authentication_challenge(
         &self,
         request: Request<AuthenticationChallengeRequest>,
@@ -98,10 +372,8 @@ where
             let mut user = api
                 .read(&req.user)
                 .ok_or_else(|| Status::not_found("User not found"))?;
-            user.r1 =
-                Some(T::from(&req.r1).or_else(|_| Err(Status::invalid_argument("Invalid r1")))?);
-            user.r2 =
-                Some(T::from(&req.r2).or_else(|_| Err(Status::invalid_argument("Invalid r2")))?);
+            user.r1 = Some(T::from(&req.r1).map_err(|_| Status::invalid_argument("Invalid r1"))?);
+            user.r2 = Some(T::from(&req.r2).map_err(|_| Status::invalid_argument("Invalid r2"))?);
             user.clone()
         };
 
@@ -136,14 +408,14 @@ where
                 .ok_or_else(|| Status::not_found("User not found"))?
         };
 
-        let s = S::from(&req.s).or_else(|_| Err(Status::invalid_argument("Invalid s")))?;
+        let s = S::from(&req.s).map_err(|_| Status::invalid_argument("Invalid s"))?;
+        // the user may have registered again since the challenge, dropping the commitment
+        let (r1, r2) = user
+            .r1
+            .zip(user.r2)
+            .ok_or_else(|| Status::failed_precondition("No commitment for this challenge"))?;
         let params = self.params.clone();
-        let verified = C::verify(
-            &params,
-            &s,
-            &challenge.c,
-            &(user.y1, user.y2, user.r1.unwrap(), user.r2.unwrap()),
-        );
+        let verified = C::verify(&params, &s, &challenge.c, &(user.y1, user.y2, r1, r2));
 
         if !verified {
             return Err(Status::invalid_argument("Invalid authentication"));
@@ -155,4 +427,235 @@ where
         api.delete_auth_challenge(&req.auth_id);
         Ok(Response::new(reply))
     }
+
+    async fn create_nonce(
+        &self,
+        request: Request<NonceRequest>,
+    ) -> Result<Response<NonceResponse>, Status> {
+        let req = request.into_inner();
+
+        let nonce = {
+            let mut api = self.api.lock().await;
+            api.read(&req.user)
+                .ok_or_else(|| Status::not_found("User not found"))?;
+            api.create_nonce(&req.user)
+                .ok_or_else(|| Status::resource_exhausted("Too many pending nonces"))?
+        };
+        Ok(Response::new(NonceResponse { nonce }))
+    }
+
+    async fn verify_signed_action(
+        &self,
+        request: Request<SignedActionRequest>,
+    ) -> Result<Response<SignedActionResponse>, Status> {
+        let req = request.into_inner();
+
+        let signature = <Signature<C> as FromBytes<_>>::from(&req.signature)
+            .map_err(|_| Status::invalid_argument("Invalid signature"))?;
+
+        // the nonce is used up whether the signature verifies or not
+        let user = {
+            let mut api = self.api.lock().await;
+            if api.take_nonce(&req.nonce).as_deref() != Some(req.user.as_str()) {
+                return Err(Status::permission_denied("Invalid nonce"));
+            }
+            api.read(&req.user)
+                .ok_or_else(|| Status::not_found("User not found"))?
+        };
+
+        let context = contexts::action(&req.user, &req.nonce);
+        if !signature.verify_in_context(&self.params, &context, &user.y1, &req.payload) {
+            return Err(Status::permission_denied("Invalid signature"));
+        }
+        Ok(Response::new(SignedActionResponse {}))
+    }
+
+    async fn verify_ring_signature(
+        &self,
+        request: Request<RingSignatureRequest>,
+    ) -> Result<Response<RingSignatureResponse>, Status> {
+        let req = request.into_inner();
+
+        let signature = <RingSignature<C> as FromBytes<_>>::from(&req.signature)
+            .map_err(|_| Status::invalid_argument("Invalid signature"))?;
+
+        if req.ring.len() > MAX_RING_SIZE {
+            return Err(Status::invalid_argument("Ring too large"));
+        }
+
+        let ring = {
+            let mut api = self.api.lock().await;
+            req.ring
+                .iter()
+                .map(|username| api.read(username).map(|user| user.y1))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| Status::not_found("User not found"))?
+        };
+
+        if !signature.verify(&self.params, &ring, req.topic.as_bytes(), &req.payload) {
+            return Err(Status::permission_denied("Invalid signature"));
+        }
+        let key_image = T::to(&signature.key_image);
+        if !self
+            .api
+            .lock()
+            .await
+            .record_key_image(&req.topic, &key_image)
+        {
+            return Err(Status::already_exists(
+                "Key image already used on this topic",
+            ));
+        }
+        Ok(Response::new(RingSignatureResponse { key_image }))
+    }
+
+    async fn migrate_user(
+        &self,
+        request: Request<MigrateUserRequest>,
+    ) -> Result<Response<MigrateUserResponse>, Status> {
+        let req = request.into_inner();
+
+        let target = self
+            .migration
+            .as_ref()
+            .ok_or_else(|| Status::unimplemented("Migration not supported"))?;
+
+        // the nonce is used up whether the proof verifies or not
+        let user = {
+            let mut api = self.api.lock().await;
+            if api.take_nonce(&req.nonce).as_deref() != Some(req.user.as_str()) {
+                return Err(Status::permission_denied("Invalid nonce"));
+            }
+            api.read(&req.user)
+                .ok_or_else(|| Status::not_found("User not found"))?
+        };
+
+        target.import(&self.params, &self.api, &user, &req).await?;
+        Ok(Response::new(MigrateUserResponse {}))
+    }
+
+    async fn rekey_user(
+        &self,
+        request: Request<RekeyUserRequest>,
+    ) -> Result<Response<RekeyUserResponse>, Status> {
+        let req = request.into_inner();
+
+        let target = self
+            .migration
+            .as_ref()
+            .ok_or_else(|| Status::unimplemented("Migration not supported"))?;
+
+        // the nonce is used up whether the proof verifies or not
+        let user = {
+            let mut api = self.api.lock().await;
+            if api.take_nonce(&req.nonce).as_deref() != Some(req.user.as_str()) {
+                return Err(Status::permission_denied("Invalid nonce"));
+            }
+            api.read(&req.user)
+                .ok_or_else(|| Status::not_found("User not found"))?
+        };
+
+        target.rekey(&self.params, &self.api, &user, &req).await?;
+        Ok(Response::new(RekeyUserResponse {}))
+    }
+
+    async fn get_parallel_config(
+        &self,
+        _: Request<ParallelConfigRequest>,
+    ) -> Result<Response<ParallelConfigResponse>, Status> {
+        let config = self
+            .parallel
+            .ok_or_else(|| Status::unimplemented("Parallel mode not enabled"))?;
+        let reply = ParallelConfigResponse {
+            challenge_bits: config.challenge_bits,
+            rounds: config.rounds as u64,
+            soundness_error: config.soundness_error(),
+        };
+        Ok(Response::new(reply))
+    }
+
+    async fn create_parallel_challenge(
+        &self,
+        request: Request<ParallelChallengeRequest>,
+    ) -> Result<Response<ParallelChallengeResponse>, Status> {
+        let req = request.into_inner();
+        let config = self
+            .parallel
+            .ok_or_else(|| Status::unimplemented("Parallel mode not enabled"))?;
+
+        if req.r1.len() != config.rounds || req.r2.len() != config.rounds {
+            return Err(Status::invalid_argument("Invalid number of rounds"));
+        }
+        let commitments = req
+            .r1
+            .iter()
+            .zip(&req.r2)
+            .map(|(r1, r2)| Ok((T::from(r1)?, T::from(r2)?)))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map_err(|_| Status::invalid_argument("Invalid commitment"))?;
+        let challenges = config.challenges();
+
+        let auth_id = {
+            let mut api = self.api.lock().await;
+            api.read(&req.user)
+                .ok_or_else(|| Status::not_found("User not found"))?;
+            api.create_parallel_challenge(&req.user, commitments, challenges.clone())
+                .ok_or_else(|| Status::resource_exhausted("Too many pending challenges"))?
+        };
+
+        let reply = ParallelChallengeResponse {
+            auth_id,
+            c: challenges,
+            soundness_error: config.soundness_error(),
+        };
+        Ok(Response::new(reply))
+    }
+
+    async fn verify_parallel_authentication(
+        &self,
+        request: Request<ParallelAnswerRequest>,
+    ) -> Result<Response<AuthenticationAnswerResponse>, Status> {
+        let req = request.into_inner();
+        let config = self
+            .parallel
+            .ok_or_else(|| Status::unimplemented("Parallel mode not enabled"))?;
+
+        // every challenge is answered at most once, the rounds are checked without the lock
+        let (challenge, user) = {
+            let mut api = self.api.lock().await;
+            let challenge = api
+                .take_parallel_challenge(&req.auth_id)
+                .ok_or_else(|| Status::not_found("Challenge not found"))?;
+            let user = api
+                .read(&challenge.user)
+                .ok_or_else(|| Status::not_found("User not found"))?;
+            (challenge, user)
+        };
+
+        let responses = req
+            .s
+            .iter()
+            .map(|s| S::from(s))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map_err(|_| Status::invalid_argument("Invalid s"))?;
+        let commitments: Vec<RoundCommitment<C>> = challenge
+            .commitments
+            .into_iter()
+            .map(|(r1, r2)| RoundCommitment { r1, r2 })
+            .collect();
+
+        let verified = parallel::verify::<C>(
+            &self.params,
+            &config,
+            (&user.y1, &user.y2),
+            &commitments,
+            &challenge.c,
+            &responses,
+        );
+        if !verified {
+            return Err(Status::invalid_argument("Invalid authentication"));
+        }
+        let session_id = Uuid::new_v4().to_string();
+        Ok(Response::new(AuthenticationAnswerResponse { session_id }))
+    }
 }
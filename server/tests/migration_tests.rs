@@ -0,0 +1,404 @@
+use chaum_pedersen::enums::KdfAlgorithm;
+use chaum_pedersen::protocol::constants::{PALLAS_GROUP_PARAMS, VESTA_GROUP_PARAMS};
+use chaum_pedersen::protocol::contexts;
+use chaum_pedersen::protocol::cross_group::CrossGroupProof;
+use chaum_pedersen::protocol::dleq::{DleqProof, DleqStatement};
+use chaum_pedersen::protocol::elliptic_curves::pallas::PallasEllipticCurve;
+use chaum_pedersen::protocol::elliptic_curves::vesta::VestaEllipticCurve;
+use chaum_pedersen::protocol::group::CyclicGroup;
+use chaum_pedersen::protocol::kdf::KdfParams;
+use chaum_pedersen::protocol::rekey::RekeyProof;
+use chaum_pedersen::protocol::secret::Secret;
+use chaum_pedersen::protocol::{GroupParams, Protocol};
+use chaum_pedersen::traits::{FromBytes, IntoBytes};
+use server::service::zkp_auth::{
+    self, auth_server::Auth, AuthenticationAnswerRequest, AuthenticationChallengeRequest,
+    KdfParamsRequest, MigrateUserRequest, NonceRequest, RegisterRequest, RekeyUserRequest,
+};
+use server::service::AuthService;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use tonic::{Code, Request};
+
+/// Logs the user in with the secret `x`, registered beforehand.
+async fn login<G>(
+    service: &AuthService<G, G::Element, G::Scalar>,
+    params: &GroupParams<G::Element>,
+    x: &Secret<G::Scalar>,
+    user: &str,
+) -> Result<(), tonic::Status>
+where
+    G: CyclicGroup
+        + Protocol<
+            Secret = Secret<G::Scalar>,
+            GroupParameters = GroupParams<G::Element>,
+            CommitParameters = (G::Element, G::Element, G::Element, G::Element),
+            Response = G::Scalar,
+            Challenge = G::Scalar,
+        > + Send
+        + Sync
+        + 'static,
+    G::Element: Send + Sync + Clone + FromBytes<G::Element> + IntoBytes<G::Element> + 'static,
+    G::Scalar: Send + Sync + Clone + FromBytes<G::Scalar> + IntoBytes<G::Scalar> + 'static,
+{
+    let ((_, _, r1, r2), k) = G::commitment(params, x);
+    let challenge = service
+        .create_authentication_challenge(Request::new(AuthenticationChallengeRequest {
+            user: user.to_string(),
+            r1: G::Element::to(&r1),
+            r2: G::Element::to(&r2),
+        }))
+        .await?
+        .into_inner();
+    let c = <G::Scalar as FromBytes<G::Scalar>>::from(&challenge.c).unwrap();
+    let s = G::challenge_response(params, &k, &c, x);
+    service
+        .verify_authentication(Request::new(AuthenticationAnswerRequest {
+            auth_id: challenge.auth_id,
+            s: G::Scalar::to(&s),
+        }))
+        .await?;
+    Ok(())
+}
+
+/// Registers the user with the secret `x`.
+async fn register<G>(
+    service: &AuthService<G, G::Element, G::Scalar>,
+    params: &GroupParams<G::Element>,
+    x: &Secret<G::Scalar>,
+    user: &str,
+) where
+    G: CyclicGroup
+        + Protocol<
+            Secret = Secret<G::Scalar>,
+            GroupParameters = GroupParams<G::Element>,
+            CommitParameters = (G::Element, G::Element, G::Element, G::Element),
+            Response = G::Scalar,
+            Challenge = G::Scalar,
+        > + Send
+        + Sync
+        + 'static,
+    G::Element: Send + Sync + Clone + FromBytes<G::Element> + IntoBytes<G::Element> + 'static,
+    G::Scalar: Send + Sync + Clone + FromBytes<G::Scalar> + IntoBytes<G::Scalar> + 'static,
+{
+    service
+        .register(Request::new(RegisterRequest {
+            user: user.to_string(),
+            y1: G::Element::to(&G::exp(params, &params.g, x.expose())),
+            y2: G::Element::to(&G::exp(params, &params.h, x.expose())),
+            kdf: None,
+        }))
+        .await
+        .unwrap();
+}
+
+/// A secret of `G1` that can be kept on `G2`, with its value there.
+fn migratable_secret<G1: CyclicGroup, G2: CyclicGroup>(
+    params1: &GroupParams<G1::Element>,
+    params2: &GroupParams<G2::Element>,
+) -> (Secret<G1::Scalar>, Secret<G2::Scalar>) {
+    loop {
+        let x = G1::random_scalar(params1);
+        if let Some(x2) = CrossGroupProof::<G1, G2>::convert_secret(params1, params2, &x) {
+            return (Secret::new(x), Secret::new(x2));
+        }
+    }
+}
+
+/// The request migrating the user of `x` to `G2`, under a nonce of the `source`.
+async fn migration_request<G1, G2>(
+    source: &AuthService<G1, G1::Element, G1::Scalar>,
+    params1: &GroupParams<G1::Element>,
+    params2: &GroupParams<G2::Element>,
+    x: &Secret<G1::Scalar>,
+    x2: &Secret<G2::Scalar>,
+    user: &str,
+) -> MigrateUserRequest
+where
+    G1: CyclicGroup
+        + Protocol<
+            Secret = Secret<G1::Scalar>,
+            GroupParameters = GroupParams<G1::Element>,
+            CommitParameters = (G1::Element, G1::Element, G1::Element, G1::Element),
+            Response = G1::Scalar,
+            Challenge = G1::Scalar,
+        > + Send
+        + Sync
+        + 'static,
+    G1::Element: Send + Sync + Clone + FromBytes<G1::Element> + IntoBytes<G1::Element> + 'static,
+    G1::Scalar: Send + Sync + Clone + FromBytes<G1::Scalar> + IntoBytes<G1::Scalar> + 'static,
+    G2: CyclicGroup
+        + Protocol<
+            Secret = Secret<G2::Scalar>,
+            GroupParameters = GroupParams<G2::Element>,
+            CommitParameters = (G2::Element, G2::Element, G2::Element, G2::Element),
+            Response = G2::Scalar,
+            Challenge = G2::Scalar,
+        > + Send
+        + Sync
+        + 'static,
+    G2::Element: Send + Sync + Clone + FromBytes<G2::Element> + IntoBytes<G2::Element> + 'static,
+    G2::Scalar: Send + Sync + Clone + FromBytes<G2::Scalar> + IntoBytes<G2::Scalar> + 'static,
+{
+    let nonce = source
+        .create_nonce(Request::new(NonceRequest {
+            user: user.to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .nonce;
+    let statement = DleqStatement::<G2> {
+        g1: params2.g.clone(),
+        y1: G2::exp(params2, &params2.g, x2.expose()),
+        g2: params2.h.clone(),
+        y2: G2::exp(params2, &params2.h, x2.expose()),
+    };
+    let mut transcript = contexts::migration(user, &nonce);
+    let proof =
+        CrossGroupProof::<G1, G2>::prove(params1, params2, &mut transcript, x.expose()).unwrap();
+    let dleq_proof = DleqProof::prove(params2, &mut transcript, &statement, x2.expose());
+    MigrateUserRequest {
+        user: user.to_string(),
+        y1: G2::Element::to(&statement.y1),
+        y2: G2::Element::to(&statement.y2),
+        proof: CrossGroupProof::to(&proof),
+        dleq_proof: DleqProof::to(&dleq_proof),
+        nonce,
+    }
+}
+
+async fn run_migration<G1, G2>(params1: GroupParams<G1::Element>, params2: GroupParams<G2::Element>)
+where
+    G1: CyclicGroup
+        + Protocol<
+            Secret = Secret<G1::Scalar>,
+            GroupParameters = GroupParams<G1::Element>,
+            CommitParameters = (G1::Element, G1::Element, G1::Element, G1::Element),
+            Response = G1::Scalar,
+            Challenge = G1::Scalar,
+        > + Send
+        + Sync
+        + 'static,
+    G1::Element: Send + Sync + Clone + FromBytes<G1::Element> + IntoBytes<G1::Element> + 'static,
+    G1::Scalar: Send + Sync + Clone + FromBytes<G1::Scalar> + IntoBytes<G1::Scalar> + 'static,
+    G2: CyclicGroup
+        + Protocol<
+            Secret = Secret<G2::Scalar>,
+            GroupParameters = GroupParams<G2::Element>,
+            CommitParameters = (G2::Element, G2::Element, G2::Element, G2::Element),
+            Response = G2::Scalar,
+            Challenge = G2::Scalar,
+        > + Send
+        + Sync
+        + 'static,
+    G2::Element: Send + Sync + Clone + FromBytes<G2::Element> + IntoBytes<G2::Element> + 'static,
+    G2::Scalar: Send + Sync + Clone + FromBytes<G2::Scalar> + IntoBytes<G2::Scalar> + 'static,
+{
+    let target = AuthService::<G2, G2::Element, G2::Scalar>::new(params2.clone());
+    let source = AuthService::<G1, G1::Element, G1::Scalar>::new(params1.clone())
+        .with_migration::<G2>(params2.clone(), target.users());
+
+    let user = "peggy";
+    let (x1, x2) = migratable_secret::<G1, G2>(&params1, &params2);
+    register::<G1>(&source, &params1, &x1, user).await;
+    login::<G1>(&source, &params1, &x1, user).await.unwrap();
+
+    let request = migration_request::<G1, G2>(&source, &params1, &params2, &x1, &x2, user).await;
+    source
+        .migrate_user(Request::new(request.clone()))
+        .await
+        .unwrap();
+
+    // the nonce is spent, so the request cannot be replayed
+    let replay = source
+        .migrate_user(Request::new(request))
+        .await
+        .unwrap_err();
+    assert_eq!(replay.code(), Code::PermissionDenied);
+
+    // the registration moved over, the same secret logging in on the other group
+    let old = login::<G1>(&source, &params1, &x1, user).await.unwrap_err();
+    assert_eq!(old.code(), Code::NotFound);
+    login::<G2>(&target, &params2, &x2, user).await.unwrap();
+
+    // a user of the same name on the other group is not overwritten
+    let (x1, x2) = migratable_secret::<G1, G2>(&params1, &params2);
+    register::<G1>(&source, &params1, &x1, user).await;
+    let request = migration_request::<G1, G2>(&source, &params1, &params2, &x1, &x2, user).await;
+    let taken = source
+        .migrate_user(Request::new(request))
+        .await
+        .unwrap_err();
+    assert_eq!(taken.code(), Code::AlreadyExists);
+    login::<G1>(&source, &params1, &x1, user).await.unwrap();
+    login::<G2>(&target, &params2, &x2, user).await.unwrap_err();
+}
+
+#[tokio::test]
+async fn pallas_user_logs_in_on_vesta_after_migrating() {
+    run_migration::<PallasEllipticCurve, VestaEllipticCurve>(
+        PALLAS_GROUP_PARAMS.to_owned(),
+        VESTA_GROUP_PARAMS.to_owned(),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn vesta_user_logs_in_on_pallas_after_migrating() {
+    run_migration::<VestaEllipticCurve, PallasEllipticCurve>(
+        VESTA_GROUP_PARAMS.to_owned(),
+        PALLAS_GROUP_PARAMS.to_owned(),
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migrations_in_both_directions_do_not_block_each_other() {
+    let (pallas, vesta) = (
+        PALLAS_GROUP_PARAMS.to_owned(),
+        VESTA_GROUP_PARAMS.to_owned(),
+    );
+    let pallas_service = AuthService::<PallasEllipticCurve, _, _>::new(pallas);
+    let vesta_service = AuthService::<VestaEllipticCurve, _, _>::new(vesta);
+    let (pallas_users, vesta_users) = (pallas_service.users(), vesta_service.users());
+    let pallas_service = pallas_service.with_migration::<VestaEllipticCurve>(vesta, vesta_users);
+    let vesta_service = vesta_service.with_migration::<PallasEllipticCurve>(pallas, pallas_users);
+
+    let mut requests = (Vec::new(), Vec::new());
+    for i in 0..2 {
+        let user = format!("peggy-{}", i);
+        let (x, x2) = migratable_secret::<PallasEllipticCurve, VestaEllipticCurve>(&pallas, &vesta);
+        register::<PallasEllipticCurve>(&pallas_service, &pallas, &x, &user).await;
+        requests.0.push(
+            migration_request::<PallasEllipticCurve, VestaEllipticCurve>(
+                &pallas_service,
+                &pallas,
+                &vesta,
+                &x,
+                &x2,
+                &user,
+            )
+            .await,
+        );
+        let user = format!("victor-{}", i);
+        let (x, x2) = migratable_secret::<VestaEllipticCurve, PallasEllipticCurve>(&vesta, &pallas);
+        register::<VestaEllipticCurve>(&vesta_service, &vesta, &x, &user).await;
+        requests.1.push(
+            migration_request::<VestaEllipticCurve, PallasEllipticCurve>(
+                &vesta_service,
+                &vesta,
+                &pallas,
+                &x,
+                &x2,
+                &user,
+            )
+            .await,
+        );
+    }
+
+    let (pallas_service, vesta_service) = (Arc::new(pallas_service), Arc::new(vesta_service));
+    let mut migrations = JoinSet::new();
+    for (to_vesta, to_pallas) in requests.0.into_iter().zip(requests.1) {
+        let service = pallas_service.clone();
+        migrations.spawn(async move { service.migrate_user(Request::new(to_vesta)).await });
+        let service = vesta_service.clone();
+        migrations.spawn(async move { service.migrate_user(Request::new(to_pallas)).await });
+    }
+    while let Some(migration) = migrations.join_next().await {
+        migration.unwrap().unwrap();
+    }
+}
+
+#[tokio::test]
+async fn rekeyed_user_logs_in_with_a_fresh_secret() {
+    let (pallas, vesta) = (
+        PALLAS_GROUP_PARAMS.to_owned(),
+        VESTA_GROUP_PARAMS.to_owned(),
+    );
+    let target = AuthService::<VestaEllipticCurve, _, _>::new(vesta);
+    let source = AuthService::<PallasEllipticCurve, _, _>::new(pallas)
+        .with_migration::<VestaEllipticCurve>(vesta, target.users());
+
+    // any secret can be re-keyed, and the parameters of the password move along
+    let user = "peggy";
+    let kdf = KdfParams::generate(KdfAlgorithm::Argon2id);
+    let x = Secret::new(PallasEllipticCurve::random_scalar(&pallas));
+    source
+        .register(Request::new(RegisterRequest {
+            user: user.to_string(),
+            y1: <PallasEllipticCurve as CyclicGroup>::Element::to(&PallasEllipticCurve::exp(
+                &pallas,
+                &pallas.g,
+                x.expose(),
+            )),
+            y2: <PallasEllipticCurve as CyclicGroup>::Element::to(&PallasEllipticCurve::exp(
+                &pallas,
+                &pallas.h,
+                x.expose(),
+            )),
+            kdf: Some(zkp_auth::KdfParams {
+                algorithm: kdf.algorithm.to_string(),
+                salt: kdf.salt.clone(),
+                memory_cost: kdf.memory_cost,
+                time_cost: kdf.time_cost,
+                parallelism: kdf.parallelism,
+            }),
+        }))
+        .await
+        .unwrap();
+
+    let x2 = Secret::new(VestaEllipticCurve::random_scalar(&vesta));
+    let nonce = source
+        .create_nonce(Request::new(NonceRequest {
+            user: user.to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .nonce;
+    let context = contexts::rekey(user, &nonce);
+    let proof = RekeyProof::<PallasEllipticCurve, VestaEllipticCurve>::prove(
+        &pallas,
+        &vesta,
+        &context,
+        x.expose(),
+        x2.expose(),
+    );
+    let request = RekeyUserRequest {
+        user: user.to_string(),
+        y1: <VestaEllipticCurve as CyclicGroup>::Element::to(&VestaEllipticCurve::exp(
+            &vesta,
+            &vesta.g,
+            x2.expose(),
+        )),
+        y2: <VestaEllipticCurve as CyclicGroup>::Element::to(&VestaEllipticCurve::exp(
+            &vesta,
+            &vesta.h,
+            x2.expose(),
+        )),
+        proof: RekeyProof::to(&proof),
+        nonce,
+    };
+    source
+        .rekey_user(Request::new(request.clone()))
+        .await
+        .unwrap();
+    let replay = source.rekey_user(Request::new(request)).await.unwrap_err();
+    assert_eq!(replay.code(), Code::PermissionDenied);
+
+    login::<PallasEllipticCurve>(&source, &pallas, &x, user)
+        .await
+        .unwrap_err();
+    login::<VestaEllipticCurve>(&target, &vesta, &x2, user)
+        .await
+        .unwrap();
+    let rekeyed = target
+        .get_kdf_params(Request::new(KdfParamsRequest {
+            user: user.to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(rekeyed.salt, kdf.salt);
+}